@@ -3,12 +3,14 @@
 use crate::util::{check_builtin_macro_attribute, warn_on_duplicate_attribute};
 use rustc_ast as ast;
 use rustc_ast::ptr::P;
+use rustc_ast::token;
 use rustc_ast_pretty::pprust;
-use rustc_errors::Applicability;
+use rustc_errors::{pluralize, Applicability, PResult};
 use rustc_expand::base::*;
+use rustc_session::parse::feature_err;
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Ident, Symbol};
-use rustc_span::Span;
+use rustc_span::{FileNameDisplayPreference, Span};
 use std::iter;
 use thin_vec::{thin_vec, ThinVec};
 
@@ -133,15 +135,75 @@ pub fn expand_test_or_bench(
     if (!is_bench && !has_test_signature(cx, &item))
         || (is_bench && !has_bench_signature(cx, &item))
     {
-        return vec![Annotatable::Item(item)];
+        return vec![Annotatable::Item(strip_test_params(item))];
     }
 
     let sp = cx.with_def_site_ctxt(item.span);
-    let ret_ty_sp = cx.with_def_site_ctxt(fn_.sig.decl.output.span());
     let attr_sp = cx.with_def_site_ctxt(attr_sp);
 
     let test_id = Ident::new(sym::test, attr_sp);
 
+    let test_path_symbol = Symbol::intern(&item_path(
+        // skip the name of the root module
+        &cx.current_expansion.module.mod_path[1..],
+        &item.ident,
+    ));
+
+    // Every `#[test_params]` attribute is a test of its own, named after its case.
+    let test_consts: Vec<_> = match test_params(cx, &item, fn_, is_bench) {
+        Some(cases) if !cases.is_empty() => cases
+            .into_iter()
+            .map(|(name, args)| {
+                let path = Symbol::intern(&format!("{test_path_symbol}::{name}"));
+                // Like the const of a test without cases, this has a def-site span, so that it
+                // can't clash with a user's item of the same name, or with the const of another
+                // test whose name and case happen to join to the same string.
+                let const_ident = Ident::from_str_and_span(&format!("{}__{name}", item.ident), sp);
+                test_const(cx, attr_sp, &item, fn_, is_bench, const_ident, path, args)
+            })
+            .collect(),
+        Some(_) => {
+            let args = if is_bench { ThinVec::new() } else { fixture_args(cx, sp, test_id, fn_) };
+            let const_ident = Ident::new(item.ident.name, sp);
+            vec![test_const(cx, attr_sp, &item, fn_, is_bench, const_ident, test_path_symbol, args)]
+        }
+        None => return vec![Annotatable::Item(strip_test_params(item))],
+    };
+
+    // extern crate test
+    let test_extern = cx.item(sp, test_id, ast::AttrVec::new(), ast::ItemKind::ExternCrate(None));
+
+    // Access to libtest under a hygienic name
+    let mut items = vec![test_extern];
+    // The generated test cases
+    items.extend(test_consts);
+    // The original item
+    items.push(strip_test_params(item));
+
+    if is_stmt {
+        items.into_iter().map(|item| Annotatable::Stmt(P(cx.stmt_item(sp, item)))).collect()
+    } else {
+        items.into_iter().map(Annotatable::Item).collect()
+    }
+}
+
+/// Builds the `const` holding the `TestDescAndFn` of one test, which calls the test function
+/// with `args`.
+fn test_const(
+    cx: &ExtCtxt<'_>,
+    attr_sp: Span,
+    item: &ast::Item,
+    fn_: &ast::Fn,
+    is_bench: bool,
+    const_ident: Ident,
+    test_path_symbol: Symbol,
+    args: ThinVec<P<ast::Expr>>,
+) -> P<ast::Item> {
+    let sp = cx.with_def_site_ctxt(item.span);
+    let ret_ty_sp = cx.with_def_site_ctxt(fn_.sig.decl.output.span());
+
+    let test_id = Ident::new(sym::test, attr_sp);
+
     // creates test::$name
     let test_path = |name| cx.path(ret_ty_sp, vec![test_id, Ident::from_str_and_span(name, sp)]);
 
@@ -172,6 +234,8 @@ pub fn expand_test_or_bench(
     // creates $name: $expr
     let field = |name, expr| cx.field_imm(sp, Ident::from_str_and_span(name, sp), expr);
 
+    let location_info = get_location_info(cx, item);
+
     let test_fn = if is_bench {
         // A simple ident for a lambda
         let b = Ident::from_str_and_span("b", attr_sp);
@@ -212,11 +276,11 @@ pub fn expand_test_or_bench(
                         sp,
                         cx.expr_path(test_path("assert_test_result")),
                         thin_vec![
-                            // $test_fn()
+                            // $test_fn($args)
                             cx.expr_call(
                                 ret_ty_sp,
                                 cx.expr_path(cx.path(sp, vec![item.ident])),
-                                ThinVec::new(),
+                                args,
                             ), // )
                         ],
                     ), // }
@@ -225,15 +289,9 @@ pub fn expand_test_or_bench(
         )
     };
 
-    let test_path_symbol = Symbol::intern(&item_path(
-        // skip the name of the root module
-        &cx.current_expansion.module.mod_path[1..],
-        &item.ident,
-    ));
-
     let mut test_const = cx.item(
         sp,
-        Ident::new(item.ident.name, sp),
+        const_ident,
         thin_vec![
             // #[cfg(test)]
             cx.attr_nested_word(sym::cfg, sym::test, attr_sp),
@@ -269,17 +327,27 @@ pub fn expand_test_or_bench(
                                     // ignore: true | false
                                     field(
                                         "ignore",
-                                        cx.expr_bool(sp, should_ignore(&cx.sess, &item)),
+                                        cx.expr_bool(sp, should_ignore(&cx.sess, item)),
                                     ),
                                     // ignore_message: Some("...") | None
                                     field(
                                         "ignore_message",
-                                        if let Some(msg) = should_ignore_message(cx, &item) {
+                                        if let Some(msg) = should_ignore_message(cx, item) {
                                             cx.expr_some(sp, cx.expr_str(sp, msg))
                                         } else {
                                             cx.expr_none(sp)
                                         },
                                     ),
+                                    // source_file: <relative_path_of_source_file>
+                                    field("source_file", cx.expr_str(sp, location_info.0)),
+                                    // start_line: start line of the test fn identifier.
+                                    field("start_line", cx.expr_usize(sp, location_info.1)),
+                                    // start_col: start column of the test fn identifier.
+                                    field("start_col", cx.expr_usize(sp, location_info.2)),
+                                    // end_line: end line of the test fn identifier.
+                                    field("end_line", cx.expr_usize(sp, location_info.3)),
+                                    // end_col: end column of the test fn identifier.
+                                    field("end_col", cx.expr_usize(sp, location_info.4)),
                                     // compile_fail: true | false
                                     field("compile_fail", cx.expr_bool(sp, false)),
                                     // no_run: true | false
@@ -287,7 +355,7 @@ pub fn expand_test_or_bench(
                                     // should_panic: ...
                                     field(
                                         "should_panic",
-                                        match should_panic(cx, &item) {
+                                        match should_panic(cx, item) {
                                             // test::ShouldPanic::No
                                             ShouldPanic::No => {
                                                 cx.expr_path(should_panic_path("No"))
@@ -322,6 +390,24 @@ pub fn expand_test_or_bench(
                                             }
                                         },
                                     ),
+                                    // timeout: Some(test::parse_test_timeout("...")) | None
+                                    field(
+                                        "timeout",
+                                        if let Some((timeout_sp, timeout)) = test_timeout(cx, item)
+                                        {
+                                            let timeout_sp = cx.with_def_site_ctxt(timeout_sp);
+                                            cx.expr_some(
+                                                sp,
+                                                cx.expr_call(
+                                                    timeout_sp,
+                                                    cx.expr_path(test_path("parse_test_timeout")),
+                                                    thin_vec![cx.expr_str(sp, timeout)],
+                                                ),
+                                            )
+                                        } else {
+                                            cx.expr_none(sp)
+                                        },
+                                    ),
                                     // },
                                 ],
                             ),
@@ -337,31 +423,138 @@ pub fn expand_test_or_bench(
         tc.vis.kind = ast::VisibilityKind::Public;
         tc
     });
+    debug!("synthetic test item:\n{}\n", pprust::item_to_string(&test_const));
+    test_const
+}
 
-    // extern crate test
-    let test_extern = cx.item(sp, test_id, ast::AttrVec::new(), ast::ItemKind::ExternCrate(None));
+/// Parses the `#[test_params(case_name, args...)]` attributes of a test function into
+/// the name and the arguments of each case. Returns `None` if any of them is invalid.
+fn test_params(
+    cx: &ExtCtxt<'_>,
+    i: &ast::Item,
+    fn_: &ast::Fn,
+    is_bench: bool,
+) -> Option<Vec<(Ident, ThinVec<P<ast::Expr>>)>> {
+    let sd = &cx.sess.parse_sess.span_diagnostic;
+    let mut cases: Vec<(Ident, ThinVec<P<ast::Expr>>)> = Vec::new();
+    let mut valid = true;
+    for attr in i.attrs.iter().filter(|attr| attr.has_name(sym::test_params)) {
+        if is_bench {
+            sd.span_err(attr.span, "`#[test_params]` can only be used on `#[test]` functions");
+            return None;
+        }
+        let (name, args) = match parse_test_params(cx, attr) {
+            Ok(case) => case,
+            Err(mut err) => {
+                err.emit();
+                valid = false;
+                continue;
+            }
+        };
+        let inputs = fn_.sig.decl.inputs.len();
+        if let Some((first, _)) = cases.iter().find(|(other, _)| other.name == name.name) {
+            sd.struct_span_err(name.span, format!("duplicate test case `{name}`"))
+                .span_label(first.span, "first defined here")
+                .emit();
+            valid = false;
+        } else if args.len() != inputs {
+            let mut err = sd.struct_span_err(
+                attr.span,
+                format!(
+                    "test case `{name}` has {} argument{}, but `{}` takes {inputs}",
+                    args.len(),
+                    pluralize!(args.len()),
+                    i.ident,
+                ),
+            );
+            if args.len() < inputs {
+                err.note(
+                    "every argument of a parameterized test comes from its cases, \
+                     so it can not take fixtures",
+                );
+            }
+            err.emit();
+            valid = false;
+        } else {
+            cases.push((name, args));
+        }
+    }
+    valid.then_some(cases)
+}
 
-    debug!("synthetic test item:\n{}\n", pprust::item_to_string(&test_const));
+/// Removes the `#[test_params]` attributes handled by `#[test]` from the test function.
+/// Any that are left are reported by the attribute checks.
+fn strip_test_params(mut item: P<ast::Item>) -> P<ast::Item> {
+    item.attrs.retain(|attr| !attr.has_name(sym::test_params));
+    item
+}
 
-    if is_stmt {
-        vec![
-            // Access to libtest under a hygienic name
-            Annotatable::Stmt(P(cx.stmt_item(sp, test_extern))),
-            // The generated test case
-            Annotatable::Stmt(P(cx.stmt_item(sp, test_const))),
-            // The original item
-            Annotatable::Stmt(P(cx.stmt_item(sp, item))),
-        ]
-    } else {
-        vec![
-            // Access to libtest under a hygienic name
-            Annotatable::Item(test_extern),
-            // The generated test case
-            Annotatable::Item(test_const),
-            // The original item
-            Annotatable::Item(item),
-        ]
+fn parse_test_params<'a>(
+    cx: &ExtCtxt<'a>,
+    attr: &ast::Attribute,
+) -> PResult<'a, (Ident, ThinVec<P<ast::Expr>>)> {
+    let mut p = cx.new_parser_from_tts(attr.get_normal_item().args.inner_tokens());
+    let name = p.parse_ident()?;
+    let mut args = ThinVec::new();
+    while p.token != token::Eof {
+        p.expect(&token::Comma)?;
+        // Allow a trailing comma.
+        if p.token == token::Eof {
+            break;
+        }
+        args.push(p.parse_expr()?);
     }
+    Ok((name, args))
+}
+
+/// Builds the arguments of a call to a test function that takes fixtures. Each argument
+/// is a per-test fixture, or a borrow of a suite fixture if the parameter is a `&T`.
+fn fixture_args(
+    cx: &ExtCtxt<'_>,
+    sp: Span,
+    test_id: Ident,
+    fn_: &ast::Fn,
+) -> ThinVec<P<ast::Expr>> {
+    // test::fixture::$name::<$ty>()
+    let fixture = |name, ty| {
+        let path = cx.path_all(
+            sp,
+            false,
+            vec![
+                test_id,
+                Ident::from_str_and_span("fixture", sp),
+                Ident::from_str_and_span(name, sp),
+            ],
+            vec![ast::GenericArg::Type(ty)],
+        );
+        cx.expr_call(sp, cx.expr_path(path), ThinVec::new())
+    };
+
+    fn_.sig
+        .decl
+        .inputs
+        .iter()
+        .map(|param| match &param.ty.kind {
+            // &*test::fixture::suite::<$ty>(), the returned `Arc` lives until the end of the call
+            ast::TyKind::Ref(_, ast::MutTy { ty, mutbl: ast::Mutability::Not }) => {
+                cx.expr_addr_of(sp, cx.expr_deref(sp, fixture("suite", ty.clone())))
+            }
+            _ => fixture("setup", param.ty.clone()),
+        })
+        .collect()
+}
+
+fn get_location_info(cx: &ExtCtxt<'_>, item: &ast::Item) -> (Symbol, usize, usize, usize, usize) {
+    let span = item.ident.span;
+    let (source_file, lo_line, lo_col, hi_line, hi_col) =
+        cx.sess.source_map().span_to_location_info(span);
+
+    let file_name = match source_file {
+        Some(sf) => sf.name.display(FileNameDisplayPreference::Remapped).to_string(),
+        None => "no-location".to_string(),
+    };
+
+    (Symbol::intern(&file_name), lo_line, lo_col, hi_line, hi_col)
 }
 
 fn item_path(mod_path: &[Ident], item_ident: &Ident) -> String {
@@ -434,6 +627,15 @@ fn should_panic(cx: &ExtCtxt<'_>, i: &ast::Item) -> ShouldPanic {
     }
 }
 
+/// Returns the span and value of the `#[test_timeout]` attribute. The value is parsed by
+/// libtest's `parse_test_timeout` while evaluating the test's descriptor, so that the
+/// attribute and `--test-timeout` share one parser.
+fn test_timeout(cx: &ExtCtxt<'_>, i: &ast::Item) -> Option<(Span, Symbol)> {
+    let attr = cx.sess.find_by_name(&i.attrs, sym::test_timeout)?;
+    // Malformed input (e.g. `#[test_timeout]`) is reported by the builtin attribute checks.
+    Some((attr.span, attr.value_str()?))
+}
+
 enum TestType {
     UnitTest,
     IntegrationTest,
@@ -488,8 +690,16 @@ fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
                 _ => true,
             };
 
-            if !sig.decl.inputs.is_empty() {
-                sd.span_err(i.span, "functions used as tests can not have any arguments");
+            // The arguments of a parameterized test are checked when its cases are parsed.
+            let has_params = cx.sess.contains_name(&i.attrs, sym::test_params);
+            if has_params && !generics.params.is_empty() {
+                sd.span_err(generics.span, "functions used as tests can not be generic");
+                return false;
+            }
+            if !sig.decl.inputs.is_empty()
+                && !has_params
+                && !has_fixture_signature(cx, i, sig, generics)
+            {
                 return false;
             }
 
@@ -520,6 +730,54 @@ fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
     }
 }
 
+/// Checks the arguments of a test function that takes fixtures, which are set up by
+/// `test::fixture::setup` (for owned types) or `test::fixture::suite` (for `&T`).
+fn has_fixture_signature(
+    cx: &ExtCtxt<'_>,
+    i: &ast::Item,
+    sig: &ast::FnSig,
+    generics: &ast::Generics,
+) -> bool {
+    let sd = &cx.sess.parse_sess.span_diagnostic;
+    if !cx.ecfg.features.map_or(false, |features| features.test_fixtures) {
+        feature_err(
+            &cx.sess.parse_sess,
+            sym::test_fixtures,
+            i.span,
+            "fixture arguments of `#[test]` functions are experimental",
+        )
+        .emit();
+        return false;
+    }
+
+    let mut ok = true;
+    if !generics.params.is_empty() {
+        sd.span_err(generics.span, "functions used as tests can not be generic");
+        ok = false;
+    }
+    if cx.sess.contains_name(&i.attrs, sym::should_panic) {
+        // A fixture that fails to set up panics, which would make the test pass.
+        sd.span_err(i.span, "functions using `#[should_panic]` can not take fixtures");
+        ok = false;
+    }
+    for param in &sig.decl.inputs {
+        match &param.ty.kind {
+            ast::TyKind::Ref(_, ast::MutTy { mutbl: ast::Mutability::Mut, .. }) => {
+                sd.struct_span_err(param.ty.span, "suite fixtures can not be borrowed mutably")
+                    .help("suite fixtures are shared by all tests, use `&T` or take `T` by value")
+                    .emit();
+                ok = false;
+            }
+            ast::TyKind::ImplTrait(..) => {
+                sd.span_err(param.ty.span, "fixtures must have a concrete type");
+                ok = false;
+            }
+            _ => {}
+        }
+    }
+    ok
+}
+
 fn has_bench_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
     let has_sig = match &i.kind {
         // N.B., inadequate check, but we're running
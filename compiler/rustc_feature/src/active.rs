@@ -512,6 +512,21 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, string_deref_patterns, "1.67.0", Some(87121), None),
     /// Allows the use of `#[target_feature]` on safe functions.
     (active, target_feature_11, "1.45.0", Some(69098), None),
+    // FIXME(test_fixtures): open a tracking issue and use it instead of `None`.
+    // no-tracking-issue-start
+    /// Allows `#[test]` functions to take fixtures as arguments.
+    (active, test_fixtures, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
+    // FIXME(test_params): open a tracking issue and use it instead of `None`.
+    // no-tracking-issue-start
+    /// Allows `#[test_params(...)]` to run a `#[test]` function once for each set of arguments.
+    (active, test_params, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
+    // FIXME(test_timeout): open a tracking issue and use it instead of `None`.
+    // no-tracking-issue-start
+    /// Allows `#[test_timeout = "..."]` to bound the run time of a single `#[test]`.
+    (active, test_timeout, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Allows using `#[thread_local]` on `static` items.
     (active, thread_local, "1.0.0", Some(29594), None),
     /// Allows defining `trait X = A + B;` alias items.
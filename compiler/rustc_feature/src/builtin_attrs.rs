@@ -279,6 +279,14 @@ pub struct BuiltinAttribute {
         should_panic, Normal,
         template!(Word, List: r#"expected = "reason""#, NameValueStr: "reason"), FutureWarnFollowing,
     ),
+    gated!(
+        test_params, Normal, template!(List: "case_name, arg1, arg2, ..."), DuplicatesOk,
+        experimental!(test_params)
+    ),
+    gated!(
+        test_timeout, Normal, template!(NameValueStr: "duration"), ErrorFollowing,
+        experimental!(test_timeout)
+    ),
     // FIXME(Centril): This can be used on stable but shouldn't.
     ungated!(reexport_test_harness_main, CrateLevel, template!(NameValueStr: "name"), ErrorFollowing),
 
@@ -21,8 +21,11 @@ pub fn check_attr(sess: &ParseSess, attr: &Attribute) {
 
     // Check input tokens for built-in and key-value attributes.
     match attr_info {
-        // `rustc_dummy` doesn't have any restrictions specific to built-in attributes.
-        Some(BuiltinAttribute { name, template, .. }) if *name != sym::rustc_dummy => {
+        // `rustc_dummy` doesn't have any restrictions specific to built-in attributes, and
+        // `test_params` takes expressions, which are parsed by the `#[test]` macro.
+        Some(BuiltinAttribute { name, template, .. })
+            if !matches!(*name, sym::rustc_dummy | sym::test_params) =>
+        {
             check_builtin_attribute(sess, attr, *name, *template)
         }
         _ if let AttrArgs::Eq(..) = attr.get_normal_item().args => {
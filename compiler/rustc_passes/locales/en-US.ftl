@@ -371,6 +371,9 @@ passes_link_ordinal =
     attribute should be applied to a foreign function or static
     .label = not a foreign function or static
 
+passes_test_params =
+    `#[test_params]` can only be used on `#[test]` functions
+
 passes_collapse_debuginfo =
     `collapse_debuginfo` attribute should be applied to macro definitions
     .label = not a macro definition
@@ -182,6 +182,7 @@ fn check_attributes(
                 | sym::rustc_allowed_through_unstable_modules
                 | sym::rustc_promotable => self.check_stability_promotable(&attr, span, target),
                 sym::link_ordinal => self.check_link_ordinal(&attr, span, target),
+                sym::test_params => self.check_test_params(&attr),
                 _ => true,
             };
             is_valid &= attr_is_valid;
@@ -2072,6 +2073,13 @@ fn check_link_ordinal(&self, attr: &Attribute, _span: Span, target: Target) -> b
         }
     }
 
+    /// `#[test]` consumes the `#[test_params]` attributes of its function, so any that are
+    /// left are on something else.
+    fn check_test_params(&self, attr: &Attribute) -> bool {
+        self.tcx.sess.emit_err(errors::TestParams { attr_span: attr.span });
+        false
+    }
+
     fn check_deprecated(&self, hir_id: HirId, attr: &Attribute, _span: Span, target: Target) {
         match target {
             Target::Closure | Target::Expression | Target::Statement | Target::Arm => {
@@ -623,6 +623,13 @@ pub struct LinkOrdinal {
     pub attr_span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_test_params)]
+pub struct TestParams {
+    #[primary_span]
+    pub attr_span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_stability_promotable)]
 pub struct StabilityPromotable {
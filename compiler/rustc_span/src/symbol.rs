@@ -1467,8 +1467,11 @@
         test_2018_feature,
         test_accepted_feature,
         test_case,
+        test_fixtures,
+        test_params,
         test_removed_feature,
         test_runner,
+        test_timeout,
         test_unstable_lint,
         thread,
         thread_local,
@@ -79,22 +79,23 @@
 // We attempt to amortize this cost as much as possible by delaying resolution
 // of an address to a human readable name for as long as possible. When
 // `Backtrace::create` is called to capture a backtrace it doesn't actually
-// perform any symbol resolution, but rather we lazily resolve symbols only just
-// before they're needed for printing. This way we can make capturing a
-// backtrace and throwing it away much cheaper, but actually printing a
-// backtrace is still basically the same cost.
+// perform any symbol resolution, but rather we lazily resolve the symbols of
+// each frame only just before they're needed for printing or inspection. This
+// way we can make capturing a backtrace and throwing it away (or only looking
+// at its instruction pointers) much cheaper, but actually printing a backtrace
+// is still basically the same cost.
 //
-// This strategy comes at the cost of some synchronization required inside of a
-// `Backtrace`, but that's a relatively small price to pay relative to capturing
+// This strategy comes at the cost of some synchronization required inside of
+// each frame, but that's a relatively small price to pay relative to capturing
 // a backtrace or actually symbolizing it.
 
 use crate::backtrace_rs::{self, BytesOrWideString};
-use crate::cell::UnsafeCell;
 use crate::env;
 use crate::ffi::c_void;
 use crate::fmt;
+use crate::path::PathBuf;
 use crate::sync::atomic::{AtomicUsize, Ordering::Relaxed};
-use crate::sync::Once;
+use crate::sync::OnceLock;
 use crate::sys_common::backtrace::{lock, output_filename};
 use crate::vec::Vec;
 
@@ -133,12 +134,11 @@ pub enum BacktraceStatus {
 enum Inner {
     Unsupported,
     Disabled,
-    Captured(LazilyResolvedCapture),
+    Captured(Capture),
 }
 
 struct Capture {
     actual_start: usize,
-    resolved: bool,
     frames: Vec<BacktraceFrame>,
 }
 
@@ -148,20 +148,29 @@ fn _assert<T: Send + Sync>() {}
 }
 
 /// A single frame of a backtrace.
+///
+/// The symbols of a frame are resolved lazily, the first time they are
+/// needed, so that inspecting only the addresses of a frame is cheap.
 #[unstable(feature = "backtrace_frames", issue = "79676")]
 pub struct BacktraceFrame {
     frame: RawFrame,
-    symbols: Vec<BacktraceSymbol>,
+    symbols: OnceLock<Vec<BacktraceSymbol>>,
 }
 
 #[derive(Debug)]
 enum RawFrame {
     Actual(backtrace_rs::Frame),
+    Unresolved(UnresolvedFrame),
     #[cfg(test)]
     Fake,
 }
 
-struct BacktraceSymbol {
+/// A symbol of a [`BacktraceFrame`].
+///
+/// A frame can have several symbols when functions were inlined into each
+/// other, the innermost function coming first.
+#[unstable(feature = "backtrace_frames", issue = "79676")]
+pub struct BacktraceSymbol {
     name: Option<Vec<u8>>,
     filename: Option<BytesOrWide>,
     lineno: Option<u32>,
@@ -173,13 +182,189 @@ enum BytesOrWide {
     Wide(Vec<u16>),
 }
 
+/// The address of a frame of a backtrace, captured without resolving any
+/// symbols.
+///
+/// These are captured with [`Backtrace::capture_unresolved`], and can be
+/// symbolized afterwards with [`Backtrace::from_unresolved`].
+// FIXME(backtrace_unresolved): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "backtrace_unresolved", issue = "none")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnresolvedFrame {
+    ip: usize,
+    module_base: Option<usize>,
+}
+
+impl UnresolvedFrame {
+    /// Creates a frame from an instruction pointer, for example one that was
+    /// captured by another process.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub const fn from_ip(ip: usize) -> UnresolvedFrame {
+        UnresolvedFrame { ip, module_base: None }
+    }
+
+    /// Sets the address at which the module of this frame (the executable or
+    /// shared library containing its code) is loaded.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub const fn with_module_base_address(self, base: usize) -> UnresolvedFrame {
+        UnresolvedFrame { ip: self.ip, module_base: Some(base) }
+    }
+
+    /// Returns the instruction pointer of this frame.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub const fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Returns the address at which the module of this frame was loaded, if
+    /// it is known.
+    ///
+    /// Frames captured with [`Backtrace::capture_unresolved`] don't have
+    /// one until it is set, for example with
+    /// [`LoadedModules::set_module_base_addresses`]. It is then the address in
+    /// the capturing process, as needed by [`relocate`].
+    ///
+    /// [`relocate`]: UnresolvedFrame::relocate
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub const fn module_base_address(&self) -> Option<usize> {
+        self.module_base
+    }
+
+    /// Translates this frame from a process in which its module (the
+    /// executable or shared library containing its code) was loaded at
+    /// `from_base`, to a process in which the same module is loaded at
+    /// `to_base`.
+    ///
+    /// This allows symbolizing a backtrace captured in another process, or
+    /// in an earlier run of the same program, as long as the current process
+    /// has the same module loaded. With address space layout randomization,
+    /// modules are loaded at different addresses in every process.
+    ///
+    /// The module base address of the frame, if known, is translated as
+    /// well.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub const fn relocate(self, from_base: usize, to_base: usize) -> UnresolvedFrame {
+        let ip = self.ip.wrapping_sub(from_base).wrapping_add(to_base);
+        let module_base = match self.module_base {
+            Some(base) => Some(base.wrapping_sub(from_base).wrapping_add(to_base)),
+            None => None,
+        };
+        UnresolvedFrame { ip, module_base }
+    }
+}
+
+/// A snapshot of the modules (the executable and shared libraries) loaded in
+/// the current process, to look up the module base addresses of
+/// [`UnresolvedFrame`]s.
+///
+/// Taking a snapshot allocates and takes the dynamic loader's lock, but
+/// looking up addresses in it afterwards does neither. So a snapshot taken
+/// before installing a crash handler can be used in the handler, to record
+/// the module base addresses of the frames captured with
+/// [`Backtrace::capture_unresolved`]. Modules loaded after the snapshot was
+/// taken are not in it.
+///
+/// # Platform-specific behavior
+///
+/// Snapshots are only supported on Linux, Android and FreeBSD, where the base
+/// address of a module is its load bias: the difference between its addresses
+/// in memory and in the file. On other platforms, snapshots are empty.
+#[unstable(feature = "backtrace_unresolved", issue = "none")]
+#[derive(Clone, Debug, Default)]
+pub struct LoadedModules {
+    /// The start, end and module base address of every loaded segment,
+    /// sorted by their start.
+    segments: Vec<(usize, usize, usize)>,
+}
+
+impl LoadedModules {
+    /// Takes a snapshot of the modules loaded in the current process.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub fn snapshot() -> LoadedModules {
+        #[allow(unused_mut)]
+        let mut segments = Vec::new();
+        #[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+        unsafe {
+            unsafe extern "C" fn push_segments(
+                info: *mut libc::dl_phdr_info,
+                _size: libc::size_t,
+                data: *mut c_void,
+            ) -> libc::c_int {
+                // The type of the program headers of the segments mapped into memory.
+                const PT_LOAD: u32 = 1;
+
+                let segments = unsafe { &mut *data.cast::<Vec<(usize, usize, usize)>>() };
+                let info = unsafe { &*info };
+                let base = info.dlpi_addr as usize;
+                for i in 0..info.dlpi_phnum as usize {
+                    let header = unsafe { &*info.dlpi_phdr.add(i) };
+                    if header.p_type == PT_LOAD && header.p_memsz != 0 {
+                        let start = base.wrapping_add(header.p_vaddr as usize);
+                        segments.push((start, start.wrapping_add(header.p_memsz as usize), base));
+                    }
+                }
+                0
+            }
+            libc::dl_iterate_phdr(Some(push_segments), (&mut segments as *mut Vec<_>).cast());
+        }
+        segments.sort_unstable();
+        LoadedModules { segments }
+    }
+
+    /// Returns the base address of the module containing `ip`, if it is in
+    /// the snapshot.
+    ///
+    /// This neither allocates nor takes any locks.
+    #[must_use]
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub fn module_base_address(&self, ip: usize) -> Option<usize> {
+        let next = self.segments.partition_point(|&(start, _, _)| start <= ip);
+        let &(_, end, base) = self.segments.get(next.checked_sub(1)?)?;
+        (ip < end).then_some(base)
+    }
+
+    /// Sets the module base address of every frame that doesn't have one yet
+    /// and whose module is in the snapshot.
+    ///
+    /// This neither allocates nor takes any locks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(backtrace_unresolved)]
+    /// use std::backtrace::{Backtrace, LoadedModules, UnresolvedFrame};
+    ///
+    /// // Before installing the crash handler:
+    /// let modules = LoadedModules::snapshot();
+    ///
+    /// // In the crash handler:
+    /// let mut frames = [UnresolvedFrame::default(); 64];
+    /// let len = Backtrace::capture_unresolved(&mut frames);
+    /// modules.set_module_base_addresses(&mut frames[..len]);
+    /// ```
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub fn set_module_base_addresses(&self, frames: &mut [UnresolvedFrame]) {
+        for frame in frames {
+            if frame.module_base.is_none() {
+                frame.module_base = self.module_base_address(frame.ip);
+            }
+        }
+    }
+}
+
 #[stable(feature = "backtrace", since = "1.65.0")]
 impl fmt::Debug for Backtrace {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let capture = match &self.inner {
             Inner::Unsupported => return fmt.write_str("<unsupported>"),
             Inner::Disabled => return fmt.write_str("<disabled>"),
-            Inner::Captured(c) => c.force(),
+            Inner::Captured(c) => c,
         };
 
         let frames = &capture.frames[capture.actual_start..];
@@ -193,7 +378,7 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
                 continue;
             }
 
-            dbg.entries(&frame.symbols);
+            dbg.entries(frame.symbols());
         }
 
         dbg.finish()
@@ -204,11 +389,12 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
 impl fmt::Debug for BacktraceFrame {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut dbg = fmt.debug_list();
-        dbg.entries(&self.symbols);
+        dbg.entries(self.symbols());
         dbg.finish()
     }
 }
 
+#[unstable(feature = "backtrace_frames", issue = "79676")]
 impl fmt::Debug for BacktraceSymbol {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         // FIXME: improve formatting: https://github.com/rust-lang/rust/issues/65280
@@ -322,6 +508,113 @@ pub const fn disabled() -> Backtrace {
         Backtrace { inner: Inner::Disabled }
     }
 
+    /// Captures the frames of the current thread's stack into `frames`,
+    /// without resolving any symbols, and returns the number of frames
+    /// captured.
+    ///
+    /// Unlike the other capture functions, this neither allocates nor takes
+    /// any locks, and ignores the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE`
+    /// environment variables. It is meant to be used where little else is
+    /// allowed, like in a signal handler for a crash. The captured frames can
+    /// be symbolized afterwards with [`Backtrace::from_unresolved`].
+    ///
+    /// If the stack is deeper than `frames` is long, only the innermost
+    /// frames are captured. The frame of this function itself is skipped.
+    ///
+    /// Only the instruction pointers are recorded. To also record the base
+    /// addresses of their modules, which are needed to [relocate] the frames
+    /// to another process, look them up in a [`LoadedModules`] snapshot that
+    /// was taken before the crash handler was installed.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On platforms where backtraces are captured with the system unwinder,
+    /// like Linux, this is async-signal-safe on a best-effort basis: it does
+    /// not allocate, but the unwinder may still need to read the unwind
+    /// tables of modules loaded in the meantime. On Windows, the first
+    /// backtrace captured in a process initializes the debugging support,
+    /// which is not safe to do in a crash handler.
+    ///
+    /// If capturing backtraces is not supported, this returns zero.
+    ///
+    /// [relocate]: UnresolvedFrame::relocate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(backtrace_unresolved)]
+    /// use std::backtrace::{Backtrace, UnresolvedFrame};
+    ///
+    /// let mut frames = [UnresolvedFrame::default(); 64];
+    /// let len = Backtrace::capture_unresolved(&mut frames);
+    ///
+    /// // Later, outside of the crash handler:
+    /// let backtrace = Backtrace::from_unresolved(&frames[..len]);
+    /// println!("{backtrace}");
+    /// ```
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    #[inline(never)] // want to make sure there's a frame here to remove
+    pub fn capture_unresolved(frames: &mut [UnresolvedFrame]) -> usize {
+        let ip = Backtrace::capture_unresolved as usize;
+        let mut len = 0;
+        let mut found_start = false;
+        unsafe {
+            backtrace_rs::trace_unsynchronized(|frame| {
+                if !found_start && frame.symbol_address().addr() == ip {
+                    // Drop the frames of the capturing machinery.
+                    found_start = true;
+                    len = 0;
+                    return true;
+                }
+                match frames.get_mut(len) {
+                    Some(slot) => {
+                        *slot = UnresolvedFrame::from_ip(frame.ip().addr());
+                        len += 1;
+                        true
+                    }
+                    // The frames so far are dropped once the start is found, so keep walking
+                    // until then instead of filling the buffer with the capturing machinery.
+                    None => !found_start,
+                }
+            });
+        }
+        len
+    }
+
+    /// Creates a backtrace from frames captured with
+    /// [`Backtrace::capture_unresolved`], to symbolize them.
+    ///
+    /// The symbols are resolved lazily, in the current process. To symbolize
+    /// frames captured in another process, first [`relocate`] them to the
+    /// addresses at which their modules are loaded in the current process.
+    ///
+    /// Frames without a module base address get the one of the module that
+    /// contains them in the current process, if any.
+    ///
+    /// If `frames` is empty, the returned backtrace is
+    /// [`Unsupported`](BacktraceStatus::Unsupported), like one captured on a
+    /// platform that doesn't support backtraces.
+    ///
+    /// [`relocate`]: UnresolvedFrame::relocate
+    #[unstable(feature = "backtrace_unresolved", issue = "none")]
+    pub fn from_unresolved(frames: &[UnresolvedFrame]) -> Backtrace {
+        if frames.is_empty() {
+            return Backtrace { inner: Inner::Unsupported };
+        }
+        let mut frames = frames.to_vec();
+        if frames.iter().any(|frame| frame.module_base.is_none()) {
+            LoadedModules::snapshot().set_module_base_addresses(&mut frames);
+        }
+        let frames = frames
+            .into_iter()
+            .map(|frame| BacktraceFrame {
+                frame: RawFrame::Unresolved(frame),
+                symbols: OnceLock::new(),
+            })
+            .collect();
+        Backtrace { inner: Inner::Captured(Capture { actual_start: 0, frames }) }
+    }
+
     // Capture a backtrace which start just before the function addressed by
     // `ip`
     fn create(ip: usize) -> Backtrace {
@@ -332,7 +625,7 @@ fn create(ip: usize) -> Backtrace {
             backtrace_rs::trace_unsynchronized(|frame| {
                 frames.push(BacktraceFrame {
                     frame: RawFrame::Actual(frame.clone()),
-                    symbols: Vec::new(),
+                    symbols: OnceLock::new(),
                 });
                 if frame.symbol_address().addr() == ip && actual_start.is_none() {
                     actual_start = Some(frames.len());
@@ -347,11 +640,7 @@ fn create(ip: usize) -> Backtrace {
         let inner = if frames.is_empty() {
             Inner::Unsupported
         } else {
-            Inner::Captured(LazilyResolvedCapture::new(Capture {
-                actual_start: actual_start.unwrap_or(0),
-                frames,
-                resolved: false,
-            }))
+            Inner::Captured(Capture { actual_start: actual_start.unwrap_or(0), frames })
         };
 
         Backtrace { inner }
@@ -373,10 +662,172 @@ pub fn status(&self) -> BacktraceStatus {
 
 impl<'a> Backtrace {
     /// Returns an iterator over the backtrace frames.
+    ///
+    /// This includes the frames of the backtrace capturing machinery itself.
+    /// Use [`Backtrace::short_frames`] to skip those, and runtime frames.
+    ///
+    /// The symbols of the frames are only resolved when they are accessed.
     #[must_use]
     #[unstable(feature = "backtrace_frames", issue = "79676")]
     pub fn frames(&'a self) -> &'a [BacktraceFrame] {
-        if let Inner::Captured(c) = &self.inner { &c.force().frames } else { &[] }
+        if let Inner::Captured(c) = &self.inner { &c.frames } else { &[] }
+    }
+
+    /// Returns the backtrace frames, without the frames of the standard
+    /// library's runtime.
+    ///
+    /// This skips the frames of the backtrace capturing machinery, the frames
+    /// of the panic machinery when captured while panicking, and the frames
+    /// that start the main function or a spawned thread. These are the frames
+    /// that are printed in the short backtrace format on panic.
+    ///
+    /// Finding the runtime frames requires resolving the symbols of the
+    /// frames, up to the start of the runtime frames at the bottom of the
+    /// stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(backtrace_frames)]
+    /// use std::backtrace::Backtrace;
+    ///
+    /// let backtrace = Backtrace::force_capture();
+    /// for frame in backtrace.short_frames() {
+    ///     for symbol in frame.symbols() {
+    ///         println!("{:?} at {:?}:{:?}", symbol.name(), symbol.filename(), symbol.lineno());
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn short_frames(&'a self) -> &'a [BacktraceFrame] {
+        let frames = match &self.inner {
+            Inner::Captured(c) => &c.frames[c.actual_start..],
+            _ => return &[],
+        };
+        let mut start = 0;
+        let mut end = frames.len();
+        for (i, frame) in frames.iter().enumerate() {
+            if frame.has_symbol_containing("__rust_begin_short_backtrace") {
+                end = i;
+                break;
+            }
+            if frame.has_symbol_containing("__rust_end_short_backtrace") {
+                start = i + 1;
+            }
+        }
+        &frames[start..end]
+    }
+}
+
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    ///
+    /// This is usually the address of the next instruction to execute in the
+    /// frame, which is typically just after a call instruction.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn ip(&self) -> *mut c_void {
+        self.frame.ip()
+    }
+
+    /// Returns the starting address of the function this frame is executing,
+    /// if known.
+    ///
+    /// If the start of the function can't be determined, this returns the
+    /// instruction pointer.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn symbol_address(&self) -> *mut c_void {
+        self.frame.symbol_address()
+    }
+
+    /// Returns the base address at which the module (the executable or shared
+    /// library) containing this frame's code is loaded, if known.
+    ///
+    /// Together with [`ip`](BacktraceFrame::ip), this allows symbolizing the
+    /// frame offline.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn module_base_address(&self) -> Option<*mut c_void> {
+        self.frame.module_base_address()
+    }
+
+    /// Returns the symbols of this frame, resolving them if that didn't happen
+    /// yet.
+    ///
+    /// This returns an empty slice if the frame could not be resolved.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        self.symbols.get_or_init(|| self.frame.resolve())
+    }
+
+    fn has_symbol_containing(&self, needle: &str) -> bool {
+        self.symbols().iter().any(|symbol| match &symbol.name {
+            Some(name) => name.windows(needle.len()).any(|window| window == needle.as_bytes()),
+            None => false,
+        })
+    }
+}
+
+impl BacktraceSymbol {
+    /// Returns the demangled name of this symbol, if known.
+    ///
+    /// The hash that is part of the mangled name of Rust symbols is omitted.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn name(&self) -> Option<String> {
+        self.name.as_ref().map(|b| format!("{:#}", backtrace_rs::SymbolName::new(b)))
+    }
+
+    /// Returns the raw, possibly mangled, name of this symbol, if known.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn raw_name(&self) -> Option<&[u8]> {
+        self.name.as_deref()
+    }
+
+    /// Returns the path of the source file of this symbol, if known.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn filename(&self) -> Option<PathBuf> {
+        self.filename.as_ref().map(BytesOrWide::to_path_buf)
+    }
+
+    /// Returns the line number in the source file of this symbol, if known.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn lineno(&self) -> Option<u32> {
+        self.lineno
+    }
+
+    /// Returns the column number in the source file of this symbol, if known.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn colno(&self) -> Option<u32> {
+        self.colno
+    }
+}
+
+impl BytesOrWide {
+    fn to_path_buf(&self) -> PathBuf {
+        match self {
+            #[cfg(unix)]
+            BytesOrWide::Bytes(b) => {
+                use crate::os::unix::ffi::OsStrExt;
+                PathBuf::from(crate::ffi::OsStr::from_bytes(b))
+            }
+            #[cfg(not(unix))]
+            BytesOrWide::Bytes(b) => PathBuf::from(String::from_utf8_lossy(b).into_owned()),
+            #[cfg(windows)]
+            BytesOrWide::Wide(w) => {
+                use crate::os::windows::ffi::OsStringExt;
+                PathBuf::from(crate::ffi::OsString::from_wide(w))
+            }
+            #[cfg(not(windows))]
+            BytesOrWide::Wide(w) => PathBuf::from(String::from_utf16_lossy(w)),
+        }
     }
 }
 
@@ -386,7 +837,7 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let capture = match &self.inner {
             Inner::Unsupported => return fmt.write_str("unsupported backtrace"),
             Inner::Disabled => return fmt.write_str("disabled backtrace"),
-            Inner::Captured(c) => c.force(),
+            Inner::Captured(c) => c,
         };
 
         let full = fmt.alternate();
@@ -408,10 +859,11 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut f = backtrace_rs::BacktraceFmt::new(fmt, style, &mut print_path);
         f.add_context()?;
         for frame in frames {
-            if frame.symbols.is_empty() {
+            let symbols = frame.symbols();
+            if symbols.is_empty() {
                 f.frame().print_raw(frame.frame.ip(), None, None, None)?;
             } else {
-                for symbol in frame.symbols.iter() {
+                for symbol in symbols {
                     f.frame().print_raw_with_column(
                         frame.frame.ip(),
                         symbol.name.as_ref().map(|b| backtrace_rs::SymbolName::new(b)),
@@ -430,75 +882,66 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
-struct LazilyResolvedCapture {
-    sync: Once,
-    capture: UnsafeCell<Capture>,
-}
-
-impl LazilyResolvedCapture {
-    fn new(capture: Capture) -> Self {
-        LazilyResolvedCapture { sync: Once::new(), capture: UnsafeCell::new(capture) }
+impl RawFrame {
+    fn ip(&self) -> *mut c_void {
+        match self {
+            RawFrame::Actual(frame) => frame.ip(),
+            RawFrame::Unresolved(frame) => crate::ptr::invalid_mut(frame.ip),
+            #[cfg(test)]
+            RawFrame::Fake => crate::ptr::invalid_mut(1),
+        }
     }
 
-    fn force(&self) -> &Capture {
-        self.sync.call_once(|| {
-            // SAFETY: This exclusive reference can't overlap with any others
-            // `Once` guarantees callers will block until this closure returns
-            // `Once` also guarantees only a single caller will enter this closure
-            unsafe { &mut *self.capture.get() }.resolve();
-        });
-
-        // SAFETY: This shared reference can't overlap with the exclusive reference above
-        unsafe { &*self.capture.get() }
+    fn symbol_address(&self) -> *mut c_void {
+        match self {
+            RawFrame::Actual(frame) => frame.symbol_address(),
+            // The start of the function is only known once the frame is resolved.
+            RawFrame::Unresolved(frame) => crate::ptr::invalid_mut(frame.ip),
+            #[cfg(test)]
+            RawFrame::Fake => crate::ptr::invalid_mut(1),
+        }
     }
-}
-
-// SAFETY: Access to the inner value is synchronized using a thread-safe `Once`
-// So long as `Capture` is `Sync`, `LazilyResolvedCapture` is too
-unsafe impl Sync for LazilyResolvedCapture where Capture: Sync {}
 
-impl Capture {
-    fn resolve(&mut self) {
-        // If we're already resolved, nothing to do!
-        if self.resolved {
-            return;
+    fn module_base_address(&self) -> Option<*mut c_void> {
+        match self {
+            RawFrame::Actual(frame) => frame.module_base_address(),
+            RawFrame::Unresolved(frame) => frame.module_base.map(crate::ptr::invalid_mut),
+            #[cfg(test)]
+            RawFrame::Fake => None,
         }
-        self.resolved = true;
+    }
+
+    fn resolve(&self) -> Vec<BacktraceSymbol> {
+        let mut symbols = Vec::new();
+        let mut push_symbol = |symbol: &backtrace_rs::Symbol| {
+            symbols.push(BacktraceSymbol {
+                name: symbol.name().map(|m| m.as_bytes().to_vec()),
+                filename: symbol.filename_raw().map(|b| match b {
+                    BytesOrWideString::Bytes(b) => BytesOrWide::Bytes(b.to_owned()),
+                    BytesOrWideString::Wide(b) => BytesOrWide::Wide(b.to_owned()),
+                }),
+                lineno: symbol.lineno(),
+                colno: symbol.colno(),
+            });
+        };
 
         // Use the global backtrace lock to synchronize this as it's a
         // requirement of the `backtrace` crate, and then actually resolve
-        // everything.
+        // the frame.
         let _lock = lock();
-        for frame in self.frames.iter_mut() {
-            let symbols = &mut frame.symbols;
-            let frame = match &frame.frame {
-                RawFrame::Actual(frame) => frame,
-                #[cfg(test)]
-                RawFrame::Fake => unimplemented!(),
-            };
-            unsafe {
-                backtrace_rs::resolve_frame_unsynchronized(frame, |symbol| {
-                    symbols.push(BacktraceSymbol {
-                        name: symbol.name().map(|m| m.as_bytes().to_vec()),
-                        filename: symbol.filename_raw().map(|b| match b {
-                            BytesOrWideString::Bytes(b) => BytesOrWide::Bytes(b.to_owned()),
-                            BytesOrWideString::Wide(b) => BytesOrWide::Wide(b.to_owned()),
-                        }),
-                        lineno: symbol.lineno(),
-                        colno: symbol.colno(),
-                    });
-                });
-            }
-        }
-    }
-}
-
-impl RawFrame {
-    fn ip(&self) -> *mut c_void {
         match self {
-            RawFrame::Actual(frame) => frame.ip(),
+            RawFrame::Actual(frame) => unsafe {
+                backtrace_rs::resolve_frame_unsynchronized(frame, &mut push_symbol)
+            },
+            RawFrame::Unresolved(frame) => unsafe {
+                backtrace_rs::resolve_unsynchronized(
+                    crate::ptr::invalid_mut(frame.ip),
+                    &mut push_symbol,
+                )
+            },
             #[cfg(test)]
-            RawFrame::Fake => crate::ptr::invalid_mut(1),
+            RawFrame::Fake => unimplemented!(),
         }
+        symbols
     }
 }
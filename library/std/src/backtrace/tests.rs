@@ -4,25 +4,25 @@ fn generate_fake_frames() -> Vec<BacktraceFrame> {
     vec![
         BacktraceFrame {
             frame: RawFrame::Fake,
-            symbols: vec![BacktraceSymbol {
+            symbols: OnceLock::from(vec![BacktraceSymbol {
                 name: Some(b"std::backtrace::Backtrace::create".to_vec()),
                 filename: Some(BytesOrWide::Bytes(b"rust/backtrace.rs".to_vec())),
                 lineno: Some(100),
                 colno: None,
-            }],
+            }]),
         },
         BacktraceFrame {
             frame: RawFrame::Fake,
-            symbols: vec![BacktraceSymbol {
+            symbols: OnceLock::from(vec![BacktraceSymbol {
                 name: Some(b"__rust_maybe_catch_panic".to_vec()),
                 filename: None,
                 lineno: None,
                 colno: None,
-            }],
+            }]),
         },
         BacktraceFrame {
             frame: RawFrame::Fake,
-            symbols: vec![
+            symbols: OnceLock::from(vec![
                 BacktraceSymbol {
                     name: Some(b"std::rt::lang_start_internal".to_vec()),
                     filename: Some(BytesOrWide::Bytes(b"rust/rt.rs".to_vec())),
@@ -35,7 +35,7 @@ fn generate_fake_frames() -> Vec<BacktraceFrame> {
                     lineno: Some(400),
                     colno: None,
                 },
-            ],
+            ]),
         },
     ]
 }
@@ -43,11 +43,7 @@ fn generate_fake_frames() -> Vec<BacktraceFrame> {
 #[test]
 fn test_debug() {
     let backtrace = Backtrace {
-        inner: Inner::Captured(LazilyResolvedCapture::new(Capture {
-            actual_start: 1,
-            resolved: true,
-            frames: generate_fake_frames(),
-        })),
+        inner: Inner::Captured(Capture { actual_start: 1, frames: generate_fake_frames() }),
     };
 
     #[rustfmt::skip]
@@ -66,11 +62,7 @@ fn test_debug() {
 #[test]
 fn test_frames() {
     let backtrace = Backtrace {
-        inner: Inner::Captured(LazilyResolvedCapture::new(Capture {
-            actual_start: 1,
-            resolved: true,
-            frames: generate_fake_frames(),
-        })),
+        inner: Inner::Captured(Capture { actual_start: 1, frames: generate_fake_frames() }),
     };
 
     let frames = backtrace.frames();
@@ -93,3 +85,103 @@ fn test_frames() {
 
     assert!(iter.all(|(f, e)| format!("{f:#?}") == *e));
 }
+
+#[test]
+fn test_symbol_accessors() {
+    let frames = generate_fake_frames();
+    assert!(!frames[0].ip().is_null());
+    assert_eq!(frames[0].module_base_address(), None);
+
+    let symbols = frames[2].symbols();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name().as_deref(), Some("std::rt::lang_start_internal"));
+    assert_eq!(symbols[0].raw_name(), Some(&b"std::rt::lang_start_internal"[..]));
+    assert_eq!(symbols[0].filename(), Some(PathBuf::from("rust/rt.rs")));
+    assert_eq!(symbols[0].lineno(), Some(300));
+    assert_eq!(symbols[0].colno(), Some(5));
+    assert_eq!(symbols[1].colno(), None);
+
+    let symbols = frames[1].symbols();
+    assert_eq!(symbols[0].filename(), None);
+    assert_eq!(symbols[0].lineno(), None);
+}
+
+#[test]
+fn test_short_frames() {
+    fn fake_frame(name: &str) -> BacktraceFrame {
+        BacktraceFrame {
+            frame: RawFrame::Fake,
+            symbols: OnceLock::from(vec![BacktraceSymbol {
+                name: Some(name.as_bytes().to_vec()),
+                filename: None,
+                lineno: None,
+                colno: None,
+            }]),
+        }
+    }
+
+    let backtrace = Backtrace {
+        inner: Inner::Captured(Capture {
+            actual_start: 1,
+            frames: vec![
+                fake_frame("std::backtrace::Backtrace::create"),
+                fake_frame("std::panicking::begin_panic_handler"),
+                fake_frame("std::sys_common::backtrace::__rust_end_short_backtrace"),
+                fake_frame("user::inner"),
+                fake_frame("user::outer"),
+                fake_frame("std::sys_common::backtrace::__rust_begin_short_backtrace"),
+                fake_frame("std::rt::lang_start"),
+            ],
+        }),
+    };
+
+    let names: Vec<_> =
+        backtrace.short_frames().iter().map(|f| f.symbols()[0].name().unwrap()).collect();
+    assert_eq!(names, ["user::inner", "user::outer"]);
+    assert_eq!(backtrace.frames().len(), 7);
+
+    assert!(Backtrace::disabled().short_frames().is_empty());
+}
+
+#[test]
+fn test_unresolved_frames() {
+    let frame = UnresolvedFrame::from_ip(0x1234);
+    assert_eq!(frame.ip(), 0x1234);
+    assert_eq!(frame.relocate(0x1000, 0x7000).ip(), 0x7234);
+    assert_eq!(frame.relocate(0x1000, 0x7000).relocate(0x7000, 0x1000), frame);
+    assert_eq!(frame.module_base_address(), None);
+    let frame = frame.with_module_base_address(0x1000);
+    assert_eq!(frame.relocate(0x1000, 0x7000).module_base_address(), Some(0x7000));
+
+    let backtrace = Backtrace::from_unresolved(&[]);
+    assert_eq!(backtrace.status(), BacktraceStatus::Unsupported);
+
+    let mut frames = [UnresolvedFrame::default(); 4];
+    assert_eq!(Backtrace::capture_unresolved(&mut frames[..0]), 0);
+    let len = Backtrace::capture_unresolved(&mut frames);
+    assert!(len <= frames.len());
+    let backtrace = Backtrace::from_unresolved(&frames[..len]);
+    assert_eq!(backtrace.frames().len(), len);
+    assert!(frames[..len].iter().all(|frame| frame.module_base_address().is_none()));
+
+    // The module base addresses are looked up when the frames are symbolized.
+    let modules = LoadedModules::snapshot();
+    modules.set_module_base_addresses(&mut frames[..len]);
+    for (frame, unresolved) in backtrace.frames().iter().zip(&frames) {
+        assert_eq!(frame.ip().addr(), unresolved.ip());
+        assert_eq!(frame.module_base_address().map(|base| base.addr()), unresolved.module_base);
+    }
+    #[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+    assert!(frames[..len].iter().all(|frame| frame.module_base_address().is_some()));
+    assert_eq!(modules.module_base_address(0), None);
+
+    // Even a buffer for a single frame gets a frame of the caller rather than of the unwinder.
+    let mut frame = [UnresolvedFrame::default()];
+    if Backtrace::capture_unresolved(&mut frame) == 1 {
+        let backtrace = Backtrace::from_unresolved(&frame);
+        let name = backtrace.frames()[0].symbols().first().and_then(|symbol| symbol.name());
+        if let Some(name) = name {
+            assert!(!name.contains("backtrace_rs"), "captured an unwinder frame: {name}");
+        }
+    }
+}
@@ -605,7 +605,11 @@ pub fn home_dir() -> Option<PathBuf> {
 /// in the temporary directory must use a secure method to create a uniquely
 /// named file. Creating a file or directory with a fixed or predictable name
 /// may result in "insecure temporary file" security vulnerabilities. Consider
-/// using a crate that securely creates temporary files or directories.
+/// using [`fs::TempFile`] and [`fs::TempDir`], which securely create temporary
+/// files and directories.
+///
+/// [`fs::TempFile`]: crate::fs::TempFile
+/// [`fs::TempDir`]: crate::fs::TempDir
 ///
 /// # Platform-specific behavior
 ///
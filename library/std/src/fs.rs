@@ -11,7 +11,7 @@
 #[cfg(all(test, not(any(target_os = "emscripten", target_env = "sgx"))))]
 mod tests;
 
-use crate::ffi::OsString;
+use crate::ffi::{OsStr, OsString};
 use crate::fmt;
 use crate::io::{self, BorrowedCursor, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
 use crate::path::{Path, PathBuf};
@@ -99,6 +99,144 @@ pub struct File {
     inner: fs_imp::File,
 }
 
+/// A file that replaces another one atomically once it's fully written.
+///
+/// An `AtomicFile` is staged as a temporary file in the same directory as the file it is
+/// going to replace. Nothing happens to that file until the new contents are committed with
+/// [`commit`], which syncs them to disk and then renames the temporary file over it, so
+/// that other processes, and the file system after a crash, either see the old contents or
+/// the new ones, never a mix of both. Dropping an `AtomicFile` without committing it
+/// removes the temporary file.
+///
+/// The new file is created with the default permissions of a new file, regardless of those
+/// of the file it replaces. They can be set with [`set_permissions`] before committing.
+///
+/// See also [`write_atomic`] to replace a file with a slice in one call.
+///
+/// # Platform-specific behavior
+///
+/// On Linux, the temporary file is created without a name, with `O_TMPFILE`, where the file
+/// system supports it, so that a crash can't leave it behind. Otherwise it's named after
+/// the file it replaces, with a random suffix. [`commit_new`] uses `renameat2` with
+/// `RENAME_NOREPLACE` on Linux, `MoveFileExW` on Windows, and creates a hard link on other
+/// platforms.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+/// [`commit`]: AtomicFile::commit
+/// [`commit_new`]: AtomicFile::commit_new
+/// [`set_permissions`]: File::set_permissions
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_atomic_write)]
+/// use std::fs::AtomicFile;
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut file = AtomicFile::create("config.toml")?;
+///     writeln!(file, "[server]")?;
+///     writeln!(file, "port = 8080")?;
+///     file.commit()
+/// }
+/// ```
+// FIXME(fs_atomic_write): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+#[derive(Debug)]
+pub struct AtomicFile {
+    file: File,
+    /// The file to replace.
+    path: PathBuf,
+    /// The name of the temporary file, or `None` if it doesn't have one.
+    temp: Option<PathBuf>,
+}
+
+/// A temporary file, which is removed when it's dropped.
+///
+/// The file is created with a random name, in the directory returned by [`env::temp_dir`]
+/// or in a directory passed to [`TempFile::new_in`], and fails to be created rather than
+/// opening a file that already exists, so that other users of the directory can't
+/// substitute a file of their own. [`keep`] keeps the file instead of removing it.
+///
+/// [`env::temp_dir`]: crate::env::temp_dir
+/// [`keep`]: TempFile::keep
+///
+/// # Platform-specific behavior
+///
+/// On Linux, the file is created without a name, with `O_TMPFILE`, where the file system
+/// supports it: it can't be left behind when the process exits abnormally, but it has no
+/// [`path`] either. On Unix, the file is only accessible by its owner.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+/// [`path`]: TempFile::path
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_temp)]
+/// use std::fs::TempFile;
+/// use std::io::{Read, Seek, Write};
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut file = TempFile::new()?;
+///     file.write_all(b"scratch data")?;
+///     file.rewind()?;
+///     let mut contents = String::new();
+///     file.read_to_string(&mut contents)?;
+///     Ok(())
+/// }
+/// ```
+// FIXME(fs_temp): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "fs_temp", issue = "none")]
+#[derive(Debug)]
+pub struct TempFile {
+    file: File,
+    /// The name of the file, or `None` if it doesn't have one.
+    path: Option<PathBuf>,
+    dir: PathBuf,
+}
+
+/// A temporary directory, which is removed along with its contents when it's dropped.
+///
+/// The directory is created with a random name, in the directory returned by
+/// [`env::temp_dir`] or in a directory passed to [`TempDir::new_in`], and fails to be
+/// created rather than reusing a directory that already exists. [`keep`] keeps the
+/// directory instead of removing it.
+///
+/// [`env::temp_dir`]: crate::env::temp_dir
+/// [`keep`]: TempDir::keep
+///
+/// # Platform-specific behavior
+///
+/// On Unix, the directory is only accessible by its owner.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_temp)]
+/// use std::fs::{self, TempDir};
+///
+/// fn main() -> std::io::Result<()> {
+///     let dir = TempDir::new()?;
+///     fs::write(dir.path().join("input.txt"), "test input")?;
+///     // `dir` and `input.txt` are removed here.
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_temp", issue = "none")]
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
 /// Metadata information about a file.
 ///
 /// This structure is returned from the [`metadata`] or
@@ -219,6 +357,152 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// A builder used to walk a directory tree recursively.
+///
+/// A walk lists the entries of a directory, and of all of its subdirectories, depth
+/// first: every subdirectory is listed right after its own entry. Entries are returned by
+/// the [`Walk`] iterator as [`DirEntry`]s, and the directory the walk starts in isn't
+/// returned itself.
+///
+/// # Platform-specific behavior
+///
+/// On Unix, subdirectories are opened relative to their parent, with `openat`. This makes
+/// the walk safe against other processes replacing directories by symlinks while it runs:
+/// unless symlinks are followed, it never leaves the directory tree it started in. On other
+/// platforms, subdirectories are opened by their path.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs::WalkDir;
+///
+/// fn main() -> std::io::Result<()> {
+///     for entry in WalkDir::new().max_depth(3).sort_by_file_name(true).walk("src")? {
+///         println!("{}", entry?.path().display());
+///     }
+///     Ok(())
+/// }
+/// ```
+// FIXME(fs_walk_dir): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct WalkDir {
+    follow_symlinks: bool,
+    max_depth: usize,
+    sort_by_file_name: bool,
+}
+
+/// Iterator over the entries of a directory tree.
+///
+/// This iterator is returned from [`WalkDir::walk`] and [`walk_dir`], and yields items of
+/// type <code>[io::Result]<[DirEntry]></code>. An error reading a directory, or opening a
+/// subdirectory, is returned in place of the entries that couldn't be read, and the walk
+/// continues with the next entry.
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct Walk {
+    options: WalkDir,
+    /// The directories being walked, from the root down to the directory of the last entry.
+    dirs: Vec<WalkedDir>,
+    /// The last entry, if it's a directory that the walk is about to descend into.
+    next_dir: Option<io::Result<WalkedDir>>,
+}
+
+struct WalkedDir {
+    inner: fs_imp::OpenDir,
+    /// The entries of the directory, if they are sorted.
+    sorted: Option<crate::vec::IntoIter<io::Result<fs_imp::DirEntry>>>,
+    /// The canonical path of the directory, to detect symlink loops.
+    canonical: Option<PathBuf>,
+}
+
+/// A builder used to copy a directory tree.
+///
+/// The regular files of the tree are copied with [`io::copy`], or cloned with reflinks on
+/// file systems that support them (see [`Reflink`]). Directories and files are created as
+/// needed, and what happens with the ones that already exist is set by [`if_exists`]. Other
+/// kinds of files, such as FIFOs, sockets and device nodes, can't be copied.
+///
+/// [`if_exists`]: CopyDir::if_exists
+///
+/// # Platform-specific behavior
+///
+/// Reflinks are made with the `FICLONE` ioctl on Linux and Android, which Btrfs, XFS and a
+/// few other file systems support. Other platforms don't support reflinks. On Linux and
+/// Android, files that aren't cloned are copied with `copy_file_range` or `sendfile` where
+/// possible, like [`copy`] does. The `fcopyfile` and `CopyFileExW` functions that [`copy`]
+/// uses on macOS and Windows aren't used.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_dir)]
+/// use std::fs::{CopyDir, IfExists, Reflink};
+///
+/// fn main() -> std::io::Result<()> {
+///     CopyDir::new()
+///         .reflink(Reflink::Always)
+///         .preserve_timestamps(true)
+///         .if_exists(IfExists::Overwrite)
+///         .copy("snapshot", "work")?;
+///     Ok(())
+/// }
+/// ```
+// FIXME(fs_copy_dir): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "fs_copy_dir", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct CopyDir {
+    reflink: Reflink,
+    preserve_permissions: bool,
+    preserve_timestamps: bool,
+    preserve_symlinks: bool,
+    if_exists: IfExists,
+}
+
+/// Whether [`CopyDir`] clones files with reflinks.
+///
+/// A reflink is a copy of a file that shares its data with the original until either is
+/// modified, which makes it almost instant to create and takes no extra space.
+#[unstable(feature = "fs_copy_dir", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Reflink {
+    /// Clones every file, and fails if one can't be cloned.
+    Always,
+    /// Clones files where the platform and the file system support it, and copies them
+    /// otherwise.
+    Auto,
+    /// Always copies files.
+    ///
+    /// Note that on Linux, some file systems share data between copied files anyway.
+    Never,
+}
+
+/// What [`CopyDir`] does with files that already exist at the destination.
+///
+/// Directories that exist at the destination are copied into, unless this is
+/// [`IfExists::Fail`].
+#[unstable(feature = "fs_copy_dir", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IfExists {
+    /// Fails with an error of kind [`io::ErrorKind::AlreadyExists`].
+    Fail,
+    /// Leaves the existing file as it is.
+    Skip,
+    /// Replaces the existing file. A symlink is replaced itself, rather than the file it
+    /// points to.
+    Overwrite,
+}
+
 /// Read the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -329,6 +613,35 @@ fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
     inner(path.as_ref(), contents.as_ref())
 }
 
+/// Atomically replaces the entire contents of a file with a slice.
+///
+/// This function creates the file if it does not exist. Unlike [`write`], a crash or another
+/// process reading the file never sees it partially written: this is a convenience function
+/// for using [`AtomicFile::create`], [`write_all`] and [`AtomicFile::commit`].
+///
+/// [`write_all`]: Write::write_all
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_atomic_write)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::write_atomic("state.json", br#"{ "version": 2 }"#)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut file = AtomicFile::create(path)?;
+        file.write_all(contents)?;
+        file.commit()
+    }
+    inner(path.as_ref(), contents.as_ref())
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -700,6 +1013,216 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
     pub fn set_modified(&self, time: SystemTime) -> io::Result<()> {
         self.set_times(FileTimes::new().set_modified(time))
     }
+
+    /// Acquires an exclusive lock on the file, blocking until the lock can be acquired.
+    ///
+    /// While the lock is held, no other file handle to this file can acquire a shared or
+    /// exclusive lock. The lock is released by [`unlock`], or when the file handle and all
+    /// of its duplicates (as created by [`try_clone`]) are closed.
+    ///
+    /// Locks are advisory: they don't prevent other processes from reading or writing the
+    /// file, only from locking it. Acquiring a lock through a file handle that already holds
+    /// one is platform-specific: Unix converts the lock, while Windows may deadlock.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the
+    /// `LOCK_EX` flag, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_EXCLUSIVE_LOCK` flag. Note that, this [may change in the future][changes].
+    ///
+    /// On Unix platforms without `flock`, such as illumos and Solaris, all the locking
+    /// functions use `fcntl` record locks (`F_SETLKW`, or `F_SETLK` for the `try_` variants)
+    /// instead. These locks belong to the process rather than the file handle: they don't
+    /// conflict with locks taken through other handles of the same process, and are
+    /// released when any handle to the file is closed. A shared lock needs the file to be
+    /// opened for reading, and an exclusive lock needs it to be opened for writing.
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`io::ErrorKind::Unsupported`] on platforms
+    /// that don't support file locking.
+    ///
+    /// [`unlock`]: File::unlock
+    /// [`try_clone`]: File::try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     f.lock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    // FIXME(file_lock): open a tracking issue and use it instead of `issue = "none"`.
+    #[unstable(feature = "file_lock", issue = "none")]
+    #[doc(alias = "flock")]
+    #[doc(alias = "LockFileEx")]
+    pub fn lock(&self) -> io::Result<()> {
+        self.inner.lock()
+    }
+
+    /// Acquires a shared lock on the file, blocking until the lock can be acquired.
+    ///
+    /// While the lock is held, other file handles to this file can acquire shared locks,
+    /// but not an exclusive one. See [`lock`] for how locks are released, and what they
+    /// do and don't prevent.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the
+    /// `LOCK_SH` flag, and the `LockFileEx` function on Windows. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`io::ErrorKind::Unsupported`] on platforms
+    /// that don't support file locking.
+    ///
+    /// [`lock`]: File::lock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.lock")?;
+    ///     f.lock_shared()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Tries to acquire an exclusive lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds a lock on the file, and `Ok(true)`
+    /// if the lock was acquired. See [`lock`] for what the lock does.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the
+    /// `LOCK_EX` and `LOCK_NB` flags, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_EXCLUSIVE_LOCK` and `LOCKFILE_FAIL_IMMEDIATELY` flags. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`io::ErrorKind::Unsupported`] on platforms
+    /// that don't support file locking.
+    ///
+    /// [`lock`]: File::lock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     if !f.try_lock()? {
+    ///         eprintln!("waiting for another process to release the lock...");
+    ///         f.lock()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.inner.try_lock()
+    }
+
+    /// Tries to acquire a shared lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds an exclusive lock on the file, and
+    /// `Ok(true)` if the lock was acquired. See [`lock_shared`] for what the lock does.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the
+    /// `LOCK_SH` and `LOCK_NB` flags, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_FAIL_IMMEDIATELY` flag. Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`io::ErrorKind::Unsupported`] on platforms
+    /// that don't support file locking.
+    ///
+    /// [`lock_shared`]: File::lock_shared
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.lock")?;
+    ///     if f.try_lock_shared()? {
+    ///         // read the shared state...
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases the lock held by this file handle.
+    ///
+    /// Locks are also released when the file handle and all of its duplicates are closed.
+    /// On Windows, a file handle holding both a shared and an exclusive lock releases only
+    /// one of them.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the
+    /// `LOCK_UN` flag, and the `UnlockFile` function on Windows. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error of kind [`io::ErrorKind::Unsupported`] on platforms
+    /// that don't support file locking. On Windows, it returns an error if the file handle
+    /// holds no lock.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     f.lock()?;
+    ///     // update the files protected by the lock...
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 // In addition to the `impl`s here, `File` also has `impl`s for
@@ -754,102 +1277,446 @@ fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
         self.inner.read_buf(cursor)
     }
 
-    #[inline]
-    fn is_read_vectored(&self) -> bool {
-        self.inner.is_read_vectored()
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        self.inner.is_read_vectored()
+    }
+
+    // Reserves space in the buffer based on the file size when available.
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        buf.reserve(buffer_capacity_required(self));
+        io::default_read_to_end(self, buf)
+    }
+
+    // Reserves space in the buffer based on the file size when available.
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        buf.reserve(buffer_capacity_required(self));
+        io::default_read_to_string(self, buf)
+    }
+}
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Read for &File {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        self.inner.read_buf(cursor)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        self.inner.is_read_vectored()
+    }
+
+    // Reserves space in the buffer based on the file size when available.
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        buf.reserve(buffer_capacity_required(self));
+        io::default_read_to_end(self, buf)
+    }
+
+    // Reserves space in the buffer based on the file size when available.
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        buf.reserve(buffer_capacity_required(self));
+        io::default_read_to_string(self, buf)
+    }
+}
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Write for &File {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Seek for &File {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl AtomicFile {
+    /// Starts staging a file that is going to replace the file at `path`.
+    ///
+    /// The staged file is opened for reading and writing, and is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't end in a file name, or if the
+    /// temporary file can't be created in the directory of `path`.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<AtomicFile> {
+        let path = path.as_ref();
+        let dir = parent_dir(path)?;
+        if let Some(inner) = fs_imp::create_anonymous(dir, false)? {
+            return Ok(AtomicFile { file: File { inner }, path: path.to_owned(), temp: None });
+        }
+        let (file, temp) = with_temp_path(path, |temp| {
+            OpenOptions::new().read(true).write(true).create_new(true).open(temp)
+        })?;
+        Ok(AtomicFile { file, path: path.to_owned(), temp: Some(temp) })
+    }
+
+    /// Returns a reference to the staged file.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns a mutable reference to the staged file.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Replaces the file with the staged file, creating it if it doesn't exist.
+    ///
+    /// The staged file is synced to disk before it replaces the file, and the directory
+    /// containing them is synced afterwards, so that the replacement is durable once this
+    /// function returns.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if syncing or renaming the staged file fails, in
+    /// which case the file is left untouched and the staged file is removed.
+    ///
+    /// It will also return an error if syncing the directory fails. The file has already
+    /// been replaced then, but the replacement might not survive a crash.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn commit(mut self) -> io::Result<()> {
+        self.file.sync_all()?;
+        if self.temp.is_none() {
+            // An unnamed file can't be renamed, so it needs a temporary name first.
+            let file = &self.file.inner;
+            let ((), temp) = with_temp_path(&self.path, |temp| fs_imp::link_anonymous(file, temp))?;
+            self.temp = Some(temp);
+        }
+        fs_imp::rename(self.temp.as_ref().unwrap(), &self.path)?;
+        self.temp = None;
+        fs_imp::sync_dir(parent_dir(&self.path)?)
+    }
+
+    /// Moves the staged file into place, failing if the file exists.
+    ///
+    /// This is like [`commit`], except that an existing file is never replaced: when two
+    /// processes commit a new file concurrently, only one of them succeeds.
+    ///
+    /// [`commit`]: AtomicFile::commit
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of kind [`io::ErrorKind::AlreadyExists`] if the
+    /// file exists, in which case the staged file is removed.
+    ///
+    /// Like [`commit`], it will also return an error if syncing the directory fails, after
+    /// the file was created.
+    #[unstable(feature = "fs_atomic_write", issue = "none")]
+    pub fn commit_new(mut self) -> io::Result<()> {
+        self.file.sync_all()?;
+        match self.temp.take() {
+            Some(temp) => {
+                let result = fs_imp::rename_noreplace(&temp, &self.path);
+                if result.is_err() {
+                    self.temp = Some(temp);
+                }
+                result?;
+            }
+            None => fs_imp::link_anonymous(&self.file.inner, &self.path)?,
+        }
+        fs_imp::sync_dir(parent_dir(&self.path)?)
+    }
+}
+
+/// Returns the directory containing `path`, which has to end in a file name.
+fn parent_dir(path: &Path) -> io::Result<&Path> {
+    match path.parent() {
+        Some(dir) if path.file_name().is_some() => {
+            Ok(if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+        }
+        _ => Err(io::const_io_error!(io::ErrorKind::InvalidInput, "path has no file name")),
+    }
+}
+
+/// Calls `f` with a random temporary path next to `path`, until it doesn't fail because
+/// the path already exists.
+fn with_temp_path<T>(
+    path: &Path,
+    f: impl FnMut(&Path) -> io::Result<T>,
+) -> io::Result<(T, PathBuf)> {
+    let mut prefix = OsString::from(".");
+    prefix.push(path.file_name().unwrap());
+    prefix.push(".");
+    with_random_path(parent_dir(path)?, &prefix, ".tmp", f)
+}
+
+/// Calls `f` with random paths in `dir`, made of `prefix`, random digits and `suffix`,
+/// until it doesn't fail because the path already exists.
+fn with_random_path<T>(
+    dir: &Path,
+    prefix: &OsStr,
+    suffix: &str,
+    mut f: impl FnMut(&Path) -> io::Result<T>,
+) -> io::Result<(T, PathBuf)> {
+    const ATTEMPTS: usize = 16;
+
+    let mut attempt = 0;
+    loop {
+        let mut name = prefix.to_owned();
+        name.push(format!("{:016x}{suffix}", crate::sys::hashmap_random_keys().0));
+        let path = dir.join(name);
+        match f(&path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < ATTEMPTS => {
+                attempt += 1;
+            }
+            result => return result.map(|t| (t, path)),
+        }
     }
+}
 
-    // Reserves space in the buffer based on the file size when available.
-    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
-        buf.reserve(buffer_capacity_required(self));
-        io::default_read_to_end(self, buf)
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if let Some(temp) = &self.temp {
+            let _ = fs_imp::unlink(temp);
+        }
     }
+}
 
-    // Reserves space in the buffer based on the file size when available.
-    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
-        buf.reserve(buffer_capacity_required(self));
-        io::default_read_to_string(self, buf)
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Read for AtomicFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
     }
 }
-#[stable(feature = "rust1", since = "1.0.0")]
-impl Write for File {
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Write for AtomicFile {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        self.file.write(buf)
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.write_vectored(bufs)
+        self.file.write_vectored(bufs)
     }
 
     #[inline]
     fn is_write_vectored(&self) -> bool {
-        self.inner.is_write_vectored()
+        self.file.is_write_vectored()
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.flush()
+        self.file.flush()
     }
 }
-#[stable(feature = "rust1", since = "1.0.0")]
-impl Seek for File {
+
+#[unstable(feature = "fs_atomic_write", issue = "none")]
+impl Seek for AtomicFile {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        self.inner.seek(pos)
+        self.file.seek(pos)
     }
 }
-#[stable(feature = "rust1", since = "1.0.0")]
-impl Read for &File {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+
+const TEMP_PREFIX: &str = ".tmp";
+
+impl TempFile {
+    /// Creates a new temporary file in the directory returned by [`env::temp_dir`].
+    ///
+    /// The file is opened for reading and writing, and is empty.
+    ///
+    /// [`env::temp_dir`]: crate::env::temp_dir
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn new() -> io::Result<TempFile> {
+        TempFile::new_in(crate::env::temp_dir())
     }
 
-    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
-        self.inner.read_buf(cursor)
+    /// Creates a new temporary file in `dir`.
+    ///
+    /// The file is opened for reading and writing, and is empty.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempFile> {
+        let dir = dir.as_ref();
+        if let Some(inner) = fs_imp::create_anonymous(dir, true)? {
+            return Ok(TempFile { file: File { inner }, path: None, dir: dir.to_owned() });
+        }
+        let (inner, path) =
+            with_random_path(dir, OsStr::new(TEMP_PREFIX), "", fs_imp::create_private_file)?;
+        Ok(TempFile { file: File { inner }, path: Some(path), dir: dir.to_owned() })
     }
 
-    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.read_vectored(bufs)
+    /// Returns the path of the file, or `None` if it was created without a name.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
 
-    #[inline]
-    fn is_read_vectored(&self) -> bool {
-        self.inner.is_read_vectored()
+    /// Returns a reference to the file.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn as_file(&self) -> &File {
+        &self.file
     }
 
-    // Reserves space in the buffer based on the file size when available.
-    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
-        buf.reserve(buffer_capacity_required(self));
-        io::default_read_to_end(self, buf)
+    /// Returns a mutable reference to the file.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
     }
 
-    // Reserves space in the buffer based on the file size when available.
-    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
-        buf.reserve(buffer_capacity_required(self));
-        io::default_read_to_string(self, buf)
+    /// Keeps the file instead of removing it, and returns it along with its path.
+    ///
+    /// A file that was created without a name is given one first, in the directory it was
+    /// created in.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be given a name, in which case
+    /// it's removed.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn keep(mut self) -> io::Result<(File, PathBuf)> {
+        let path = match self.path.take() {
+            Some(path) => path,
+            None => {
+                let file = &self.file.inner;
+                let link = |path: &Path| fs_imp::link_anonymous(file, path);
+                with_random_path(&self.dir, OsStr::new(TEMP_PREFIX), "", link)?.1
+            }
+        };
+        let this = crate::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used nor dropped after its fields are moved out of it.
+        let (file, _dir) = unsafe { (crate::ptr::read(&this.file), crate::ptr::read(&this.dir)) };
+        Ok((file, path))
     }
 }
-#[stable(feature = "rust1", since = "1.0.0")]
-impl Write for &File {
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs_imp::unlink(path);
+        }
+    }
+}
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl Read for TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl Write for TempFile {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        self.file.write(buf)
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.write_vectored(bufs)
+        self.file.write_vectored(bufs)
     }
 
     #[inline]
     fn is_write_vectored(&self) -> bool {
-        self.inner.is_write_vectored()
+        self.file.is_write_vectored()
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.flush()
+        self.file.flush()
     }
 }
-#[stable(feature = "rust1", since = "1.0.0")]
-impl Seek for &File {
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl Seek for TempFile {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        self.inner.seek(pos)
+        self.file.seek(pos)
+    }
+}
+
+impl TempDir {
+    /// Creates a new temporary directory in the directory returned by [`env::temp_dir`].
+    ///
+    /// [`env::temp_dir`]: crate::env::temp_dir
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn new() -> io::Result<TempDir> {
+        TempDir::new_in(crate::env::temp_dir())
+    }
+
+    /// Creates a new temporary directory in `dir`.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempDir> {
+        let prefix = OsStr::new(TEMP_PREFIX);
+        let ((), path) = with_random_path(dir.as_ref(), prefix, "", fs_imp::create_private_dir)?;
+        Ok(TempDir { path })
+    }
+
+    /// Returns the path of the directory.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keeps the directory and its contents instead of removing them, and returns its path.
+    #[unstable(feature = "fs_temp", issue = "none")]
+    #[must_use = "the directory is kept, but its path is lost"]
+    pub fn keep(self) -> PathBuf {
+        let this = crate::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used nor dropped after its path is moved out of it.
+        unsafe { crate::ptr::read(&this.path) }
+    }
+}
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[unstable(feature = "fs_temp", issue = "none")]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.path);
     }
 }
 
@@ -1982,6 +2849,36 @@ pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
     fs_imp::copy(from.as_ref(), to.as_ref())
 }
 
+/// Copies a directory and all of its contents to another path.
+///
+/// This is a shortcut for <code>[CopyDir::new]\().[copy]\(from, to)</code>: files are cloned
+/// with reflinks where possible and copied otherwise, permissions are preserved, symlinks
+/// are copied as symlinks, and the copy fails if anything already exists at `to`.
+///
+/// [copy]: CopyDir::copy
+///
+/// # Errors
+///
+/// This function will return an error if `from` isn't a directory, if `to` is inside of
+/// `from`, or if any entry of `from` fails to be copied. In that case, the entries that
+/// were copied before the error are left at `to`.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_dir)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::copy_dir_all("template", "new-project")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_copy_dir", issue = "none")]
+pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    CopyDir::new().copy(from, to)
+}
+
 /// Creates a new hard link on the filesystem.
 ///
 /// The `link` path will be a link pointing to the `original` path. Note that
@@ -2373,6 +3270,39 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
     fs_imp::readdir(path.as_ref()).map(ReadDir)
 }
 
+/// Returns an iterator over the entries of a directory and of all of its subdirectories.
+///
+/// This is a shortcut for <code>[WalkDir::new]\().[walk]\(path)</code>: it doesn't follow
+/// symlinks, doesn't limit the depth of the walk, and lists the entries of each directory
+/// in the order the platform returns them.
+///
+/// [walk]: WalkDir::walk
+///
+/// # Errors
+///
+/// This function will return an error if `path` can't be opened as a directory, as with
+/// [`read_dir`]. Errors while walking the directory tree are returned by the iterator.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut size = 0;
+///     for entry in fs::walk_dir("target")? {
+///         size += entry?.metadata()?.len();
+///     }
+///     println!("target takes up {size} bytes");
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> io::Result<Walk> {
+    WalkDir::new().walk(path)
+}
+
 /// Changes the permissions found on a file or a directory.
 ///
 /// # Platform-specific behavior
@@ -2506,6 +3436,397 @@ fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
     }
 }
 
+impl WalkDir {
+    /// Creates a new set of options to walk a directory tree.
+    ///
+    /// By default, symlinks aren't followed, the depth of the walk isn't limited, and the
+    /// entries of each directory are listed in the order the platform returns them.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn new() -> WalkDir {
+        WalkDir { follow_symlinks: false, max_depth: usize::MAX, sort_by_file_name: false }
+    }
+
+    /// Sets whether the walk descends into symlinks to directories.
+    ///
+    /// Followed symlinks are still returned as symlinks by [`DirEntry::file_type`], with
+    /// their contents listed after them. A symlink to one of the directories being walked
+    /// would make the walk loop forever, so it's returned as an error of kind
+    /// [`io::ErrorKind::FilesystemLoop`] instead.
+    ///
+    /// The directory the walk starts in is always opened through symlinks.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn follow_symlinks(&mut self, follow: bool) -> &mut Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Sets the maximum depth of the entries returned by the walk.
+    ///
+    /// The entries of the directory the walk starts in have a depth of 1, their own entries
+    /// a depth of 2, and so on. A maximum depth of 1 lists the same entries as [`read_dir`].
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets whether the entries of each directory are sorted by their file name.
+    ///
+    /// Sorting reads all the entries of a directory before returning the first one.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by_file_name(&mut self, sort: bool) -> &mut Self {
+        self.sort_by_file_name = sort;
+        self
+    }
+
+    /// Starts walking the directory tree at `path` with the options in `self`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` can't be opened as a directory, as with
+    /// [`read_dir`]. Errors while walking the directory tree are returned by the iterator.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn walk<P: AsRef<Path>>(&self, path: P) -> io::Result<Walk> {
+        let path = path.as_ref();
+        let canonical = if self.follow_symlinks { Some(canonicalize(path)?) } else { None };
+        let root = WalkedDir { inner: fs_imp::OpenDir::open(path)?, sorted: None, canonical };
+        let dirs = if self.max_depth == 0 { Vec::new() } else { vec![root] };
+        Ok(Walk { options: self.clone(), dirs, next_dir: None })
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Default for WalkDir {
+    fn default() -> WalkDir {
+        WalkDir::new()
+    }
+}
+
+impl Walk {
+    /// Returns the depth of the entry that was returned last.
+    ///
+    /// The entries of the directory the walk started in have a depth of 1.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.dirs.len()
+    }
+
+    /// Skips the contents of the entry that was returned last, if it is a directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_walk_dir)]
+    /// use std::fs;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut walk = fs::walk_dir(".")?;
+    ///     while let Some(entry) = walk.next() {
+    ///         let entry = entry?;
+    ///         if entry.file_name() == ".git" {
+    ///             walk.skip_subtree();
+    ///         } else {
+    ///             println!("{}", entry.path().display());
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn skip_subtree(&mut self) {
+        self.next_dir = None;
+    }
+
+    /// Opens `entry` of the last directory being walked, if the walk descends into it.
+    fn open_dir(&self, entry: &fs_imp::DirEntry) -> io::Result<Option<WalkedDir>> {
+        let file_type = entry.file_type()?;
+        let is_dir = if file_type.is_symlink() && self.options.follow_symlinks {
+            // A broken symlink is just an entry, and isn't descended into.
+            metadata(entry.path()).map_or(false, |m| m.is_dir())
+        } else {
+            file_type.is_dir()
+        };
+        if !is_dir {
+            return Ok(None);
+        }
+
+        let canonical =
+            if self.options.follow_symlinks { Some(canonicalize(entry.path())?) } else { None };
+        if canonical.is_some() && self.dirs.iter().any(|dir| dir.canonical == canonical) {
+            return Err(io::const_io_error!(
+                io::ErrorKind::FilesystemLoop,
+                "symlink to a directory that contains it",
+            ));
+        }
+        let parent = &self.dirs.last().unwrap().inner;
+        let inner = parent.open_child(entry, self.options.follow_symlinks)?;
+        Ok(Some(WalkedDir { inner, sorted: None, canonical }))
+    }
+}
+
+impl WalkedDir {
+    fn next_entry(&mut self, sort: bool) -> Option<io::Result<fs_imp::DirEntry>> {
+        if !sort {
+            return self.inner.next();
+        }
+        let inner = &mut self.inner;
+        let sorted = self.sorted.get_or_insert_with(|| {
+            let mut entries: Vec<_> = inner.collect();
+            // Errors go first, so they are sorted too.
+            entries.sort_by(|a, b| match (a, b) {
+                (Ok(a), Ok(b)) => a.file_name().cmp(&b.file_name()),
+                (a, b) => a.is_ok().cmp(&b.is_ok()),
+            });
+            entries.into_iter()
+        });
+        sorted.next()
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Iterator for Walk {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        match self.next_dir.take() {
+            Some(Ok(dir)) => self.dirs.push(dir),
+            Some(Err(e)) => return Some(Err(e)),
+            None => {}
+        }
+        loop {
+            let dir = self.dirs.last_mut()?;
+            match dir.next_entry(self.options.sort_by_file_name) {
+                Some(Ok(entry)) => {
+                    if self.dirs.len() < self.options.max_depth {
+                        self.next_dir = self.open_dir(&entry).transpose();
+                    }
+                    return Some(Ok(DirEntry(entry)));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.dirs.pop();
+                }
+            }
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Walk")
+            .field("options", &self.options)
+            .field("depth", &self.dirs.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CopyDir {
+    /// Creates a new set of options to copy a directory tree.
+    ///
+    /// By default, files are cloned with reflinks where possible, permissions are
+    /// preserved but timestamps aren't, symlinks are copied as symlinks, and the copy fails
+    /// if a file or directory already exists at the destination.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    #[must_use]
+    pub fn new() -> CopyDir {
+        CopyDir {
+            reflink: Reflink::Auto,
+            preserve_permissions: true,
+            preserve_timestamps: false,
+            preserve_symlinks: true,
+            if_exists: IfExists::Fail,
+        }
+    }
+
+    /// Sets whether files are cloned with reflinks.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn reflink(&mut self, reflink: Reflink) -> &mut Self {
+        self.reflink = reflink;
+        self
+    }
+
+    /// Sets whether the copies get the permissions of the files and directories they are
+    /// copied from.
+    ///
+    /// Otherwise, they get the default permissions of new files and directories.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn preserve_permissions(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_permissions = preserve;
+        self
+    }
+
+    /// Sets whether the copies get the access and modification times of the files and
+    /// directories they are copied from.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn preserve_timestamps(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_timestamps = preserve;
+        self
+    }
+
+    /// Sets whether symlinks are copied as symlinks, with the same target.
+    ///
+    /// Otherwise, the files and directories that symlinks point to are copied in their
+    /// place. A symlink to a directory that contains it then makes the copy fail once the
+    /// paths get too long.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn preserve_symlinks(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_symlinks = preserve;
+        self
+    }
+
+    /// Sets what happens with files that already exist at the destination.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn if_exists(&mut self, if_exists: IfExists) -> &mut Self {
+        self.if_exists = if_exists;
+        self
+    }
+
+    /// Copies the directory `from` and all of its contents to `to`, with the options in
+    /// `self`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `from` isn't a directory, if `to` is inside of
+    /// `from`, if `from` contains a file that is neither a regular file, a directory nor a
+    /// symlink, or if any entry of `from` fails to be copied. In that case, the entries that
+    /// were copied before the error are left at `to`.
+    #[unstable(feature = "fs_copy_dir", issue = "none")]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<()> {
+        self._copy(from.as_ref(), to.as_ref())
+    }
+
+    fn _copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let metadata = metadata(from)?;
+        if !metadata.is_dir() {
+            return Err(io::const_io_error!(
+                io::ErrorKind::NotADirectory,
+                "the source path is not a directory",
+            ));
+        }
+        let from_canonical = canonicalize(from)?;
+        let to_canonical = match canonicalize(to) {
+            Ok(path) => path,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                canonicalize(parent_dir(to)?)?.join(to.file_name().unwrap())
+            }
+            Err(e) => return Err(e),
+        };
+        if to_canonical.starts_with(&from_canonical) {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "cannot copy a directory into itself",
+            ));
+        }
+        self.copy_dir(from, to, &metadata)
+    }
+
+    fn copy_dir(&self, from: &Path, to: &Path, metadata: &Metadata) -> io::Result<()> {
+        match create_dir(to) {
+            Ok(()) => {}
+            Err(e)
+                if e.kind() == io::ErrorKind::AlreadyExists
+                    && self.if_exists != IfExists::Fail
+                    && to.is_dir() => {}
+            Err(e) => return Err(e),
+        }
+
+        for entry in read_dir(from)? {
+            let entry = entry?;
+            let (from, to) = (entry.path(), to.join(entry.file_name()));
+            let metadata = if entry.file_type()?.is_symlink() {
+                if self.preserve_symlinks {
+                    self.create(&to, || fs_imp::copy_symlink(&from, &to))?;
+                    continue;
+                }
+                crate::fs::metadata(&from)?
+            } else {
+                entry.metadata()?
+            };
+            if metadata.is_dir() {
+                self.copy_dir(&from, &to, &metadata)?;
+            } else if metadata.is_file() {
+                self.copy_file(&from, &to, &metadata)?;
+            } else {
+                // Opening a FIFO would block, and devices and sockets can't be copied by
+                // reading them.
+                return Err(io::const_io_error!(
+                    io::ErrorKind::InvalidInput,
+                    "cannot copy a file that is not a regular file, directory or symlink",
+                ));
+            }
+        }
+
+        // The timestamps are only set once the entries are in place, as adding them changes
+        // the modification time, and the permissions last, in case they don't allow writing.
+        if self.preserve_timestamps {
+            fs_imp::set_dir_times(to, Self::file_times(metadata)?.0)?;
+        }
+        if self.preserve_permissions {
+            set_permissions(to, metadata.permissions())?;
+        }
+        Ok(())
+    }
+
+    fn copy_file(&self, from: &Path, to: &Path, metadata: &Metadata) -> io::Result<()> {
+        let mut reader = File::open(from)?;
+        let Some(mut writer) =
+            self.create(to, || OpenOptions::new().write(true).create_new(true).open(to))?
+        else {
+            return Ok(());
+        };
+
+        let cloned = match self.reflink {
+            Reflink::Always => {
+                fs_imp::reflink(&reader.inner, &writer.inner)?;
+                true
+            }
+            Reflink::Auto => fs_imp::reflink(&reader.inner, &writer.inner).is_ok(),
+            Reflink::Never => false,
+        };
+        if !cloned {
+            io::copy(&mut reader, &mut writer)?;
+        }
+
+        if self.preserve_timestamps {
+            writer.set_times(Self::file_times(metadata)?)?;
+        }
+        if self.preserve_permissions {
+            writer.set_permissions(metadata.permissions())?;
+        }
+        Ok(())
+    }
+
+    /// Creates the file or symlink `to` with `create`, following the `if_exists` policy if
+    /// it already exists. Returns `None` if the existing file is kept.
+    fn create<T>(&self, to: &Path, create: impl Fn() -> io::Result<T>) -> io::Result<Option<T>> {
+        match create() {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => match self.if_exists {
+                IfExists::Fail => Err(e),
+                IfExists::Skip => Ok(None),
+                IfExists::Overwrite => {
+                    remove_file(to)?;
+                    create().map(Some)
+                }
+            },
+            result => result.map(Some),
+        }
+    }
+
+    fn file_times(metadata: &Metadata) -> io::Result<FileTimes> {
+        Ok(FileTimes::new().set_accessed(metadata.accessed()?).set_modified(metadata.modified()?))
+    }
+}
+
+#[unstable(feature = "fs_copy_dir", issue = "none")]
+impl Default for CopyDir {
+    fn default() -> CopyDir {
+        CopyDir::new()
+    }
+}
+
 /// Returns `Ok(true)` if the path points at an existing entity.
 ///
 /// This function will traverse symbolic links to query information about the
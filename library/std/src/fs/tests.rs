@@ -8,7 +8,7 @@
 use crate::sync::Arc;
 use crate::sys_common::io::test::{tmpdir, TempDir};
 use crate::thread;
-use crate::time::{Duration, Instant};
+use crate::time::{Duration, Instant, SystemTime};
 
 use rand::RngCore;
 
@@ -1233,6 +1233,141 @@ fn file_try_clone() {
     check!(f1.write_all(b"!"));
 }
 
+#[test]
+#[cfg(any(
+    windows,
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn file_lock_multiple_shared() {
+    let tmpdir = tmpdir();
+    let filename = &tmpdir.join("file_lock_multiple_shared_test.txt");
+    let f1 = check!(File::create(filename));
+    let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+    check!(f1.lock_shared());
+    check!(f2.lock_shared());
+    check!(f1.unlock());
+    check!(f2.unlock());
+    assert!(check!(f1.try_lock_shared()));
+    assert!(check!(f2.try_lock_shared()));
+}
+
+#[test]
+#[cfg(any(
+    windows,
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn file_lock_exclusive() {
+    let tmpdir = tmpdir();
+    let filename = &tmpdir.join("file_lock_exclusive_test.txt");
+    let f1 = check!(File::create(filename));
+    let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+    check!(f1.lock());
+    assert!(!check!(f2.try_lock()));
+    assert!(!check!(f2.try_lock_shared()));
+    check!(f1.unlock());
+    assert!(check!(f2.try_lock()));
+    assert!(!check!(f1.try_lock_shared()));
+}
+
+#[test]
+#[cfg(any(
+    windows,
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn file_lock_blocking() {
+    let tmpdir = tmpdir();
+    let filename = &tmpdir.join("file_lock_blocking_test.txt");
+    let f1 = check!(File::create(filename));
+    let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+    check!(f1.lock_shared());
+    let t = thread::spawn(move || {
+        check!(f2.lock());
+        f2
+    });
+    thread::sleep(Duration::from_millis(100));
+    assert!(!t.is_finished());
+    // Releasing the lock lets the other thread acquire it.
+    drop(f1);
+    let f2 = t.join().unwrap();
+    check!(f2.unlock());
+}
+
+#[test]
+#[cfg(windows)]
+fn file_try_lock_overlapped() {
+    use crate::os::windows::fs::OpenOptionsExt;
+
+    const FILE_FLAG_OVERLAPPED: u32 = 0x40000000;
+
+    let tmpdir = tmpdir();
+    let filename = &tmpdir.join("file_try_lock_overlapped_test.txt");
+    let f1 = check!(File::create(filename));
+    let f2 =
+        check!(OpenOptions::new().write(true).custom_flags(FILE_FLAG_OVERLAPPED).open(filename));
+
+    check!(f1.lock());
+    assert!(!check!(f2.try_lock()));
+    check!(f1.unlock());
+    assert!(check!(f2.try_lock()));
+    check!(f2.unlock());
+}
+
+#[test]
+#[cfg(any(target_os = "illumos", target_os = "solaris"))]
+fn file_lock_fcntl() {
+    let tmpdir = tmpdir();
+    let filename = &tmpdir.join("file_lock_fcntl_test.txt");
+    let f = check!(OpenOptions::new().read(true).write(true).create(true).open(filename));
+
+    // Record locks never conflict within a process, so only check that they can be taken.
+    check!(f.lock());
+    check!(f.lock_shared());
+    assert!(check!(f.try_lock()));
+    check!(f.unlock());
+}
+
+#[test]
+#[cfg(not(any(
+    windows,
+    target_os = "aix",
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "emscripten",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "haiku",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "nto",
+    target_os = "openbsd",
+    target_os = "solaris",
+    target_os = "watchos",
+)))]
+fn file_lock_unsupported() {
+    let tmpdir = tmpdir();
+    let f = check!(File::create(&tmpdir.join("file_lock_unsupported_test.txt")));
+    assert_eq!(f.lock().unwrap_err().kind(), ErrorKind::Unsupported);
+    assert_eq!(f.try_lock().unwrap_err().kind(), ErrorKind::Unsupported);
+}
+
 #[test]
 #[cfg(not(windows))]
 fn unlink_readonly() {
@@ -1359,6 +1494,323 @@ fn read_dir_not_found() {
     assert_eq!(res.err().unwrap().kind(), ErrorKind::NotFound);
 }
 
+#[test]
+fn walk_dir_order_and_depth() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(File::create(tmpdir.join("a/b/c")));
+    check!(File::create(tmpdir.join("a/d")));
+    check!(fs::create_dir(tmpdir.join("e")));
+
+    let mut walk = check!(fs::WalkDir::new().sort_by_file_name(true).walk(tmpdir.path()));
+    let mut entries = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        let path = entry.path().strip_prefix(tmpdir.path()).unwrap().to_path_buf();
+        entries.push((path, walk.depth()));
+    }
+    let expected = [("a", 1), ("a/b", 2), ("a/b/c", 3), ("a/d", 2), ("e", 1)];
+    let expected: Vec<_> = expected.iter().map(|&(p, d)| (Path::new(p).to_path_buf(), d)).collect();
+    assert_eq!(entries, expected);
+
+    // Unsorted walks list the same entries.
+    let mut unsorted: Vec<_> =
+        check!(fs::walk_dir(tmpdir.path())).map(|entry| check!(entry).path()).collect();
+    unsorted.sort();
+    let mut sorted: Vec<_> = expected.iter().map(|(p, _)| tmpdir.join(p)).collect();
+    sorted.sort();
+    assert_eq!(unsorted, sorted);
+}
+
+#[test]
+fn walk_dir_max_depth() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b/c")));
+
+    let count = |depth| check!(fs::WalkDir::new().max_depth(depth).walk(tmpdir.path())).count();
+    assert_eq!(count(0), 0);
+    assert_eq!(count(1), 1);
+    assert_eq!(count(2), 2);
+    assert_eq!(count(usize::MAX), 3);
+}
+
+#[test]
+fn walk_dir_skip_subtree() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(fs::create_dir_all(tmpdir.join("c/d")));
+
+    let mut walk = check!(fs::WalkDir::new().sort_by_file_name(true).walk(tmpdir.path()));
+    let mut names = Vec::new();
+    while let Some(entry) = walk.next() {
+        let name = check!(entry).file_name();
+        if name == "a" {
+            walk.skip_subtree();
+        }
+        names.push(name);
+    }
+    assert_eq!(names, ["a", "c", "d"]);
+}
+
+#[test]
+fn walk_dir_symlinks() {
+    let tmpdir = tmpdir();
+    if !got_symlink_permission(&tmpdir) {
+        return;
+    };
+    check!(fs::create_dir_all(tmpdir.join("a/b")));
+    check!(symlink_dir(tmpdir.join("a"), tmpdir.join("a/b/loop")));
+    check!(symlink_dir(tmpdir.join("a/b"), tmpdir.join("link")));
+
+    // Symlinks are returned, but not descended into, by default.
+    let paths: Vec<_> =
+        check!(fs::walk_dir(tmpdir.path())).map(|entry| check!(entry).path()).collect();
+    assert_eq!(paths.len(), 4);
+    assert!(paths.contains(&tmpdir.join("a/b/loop")));
+    assert!(paths.contains(&tmpdir.join("link")));
+
+    // Following them descends into `link/loop`, but not into `a/b/loop` nor into
+    // `link/loop/b`, which lead back to a directory being walked.
+    let mut walk = check!(fs::WalkDir::new().follow_symlinks(true).walk(tmpdir.path()));
+    let mut loops = 0;
+    let mut paths = Vec::new();
+    while let Some(entry) = walk.next() {
+        match entry {
+            Ok(entry) => paths.push(entry.path()),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::FilesystemLoop);
+                loops += 1;
+            }
+        }
+    }
+    assert!(paths.contains(&tmpdir.join("link/loop")));
+    assert!(paths.contains(&tmpdir.join("link/loop/b")));
+    assert!(loops >= 2);
+}
+
+#[test]
+fn write_atomic_replaces() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("file");
+    check!(fs::write_atomic(&path, "first"));
+    assert_eq!(check!(fs::read_to_string(&path)), "first");
+    check!(fs::write_atomic(&path, "second"));
+    assert_eq!(check!(fs::read_to_string(&path)), "second");
+    // No temporary file is left behind.
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+fn atomic_file_drop_without_commit() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("file");
+    check!(fs::write(&path, "old"));
+
+    let mut file = check!(fs::AtomicFile::create(&path));
+    check!(file.write_all(b"new"));
+    assert_eq!(check!(fs::read_to_string(&path)), "old");
+    drop(file);
+    assert_eq!(check!(fs::read_to_string(&path)), "old");
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+fn atomic_file_commit_new() {
+    let tmpdir = tmpdir();
+    let path = tmpdir.join("file");
+
+    let mut file = check!(fs::AtomicFile::create(&path));
+    check!(file.write_all(b"first"));
+    check!(file.commit_new());
+    assert_eq!(check!(fs::read_to_string(&path)), "first");
+
+    let mut file = check!(fs::AtomicFile::create(&path));
+    check!(file.write_all(b"second"));
+    assert_eq!(file.commit_new().unwrap_err().kind(), ErrorKind::AlreadyExists);
+    assert_eq!(check!(fs::read_to_string(&path)), "first");
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+fn atomic_file_no_file_name() {
+    let tmpdir = tmpdir();
+    let err = fs::AtomicFile::create(tmpdir.join("..")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn copy_dir_all_tree() {
+    let tmpdir = tmpdir();
+    let from = tmpdir.join("from");
+    let to = tmpdir.join("to");
+    check!(fs::create_dir_all(from.join("a/b")));
+    check!(fs::write(from.join("a/b/file"), "contents"));
+    check!(fs::write(from.join("top"), "top"));
+
+    check!(fs::copy_dir_all(&from, &to));
+    assert_eq!(check!(fs::read_to_string(to.join("a/b/file"))), "contents");
+    assert_eq!(check!(fs::read_to_string(to.join("top"))), "top");
+    assert_eq!(check!(fs::read_dir(&to)).count(), 2);
+
+    // The destination exists now.
+    assert_eq!(fs::copy_dir_all(&from, &to).unwrap_err().kind(), ErrorKind::AlreadyExists);
+    // A directory can't be copied into itself.
+    assert_eq!(
+        fs::copy_dir_all(&from, from.join("a/copy")).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn copy_dir_if_exists() {
+    let tmpdir = tmpdir();
+    let from = tmpdir.join("from");
+    let to = tmpdir.join("to");
+    check!(fs::create_dir_all(&from));
+    check!(fs::create_dir_all(&to));
+    check!(fs::write(from.join("file"), "new"));
+    check!(fs::write(from.join("other"), "other"));
+    check!(fs::write(to.join("file"), "old"));
+
+    check!(fs::CopyDir::new().if_exists(fs::IfExists::Skip).copy(&from, &to));
+    assert_eq!(check!(fs::read_to_string(to.join("file"))), "old");
+    assert_eq!(check!(fs::read_to_string(to.join("other"))), "other");
+
+    check!(fs::CopyDir::new().if_exists(fs::IfExists::Overwrite).copy(&from, &to));
+    assert_eq!(check!(fs::read_to_string(to.join("file"))), "new");
+}
+
+#[test]
+fn copy_dir_options() {
+    let tmpdir = tmpdir();
+    let from = tmpdir.join("from");
+    let to = tmpdir.join("to");
+    check!(fs::create_dir_all(&from));
+    check!(fs::write(from.join("file"), "contents"));
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let times = fs::FileTimes::new().set_accessed(modified).set_modified(modified);
+    check!(check!(OpenOptions::new().write(true).open(from.join("file"))).set_times(times));
+    let mut perm = check!(fs::metadata(from.join("file"))).permissions();
+    perm.set_readonly(true);
+    check!(fs::set_permissions(from.join("file"), perm));
+
+    check!(fs::CopyDir::new()
+        .reflink(fs::Reflink::Never)
+        .preserve_timestamps(true)
+        .copy(&from, &to));
+    let metadata = check!(fs::metadata(to.join("file")));
+    assert_eq!(check!(fs::read_to_string(to.join("file"))), "contents");
+    assert!(metadata.permissions().readonly());
+    assert_eq!(check!(metadata.modified()), modified);
+
+    for path in [from.join("file"), to.join("file")] {
+        let mut perm = check!(fs::metadata(&path)).permissions();
+        perm.set_readonly(false);
+        check!(fs::set_permissions(&path, perm));
+    }
+}
+
+#[test]
+fn copy_dir_symlinks() {
+    let tmpdir = tmpdir();
+    if !got_symlink_permission(&tmpdir) {
+        return;
+    };
+    let from = tmpdir.join("from");
+    check!(fs::create_dir_all(from.join("dir")));
+    check!(fs::write(from.join("dir/file"), "contents"));
+    check!(symlink_dir("dir", from.join("link")));
+
+    let to = tmpdir.join("to");
+    check!(fs::copy_dir_all(&from, &to));
+    assert!(check!(fs::symlink_metadata(to.join("link"))).file_type().is_symlink());
+    assert_eq!(check!(fs::read_link(to.join("link"))), Path::new("dir"));
+
+    let to = tmpdir.join("followed");
+    check!(fs::CopyDir::new().preserve_symlinks(false).copy(&from, &to));
+    assert!(check!(fs::symlink_metadata(to.join("link"))).is_dir());
+    assert_eq!(check!(fs::read_to_string(to.join("link/file"))), "contents");
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux", target_os = "macos"))]
+fn copy_dir_special_file() {
+    use crate::os::unix::net::UnixListener;
+
+    let tmpdir = tmpdir();
+    let from = tmpdir.join("from");
+    check!(fs::create_dir_all(&from));
+    let _listener = check!(UnixListener::bind(from.join("socket")));
+
+    let err = fs::copy_dir_all(&from, tmpdir.join("to")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn temp_file_removed_on_drop() {
+    let tmpdir = tmpdir();
+    let mut file = check!(fs::TempFile::new_in(tmpdir.path()));
+    check!(file.write_all(b"contents"));
+    check!(file.rewind());
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "contents");
+    if let Some(path) = file.path() {
+        assert!(path.starts_with(tmpdir.path()));
+        assert!(path.exists());
+    }
+    drop(file);
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 0);
+}
+
+#[test]
+fn temp_file_keep() {
+    let tmpdir = tmpdir();
+    let mut file = check!(fs::TempFile::new_in(tmpdir.path()));
+    check!(file.write_all(b"kept"));
+    let (_file, path) = check!(file.keep());
+    assert!(path.starts_with(tmpdir.path()));
+    assert_eq!(check!(fs::read_to_string(&path)), "kept");
+    assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+}
+
+#[test]
+fn temp_dir_unique() {
+    let tmpdir = tmpdir();
+    let dirs: Vec<_> =
+        (0..10).map(|_| check!(fs::TempDir::new_in(tmpdir.path())).keep()).collect();
+    for (i, a) in dirs.iter().enumerate() {
+        assert!(dirs[i + 1..].iter().all(|b| a != b));
+    }
+}
+
+#[test]
+fn temp_dir_removed_on_drop() {
+    let tmpdir = tmpdir();
+    let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+    let path = dir.path().to_owned();
+    assert!(path.is_dir());
+    check!(fs::create_dir(path.join("sub")));
+    check!(fs::write(path.join("sub/file"), "contents"));
+    drop(dir);
+    assert!(!path.exists());
+
+    let path = check!(fs::TempDir::new_in(tmpdir.path())).keep();
+    assert!(path.is_dir());
+}
+
+#[test]
+#[cfg(unix)]
+fn temp_dir_private() {
+    use crate::os::unix::fs::PermissionsExt;
+
+    let tmpdir = tmpdir();
+    let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+    assert_eq!(check!(dir.path().metadata()).permissions().mode() & 0o777, 0o700);
+    let file = check!(fs::TempFile::new_in(tmpdir.path()));
+    assert_eq!(check!(file.as_file().metadata()).permissions().mode() & 0o777, 0o600);
+}
+
 #[test]
 fn file_open_not_found() {
     let res = File::open("/path/that/does/not/exist");
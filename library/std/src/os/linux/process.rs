@@ -10,7 +10,10 @@
 use crate::sealed::Sealed;
 #[cfg(not(doc))]
 use crate::sys::fd::FileDesc;
+#[cfg(not(doc))]
+use crate::sys::process::pidfd;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::time::Duration;
 
 #[cfg(doc)]
 struct FileDesc;
@@ -19,7 +22,12 @@
 ///
 /// A `PidFd` can be obtained by setting the corresponding option on [`Command`]
 /// with [`create_pidfd`]. Subsequently, the created pidfd can be retrieved
-/// from the [`Child`] by calling [`pidfd`] or [`take_pidfd`].
+/// from the [`Child`] by calling [`pidfd`] or [`take_pidfd`]. A `PidFd` for
+/// any existing process can be obtained with [`PidFd::open`].
+///
+/// Unlike a process ID, a `PidFd` keeps referring to the same process after
+/// it exited, so signalling or waiting on it can't accidentally affect an
+/// unrelated process that reused the ID.
 ///
 /// Example:
 /// ```no_run
@@ -51,6 +59,96 @@ pub struct PidFd {
     inner: FileDesc,
 }
 
+impl PidFd {
+    /// Opens a `PidFd` referring to the process with the given ID.
+    ///
+    /// The process ID is only resolved once, when the `PidFd` is created. If
+    /// the process already exited and its ID was reused, this refers to the
+    /// new process, so the ID should come from a source that rules this out,
+    /// such as an unreaped child of the calling process.
+    ///
+    /// This requires Linux 5.3 or later.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd)]
+    /// use std::os::linux::process::PidFd;
+    ///
+    /// let pidfd = PidFd::open(1234)?;
+    /// pidfd.kill()?;
+    /// # std::io::Result::Ok(())
+    /// ```
+    pub fn open(pid: u32) -> Result<PidFd> {
+        pidfd::open(pid as libc::pid_t).map(PidFd::from_inner)
+    }
+
+    /// Forces the process to exit, by sending it `SIGKILL`.
+    ///
+    /// This is equivalent to [`send_signal`] with `SIGKILL`.
+    ///
+    /// [`send_signal`]: PidFd::send_signal
+    pub fn kill(&self) -> Result<()> {
+        self.send_signal(libc::SIGKILL)
+    }
+
+    /// Sends the signal `signal` to the process.
+    ///
+    /// This fails with an error of kind [`PermissionDenied`] if the caller
+    /// isn't allowed to signal the process. Signalling a process that exited
+    /// but wasn't reaped yet succeeds without doing anything, and signalling
+    /// a process that was reaped fails with `ESRCH`.
+    ///
+    /// Refer to the man page of [`pidfd_send_signal(2)`] for further details.
+    ///
+    /// [`PermissionDenied`]: crate::io::ErrorKind::PermissionDenied
+    /// [`pidfd_send_signal(2)`]: https://man7.org/linux/man-pages/man2/pidfd_send_signal.2.html
+    pub fn send_signal(&self, signal: i32) -> Result<()> {
+        pidfd::send_signal(self.as_inner(), signal)
+    }
+
+    /// Waits for the process to exit, and reaps it.
+    ///
+    /// The process must be a child of the calling process. If it was spawned
+    /// with [`Command`], the corresponding [`Child`] can't be waited on
+    /// afterwards, and will report an error from [`wait`] and [`try_wait`].
+    ///
+    /// Waiting on a `PidFd` requires Linux 5.4 or later. On older kernels,
+    /// this fails with an error of kind [`Unsupported`].
+    ///
+    /// [`Unsupported`]: crate::io::ErrorKind::Unsupported
+    /// [`Command`]: process::Command
+    /// [`Child`]: process::Child
+    /// [`wait`]: process::Child::wait
+    /// [`try_wait`]: process::Child::try_wait
+    pub fn wait(&self) -> Result<process::ExitStatus> {
+        pidfd::wait(self.as_inner()).map(process::ExitStatus::from_inner)
+    }
+
+    /// Reaps the process if it has exited, without blocking.
+    ///
+    /// Returns `Ok(None)` if the process is still running. Like [`wait`],
+    /// this requires the process to be a child of the calling process, and
+    /// Linux 5.4 or later.
+    ///
+    /// [`wait`]: PidFd::wait
+    pub fn try_wait(&self) -> Result<Option<process::ExitStatus>> {
+        Ok(pidfd::try_wait(self.as_inner())?.map(process::ExitStatus::from_inner))
+    }
+
+    /// Waits at most `timeout` for the process to exit, and reaps it if it
+    /// did.
+    ///
+    /// Returns `Ok(None)` if the process is still running after `timeout`.
+    /// Like [`wait`], this requires the process to be a child of the calling
+    /// process, and Linux 5.4 or later.
+    ///
+    /// [`wait`]: PidFd::wait
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<Option<process::ExitStatus>> {
+        Ok(pidfd::wait_timeout(self.as_inner(), timeout)?.map(process::ExitStatus::from_inner))
+    }
+}
+
 impl AsInner<FileDesc> for PidFd {
     fn as_inner(&self) -> &FileDesc {
         &self.inner
@@ -155,6 +253,37 @@ pub trait CommandExt: Sealed {
     /// [`pidfd`]: fn@ChildExt::pidfd
     /// [`take_pidfd`]: ChildExt::take_pidfd
     fn create_pidfd(&mut self, val: bool) -> &mut process::Command;
+
+    /// Sets the signal that the child process gets when its parent exits,
+    /// using `PR_SET_PDEATHSIG`.
+    ///
+    /// The "parent" here is the thread that spawned the child process, so
+    /// the signal is sent as soon as that thread exits, even if the rest of
+    /// the process keeps running. If the parent already exited before the
+    /// child process could set this up, the child process raises the signal
+    /// itself. This is cleared when the child process executes a set-user-ID
+    /// or set-group-ID program.
+    ///
+    /// Refer to the man page of [`prctl(2)`] for further details.
+    ///
+    /// [`prctl(2)`]: https://man7.org/linux/man-pages/man2/prctl.2.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd, process_spawn_controls)]
+    /// # #![feature(rustc_private)]
+    /// extern crate libc;
+    /// use std::os::linux::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// // Don't leave the worker behind if we crash.
+    /// Command::new("worker").parent_death_signal(libc::SIGKILL).spawn()?;
+    /// #
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn parent_death_signal(&mut self, signal: i32) -> &mut process::Command;
 }
 
 impl CommandExt for process::Command {
@@ -162,4 +291,9 @@ fn create_pidfd(&mut self, val: bool) -> &mut process::Command {
         self.as_inner_mut().create_pidfd(val);
         self
     }
+
+    fn parent_death_signal(&mut self, signal: i32) -> &mut process::Command {
+        self.as_inner_mut().pdeathsig(signal);
+        self
+    }
 }
@@ -187,6 +187,97 @@ fn arg0<S>(&mut self, arg: S) -> &mut process::Command
     /// ```
     #[stable(feature = "process_set_process_group", since = "1.64.0")]
     fn process_group(&mut self, pgroup: i32) -> &mut process::Command;
+
+    /// Sets whether the child process starts a new session. Equivalent to a
+    /// `setsid` call in the child process, but may be more efficient.
+    ///
+    /// The child process becomes the leader of a new session and of a new
+    /// process group, and has no controlling terminal. This is commonly used
+    /// to detach daemons from the terminal they were started from.
+    ///
+    /// Starting a new session fails if the child process also gets a
+    /// [`process_group`] other than its own.
+    ///
+    /// [`process_group`]: CommandExt::process_group
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_spawn_controls)]
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// Command::new("sleep").arg("10").setsid(true).spawn()?;
+    /// #
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    // FIXME(process_spawn_controls): open a tracking issue and use it instead of `issue = "none"`.
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command;
+
+    /// Sets whether the signal mask of the child process is cleared, so that
+    /// no signals are blocked when it starts.
+    ///
+    /// By default, the child process inherits the signal mask of the thread
+    /// that spawns it.
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn reset_signal_mask(&mut self, reset: bool) -> &mut process::Command;
+
+    /// Sets whether all signal dispositions of the child process are reset
+    /// to their defaults.
+    ///
+    /// Signals that are handled in the parent process are always reset when
+    /// the child process executes its program, but signals that are ignored
+    /// stay ignored by default. Except for `SIGPIPE`, which is reset unless
+    /// the program uses `#[unix_sigpipe]`.
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn reset_signal_dispositions(&mut self, reset: bool) -> &mut process::Command;
+
+    /// Sets a resource limit of the child process. Equivalent to a
+    /// `setrlimit` call in the child process.
+    ///
+    /// `resource` is one of the `RLIMIT_*` constants, and `soft` and `hard`
+    /// are the soft and hard limits. `u64::MAX` means no limit. Setting a
+    /// limit again for the same resource replaces the earlier one.
+    ///
+    /// Raising a hard limit requires privileges. The limits are set before
+    /// changing the [`uid`] or [`gid`] of the child process.
+    ///
+    /// [`uid`]: CommandExt::uid
+    /// [`gid`]: CommandExt::gid
+    ///
+    /// # Examples
+    ///
+    /// Forbid a child process from dumping core:
+    ///
+    /// ```no_run
+    /// #![feature(process_spawn_controls)]
+    /// # #![feature(rustc_private)]
+    /// extern crate libc;
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// Command::new("untrusted").rlimit(libc::RLIMIT_CORE as i32, 0, 0).spawn()?;
+    /// #
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn rlimit(&mut self, resource: i32, soft: u64, hard: u64) -> &mut process::Command;
+
+    /// Closes all file descriptors greater than `fd` in the child process,
+    /// so that it doesn't inherit any that were opened without `O_CLOEXEC`,
+    /// possibly by other libraries.
+    ///
+    /// Standard input, output and error are never closed. The descriptors
+    /// are closed before calling the [`pre_exec`] closures, which can still
+    /// open new ones.
+    ///
+    /// On platforms that can't close a range of descriptors at once, this
+    /// closes every possible descriptor below the `RLIMIT_NOFILE` limit.
+    ///
+    /// [`pre_exec`]: CommandExt::pre_exec
+    #[unstable(feature = "process_spawn_controls", issue = "none")]
+    fn close_fds_above(&mut self, fd: RawFd) -> &mut process::Command;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -232,6 +323,31 @@ fn process_group(&mut self, pgroup: i32) -> &mut process::Command {
         self.as_inner_mut().pgroup(pgroup);
         self
     }
+
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command {
+        self.as_inner_mut().setsid(setsid);
+        self
+    }
+
+    fn reset_signal_mask(&mut self, reset: bool) -> &mut process::Command {
+        self.as_inner_mut().reset_sigmask(reset);
+        self
+    }
+
+    fn reset_signal_dispositions(&mut self, reset: bool) -> &mut process::Command {
+        self.as_inner_mut().reset_sigdispositions(reset);
+        self
+    }
+
+    fn rlimit(&mut self, resource: i32, soft: u64, hard: u64) -> &mut process::Command {
+        self.as_inner_mut().rlimit(resource, soft, hard);
+        self
+    }
+
+    fn close_fds_above(&mut self, fd: RawFd) -> &mut process::Command {
+        self.as_inner_mut().close_fds_above(fd);
+        self
+    }
 }
 
 /// Unix-specific extensions to [`process::ExitStatus`] and
@@ -40,6 +40,9 @@
 #[unstable(feature = "panic_update_hook", issue = "92649")]
 pub use crate::panicking::update_hook;
 
+#[unstable(feature = "panic_add_hook", issue = "none")]
+pub use crate::panicking::{add_hook, remove_hook, PanicEvent, PanicHookId};
+
 #[stable(feature = "panic_hooks", since = "1.10.0")]
 pub use core::panic::{Location, PanicInfo};
 
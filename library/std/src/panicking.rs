@@ -13,6 +13,8 @@
 use core::panic::{BoxMeUp, Location, PanicInfo};
 
 use crate::any::Any;
+use crate::backtrace::Backtrace;
+use crate::cell::OnceCell;
 use crate::fmt;
 use crate::intrinsics;
 use crate::mem::{self, ManuallyDrop};
@@ -236,6 +238,205 @@ pub fn update_hook<F>(hook_fn: F)
     *hook = Hook::Custom(Box::new(move |info| hook_fn(&prev, info)));
 }
 
+type AddedHook = Box<dyn Fn(&PanicEvent<'_>) + 'static + Sync + Send>;
+
+struct AddedHooks {
+    next_id: u64,
+    hooks: Vec<(PanicHookId, AddedHook)>,
+}
+
+static ADDED_HOOKS: RwLock<AddedHooks> = RwLock::new(AddedHooks { next_id: 0, hooks: Vec::new() });
+
+/// An identifier of a panic hook registered with [`add_hook`], to unregister
+/// it with [`remove_hook`].
+// FIXME(panic_add_hook): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "panic_add_hook", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PanicHookId(u64);
+
+/// Registers an additional panic hook, which runs alongside the others.
+///
+/// Unlike with [`set_hook`], this does not replace the hook set with
+/// [`set_hook`] (or the default hook, which prints the panic message). Hooks
+/// registered with this function run after that hook, in the order in which
+/// they were registered, and are provided with a [`PanicEvent`] describing
+/// the panic.
+///
+/// This allows independent libraries, like a telemetry library and a test
+/// framework, to each observe panics without interfering with each other.
+///
+/// The returned identifier can be used to unregister the hook again with
+/// [`remove_hook`].
+///
+/// # Panics
+///
+/// Panics if called from a panicking thread.
+///
+/// # Examples
+///
+/// The following will print the message of the panic after the normal output
+/// of the panic:
+///
+/// ```should_panic
+/// #![feature(panic_add_hook)]
+/// use std::panic;
+///
+/// panic::add_hook(Box::new(|event| {
+///     eprintln!("reporting panic: {} at {}", event.message(), event.location());
+/// }));
+///
+/// panic!("Normal panic");
+/// ```
+#[unstable(feature = "panic_add_hook", issue = "none")]
+pub fn add_hook(hook: Box<dyn Fn(&PanicEvent<'_>) + 'static + Sync + Send>) -> PanicHookId {
+    if thread::panicking() {
+        panic!("cannot modify the panic hook from a panicking thread");
+    }
+
+    let mut hooks = ADDED_HOOKS.write().unwrap_or_else(PoisonError::into_inner);
+    let id = PanicHookId(hooks.next_id);
+    hooks.next_id += 1;
+    hooks.hooks.push((id, hook));
+    id
+}
+
+/// Unregisters a panic hook registered with [`add_hook`].
+///
+/// Returns whether the hook was still registered.
+///
+/// # Panics
+///
+/// Panics if called from a panicking thread.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(panic_add_hook)]
+/// use std::panic;
+///
+/// let id = panic::add_hook(Box::new(|_| println!("Custom panic hook")));
+/// assert!(panic::remove_hook(id));
+/// assert!(!panic::remove_hook(id));
+/// ```
+#[unstable(feature = "panic_add_hook", issue = "none")]
+pub fn remove_hook(id: PanicHookId) -> bool {
+    if thread::panicking() {
+        panic!("cannot modify the panic hook from a panicking thread");
+    }
+
+    let mut hooks = ADDED_HOOKS.write().unwrap_or_else(PoisonError::into_inner);
+    let old =
+        hooks.hooks.iter().position(|(hook_id, _)| *hook_id == id).map(|i| hooks.hooks.remove(i));
+    drop(hooks);
+    // Only drop the old hook after releasing the lock to avoid deadlocking
+    // if its destructor panics.
+    old.is_some()
+}
+
+/// A description of a panic, passed to the hooks registered with
+/// [`add_hook`].
+#[unstable(feature = "panic_add_hook", issue = "none")]
+pub struct PanicEvent<'a> {
+    info: &'a PanicInfo<'a>,
+    thread: Option<thread::Thread>,
+    double_panic: bool,
+    backtrace: OnceCell<Backtrace>,
+}
+
+impl<'a> PanicEvent<'a> {
+    /// Returns the [`PanicInfo`] of the panic, which also gives access to the
+    /// panic payload.
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn info(&self) -> &PanicInfo<'a> {
+        self.info
+    }
+
+    /// Returns the message of the panic.
+    ///
+    /// This is the payload of the panic if it is a string, like for panics
+    /// created with [`panic!`]. Otherwise, this is `"Box<dyn Any>"`.
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn message(&self) -> &str {
+        payload_as_str(self.info.payload())
+    }
+
+    /// Returns the location from which the panic originated.
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn location(&self) -> &Location<'a> {
+        // The current implementation always returns `Some`.
+        self.info.location().unwrap()
+    }
+
+    /// Returns a handle to the panicking thread.
+    ///
+    /// This returns `None` if the handle isn't available anymore, because the
+    /// thread is being torn down.
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn thread(&self) -> Option<&thread::Thread> {
+        self.thread.as_ref()
+    }
+
+    /// Returns whether this panic happened while the thread was already
+    /// panicking, for example in a destructor that ran during unwinding.
+    ///
+    /// The process aborts after the hooks of a double panic have run.
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn is_double_panic(&self) -> bool {
+        self.double_panic
+    }
+
+    /// Returns a backtrace of the panic, if backtraces are enabled.
+    ///
+    /// The backtrace is captured the first time this is called, and shared
+    /// by all hooks. Like with the default hook, it is only captured if
+    /// enabled by [`get_backtrace_style`], or for a double panic.
+    ///
+    /// [`get_backtrace_style`]: crate::panic::get_backtrace_style
+    #[must_use]
+    #[unstable(feature = "panic_add_hook", issue = "none")]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        let backtrace = self.backtrace.get_or_init(|| {
+            let enabled = match crate::panic::get_backtrace_style() {
+                Some(BacktraceStyle::Short | BacktraceStyle::Full) => true,
+                Some(BacktraceStyle::Off) => self.double_panic,
+                None => false,
+            };
+            if enabled { Backtrace::force_capture() } else { Backtrace::disabled() }
+        });
+        match backtrace.status() {
+            crate::backtrace::BacktraceStatus::Captured => Some(backtrace),
+            _ => None,
+        }
+    }
+}
+
+#[unstable(feature = "panic_add_hook", issue = "none")]
+impl fmt::Debug for PanicEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicEvent")
+            .field("message", &self.message())
+            .field("location", self.location())
+            .field("thread", &self.thread)
+            .field("double_panic", &self.double_panic)
+            .finish_non_exhaustive()
+    }
+}
+
+fn payload_as_str(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&'static str>() {
+        Some(s) => *s,
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => &s[..],
+            None => "Box<dyn Any>",
+        },
+    }
+}
+
 fn default_hook(info: &PanicInfo<'_>) {
     // If this is a double panic, make sure that we print a backtrace
     // for this panic. Otherwise only print it if logging is enabled.
@@ -248,13 +449,7 @@ fn default_hook(info: &PanicInfo<'_>) {
     // The current implementation always returns `Some`.
     let location = info.location().unwrap();
 
-    let msg = match info.payload().downcast_ref::<&'static str>() {
-        Some(s) => *s,
-        None => match info.payload().downcast_ref::<String>() {
-            Some(s) => &s[..],
-            None => "Box<dyn Any>",
-        },
-    };
+    let msg = payload_as_str(info.payload());
     let thread = thread_info::current_thread();
     let name = thread.as_ref().and_then(|t| t.name()).unwrap_or("<unnamed>");
 
@@ -698,6 +893,21 @@ fn rust_panic_with_hook(
     };
     drop(hook);
 
+    let hooks = ADDED_HOOKS.read().unwrap_or_else(PoisonError::into_inner);
+    if !hooks.hooks.is_empty() {
+        info.set_payload(payload.get());
+        let event = PanicEvent {
+            info: &info,
+            thread: thread_info::current_thread(),
+            double_panic: panics > 1,
+            backtrace: OnceCell::new(),
+        };
+        for (_, hook) in &hooks.hooks {
+            hook(&event);
+        }
+    }
+    drop(hooks);
+
     if panics > 1 || !can_unwind {
         // If a thread panics while it's already unwinding then we
         // have limited options. Currently our preference is to
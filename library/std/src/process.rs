@@ -58,6 +58,9 @@
 //! assert_eq!(b"Oh no, a typo!\n", output.stdout.as_slice());
 //! ```
 //!
+//! A [`Pipeline`] connects any number of commands like this, and collects
+//! the exit statuses of all of them.
+//!
 //! Note that [`ChildStderr`] and [`ChildStdout`] implement [`Read`] and
 //! [`ChildStdin`] implements [`Write`]:
 //!
@@ -111,6 +114,7 @@
 use crate::fmt;
 use crate::fs;
 use crate::io::{self, IoSlice, IoSliceMut};
+use crate::mem;
 use crate::num::NonZeroI32;
 use crate::path::Path;
 use crate::str;
@@ -119,6 +123,8 @@
 #[stable(feature = "command_access", since = "1.57.0")]
 pub use crate::sys_common::process::CommandEnvs;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::thread;
+use crate::time::Duration;
 
 /// Representation of a running or exited child process.
 ///
@@ -1962,6 +1968,47 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         Ok(self.handle.try_wait()?.map(ExitStatus))
     }
 
+    /// Waits at most `timeout` for the child to exit, returning the status
+    /// that it exited with, if it did.
+    ///
+    /// If the child exits before the timeout elapses, then `Ok(Some(status))`
+    /// is returned, and the child is reaped like with [`wait`]. Otherwise,
+    /// `Ok(None)` is returned and the child keeps running.
+    ///
+    /// The stdin handle to the child process, if any, will be closed
+    /// before waiting, like with [`wait`].
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux, this waits on a pidfd for the child, which doesn't require
+    /// waking up before the child exits or the timeout elapses. On Windows,
+    /// this waits on the process handle. On other Unix platforms, and on
+    /// Linux before 5.3, this checks on the child at increasing intervals of
+    /// up to 100 milliseconds.
+    ///
+    /// [`wait`]: Child::wait
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(child_wait_timeout)]
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("60").spawn().unwrap();
+    ///
+    /// if child.wait_timeout(Duration::from_secs(5)).unwrap().is_none() {
+    ///     child.kill().unwrap();
+    ///     child.wait().unwrap();
+    /// }
+    /// ```
+    // FIXME(child_wait_timeout): open a tracking issue and use it instead of `issue = "none"`.
+    #[unstable(feature = "child_wait_timeout", issue = "none")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        drop(self.stdin.take());
+        Ok(self.handle.wait_timeout(timeout)?.map(ExitStatus))
+    }
+
     /// Simultaneously waits for the child to exit and collect all remaining
     /// output on the stdout/stderr handles, returning an `Output`
     /// instance.
@@ -2020,6 +2067,341 @@ pub fn wait_with_output(mut self) -> io::Result<Output> {
     }
 }
 
+/// A builder for a pipeline of processes, like `a | b | c` in a shell.
+///
+/// The standard output of each command is connected to the standard input
+/// of the next one. The standard input of the first command and the
+/// standard output of the last one can be configured with [`stdin`] and
+/// [`stdout`], and otherwise follow the configuration of those commands.
+/// Which by default means that they are inherited from the parent.
+///
+/// Any other configuration of the commands, including their standard error,
+/// is kept. Configuring the standard input of a command other than the first
+/// one, or the standard output of a command other than the last one, has no
+/// effect.
+///
+/// Unlike a [`Command`], a pipeline can only be run once: the commands are
+/// handed over to the processes when it is spawned, and [`spawn`],
+/// [`status`] and [`output`] fail if they are called again.
+///
+/// [`stdin`]: Pipeline::stdin
+/// [`stdout`]: Pipeline::stdout
+/// [`spawn`]: Pipeline::spawn
+/// [`status`]: Pipeline::status
+/// [`output`]: Pipeline::output
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(process_pipeline)]
+/// use std::process::{Command, Pipeline};
+///
+/// let mut echo = Command::new("echo");
+/// echo.arg("Oh no, a tpyo!");
+/// let mut sed = Command::new("sed");
+/// sed.arg("s/tpyo/typo/");
+///
+/// let output = Pipeline::new()
+///     .command(echo)
+///     .command(sed)
+///     .output()
+///     .expect("Failed to run pipeline");
+///
+/// assert!(output.statuses.iter().all(|status| status.success()));
+/// assert_eq!(b"Oh no, a typo!\n", output.stdout.as_slice());
+/// ```
+// FIXME(process_pipeline): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug, Default)]
+pub struct Pipeline {
+    commands: Vec<Command>,
+    stdin: Option<Stdio>,
+    stdout: Option<Stdio>,
+    stderr_to_stdout: bool,
+    spawned: bool,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline, to which commands can be added with
+    /// [`command`].
+    ///
+    /// [`command`]: Pipeline::command
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    #[must_use]
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Adds a command to the end of the pipeline.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn command(&mut self, command: Command) -> &mut Pipeline {
+        self.commands.push(command);
+        self
+    }
+
+    /// Configuration for the standard input of the first command.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn stdin<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Pipeline {
+        self.stdin = Some(cfg.into());
+        self
+    }
+
+    /// Configuration for the standard output of the last command.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Pipeline {
+        self.stdout = Some(cfg.into());
+        self
+    }
+
+    /// Sets whether the standard error of every command is redirected to
+    /// its standard output, like `2>&1` in a shell.
+    ///
+    /// The standard error configured for the commands is then ignored.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn stderr_to_stdout(&mut self, merge: bool) -> &mut Pipeline {
+        self.stderr_to_stdout = merge;
+        self
+    }
+
+    /// Spawns all the commands of the pipeline, returning handles to them.
+    ///
+    /// If any command fails to spawn, the commands that were already spawned
+    /// are killed and waited for, and the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind [`InvalidInput`] if the pipeline has no
+    /// commands, or if it was already spawned.
+    ///
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn spawn(&mut self) -> io::Result<PipelineChild> {
+        self.spawn_with(false)
+    }
+
+    /// Runs all the commands of the pipeline, waits for all of them to exit,
+    /// and returns their exit statuses, in the order of the commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn status(&mut self) -> io::Result<Vec<ExitStatus>> {
+        self.spawn_with(false)?.wait()
+    }
+
+    /// Runs all the commands of the pipeline, waits for all of them to exit,
+    /// and collects their exit statuses and output.
+    ///
+    /// Like with [`Command::output`], by default the standard output of the
+    /// last command and the standard error of every command are captured, and
+    /// the standard input of the first command is not inherited.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn output(&mut self) -> io::Result<PipelineOutput> {
+        self.spawn_with(true)?.wait_with_output()
+    }
+
+    fn spawn_with(&mut self, capture: bool) -> io::Result<PipelineChild> {
+        if self.spawned {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "a pipeline can only be spawned once",
+            ));
+        }
+        if self.commands.is_empty() {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "a pipeline needs at least one command",
+            ));
+        }
+
+        self.spawned = true;
+        let last = self.commands.len() - 1;
+        let mut children: Vec<Child> = Vec::with_capacity(self.commands.len());
+        let mut previous_stdout = None;
+        // Every command is dropped right after it is spawned, closing our copy
+        // of the pipe it reads from. Otherwise the command writing to that pipe
+        // wouldn't notice when the reading command exits early, and could block
+        // forever once the pipe is full.
+        for (i, mut command) in mem::take(&mut self.commands).into_iter().enumerate() {
+            if let Some(stdout) = previous_stdout.take() {
+                command.stdin(Stdio::from(stdout));
+            } else if let Some(stdin) = self.stdin.take() {
+                command.stdin(stdin);
+            }
+            if i < last {
+                command.stdout(Stdio::piped());
+            } else if let Some(stdout) = self.stdout.take() {
+                command.stdout(stdout);
+            }
+            command.as_inner_mut().stderr_to_stdout(self.stderr_to_stdout);
+
+            // Like `Command::output`, capture what isn't configured otherwise.
+            let spawned = if capture {
+                command.inner.spawn(imp::Stdio::MakePipe, false)
+            } else {
+                command.inner.spawn(imp::Stdio::Inherit, true)
+            };
+            match spawned.map(Child::from_inner) {
+                Ok(mut child) => {
+                    if i < last {
+                        previous_stdout = child.stdout.take();
+                    }
+                    children.push(child);
+                }
+                Err(e) => {
+                    for child in &mut children {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let stdin = children[0].stdin.take();
+        let stdout = children[last].stdout.take();
+        let stderr = children[last].stderr.take();
+        Ok(PipelineChild { stdin, stdout, stderr, children })
+    }
+}
+
+#[unstable(feature = "process_pipeline", issue = "none")]
+impl FromIterator<Command> for Pipeline {
+    fn from_iter<I: IntoIterator<Item = Command>>(iter: I) -> Pipeline {
+        Pipeline { commands: iter.into_iter().collect(), ..Pipeline::default() }
+    }
+}
+
+/// Handles to the running processes of a [`Pipeline`], created by
+/// [`Pipeline::spawn`].
+///
+/// Like with [`Child`], the processes keep running when this is dropped, and
+/// are not waited for.
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(Debug)]
+pub struct PipelineChild {
+    /// The handle for writing to the standard input of the first command,
+    /// if it was configured with [`Stdio::piped`].
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdin: Option<ChildStdin>,
+
+    /// The handle for reading from the standard output of the last command,
+    /// if it was configured with [`Stdio::piped`].
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Option<ChildStdout>,
+
+    /// The handle for reading from the standard error of the last command,
+    /// if it was configured with [`Stdio::piped`].
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stderr: Option<ChildStderr>,
+
+    children: Vec<Child>,
+}
+
+impl PipelineChild {
+    /// Returns the handles to the processes, in the order of the commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn children(&self) -> &[Child] {
+        &self.children
+    }
+
+    /// Returns mutable handles to the processes, in the order of the
+    /// commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn children_mut(&mut self) -> &mut [Child] {
+        &mut self.children
+    }
+
+    /// Forces all the processes to exit, like [`Child::kill`].
+    ///
+    /// Every process is killed even if killing another one fails. The first
+    /// error that occurred is returned.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn kill(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        for child in &mut self.children {
+            let res = child.kill();
+            if result.is_ok() {
+                result = res;
+            }
+        }
+        result
+    }
+
+    /// Waits for all the processes to exit, returning their exit statuses in
+    /// the order of the commands.
+    ///
+    /// The standard input handle of the first process, if any, is closed
+    /// before waiting, like with [`Child::wait`].
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait(&mut self) -> io::Result<Vec<ExitStatus>> {
+        drop(self.stdin.take());
+        self.children.iter_mut().map(Child::wait).collect()
+    }
+
+    /// Waits for all the processes to exit, and collects their exit statuses
+    /// and all remaining output.
+    ///
+    /// This reads the standard output of the last process, and the standard
+    /// error of every process whose handle for it was not taken. All of them
+    /// are read at the same time, so that no process can block on writing one
+    /// while this waits for another.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub fn wait_with_output(mut self) -> io::Result<PipelineOutput> {
+        drop(self.stdin.take());
+
+        let others: Vec<ChildStderr> =
+            self.children.iter_mut().filter_map(|child| child.stderr.take()).collect();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let others = thread::scope(|s| -> io::Result<Vec<Vec<u8>>> {
+            let readers: Vec<_> = others
+                .into_iter()
+                .map(|mut err| {
+                    s.spawn(move || {
+                        let mut buf = Vec::new();
+                        err.read_to_end(&mut buf).map(|_| buf)
+                    })
+                })
+                .collect();
+
+            match (self.stdout.take(), self.stderr.take()) {
+                (None, None) => {}
+                (Some(mut out), None) => {
+                    out.read_to_end(&mut stdout)?;
+                }
+                (None, Some(mut err)) => {
+                    err.read_to_end(&mut stderr)?;
+                }
+                (Some(out), Some(err)) => {
+                    read2(out.inner, &mut stdout, err.inner, &mut stderr)?;
+                }
+            }
+
+            readers.into_iter().map(|reader| reader.join().unwrap()).collect()
+        })?;
+
+        let stderr = [others.concat(), stderr].concat();
+        let statuses = self.wait()?;
+        Ok(PipelineOutput { statuses, stdout, stderr })
+    }
+}
+
+/// The output of a finished [`Pipeline`].
+///
+/// This is returned by [`Pipeline::output`] and
+/// [`PipelineChild::wait_with_output`].
+#[unstable(feature = "process_pipeline", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PipelineOutput {
+    /// The exit statuses of the processes, in the order of the commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub statuses: Vec<ExitStatus>,
+    /// The data that the last process wrote to stdout.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stdout: Vec<u8>,
+    /// The data that the processes wrote to stderr, in the order of the
+    /// commands.
+    #[unstable(feature = "process_pipeline", issue = "none")]
+    pub stderr: Vec<u8>,
+}
+
 /// Terminates the current process with the specified exit code.
 ///
 /// This function will never return and will immediately terminate the current
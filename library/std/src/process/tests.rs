@@ -1,6 +1,6 @@
 use crate::io::prelude::*;
 
-use super::{Command, Output, Stdio};
+use super::{Command, Output, Pipeline, PipelineOutput, Stdio};
 use crate::io::ErrorKind;
 use crate::str;
 
@@ -198,6 +198,31 @@ fn test_finish_twice() {
     assert!(prog.wait().unwrap().code() == Some(1));
 }
 
+#[test]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_wait_timeout() {
+    use crate::time::Duration;
+
+    let mut prog = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "ping -n 30 127.0.0.1 > nul"]).spawn().unwrap()
+    } else {
+        shell_cmd().arg("-c").arg("sleep 30").spawn().unwrap()
+    };
+    assert!(prog.wait_timeout(Duration::from_millis(10)).unwrap().is_none());
+    prog.kill().unwrap();
+    let status = prog.wait_timeout(Duration::from_secs(30)).unwrap().unwrap();
+    assert!(!status.success());
+    assert_eq!(prog.wait_timeout(Duration::ZERO).unwrap(), Some(status));
+
+    let mut prog = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(&["/C", "exit 1"]).spawn().unwrap()
+    } else {
+        shell_cmd().arg("-c").arg("false").spawn().unwrap()
+    };
+    let status = prog.wait_timeout(Duration::from_secs(30)).unwrap().unwrap();
+    assert_eq!(status.code(), Some(1));
+}
+
 #[test]
 #[cfg_attr(any(target_os = "vxworks"), ignore)]
 fn test_wait_with_output_once() {
@@ -215,6 +240,118 @@ fn test_wait_with_output_once() {
     assert_eq!(stderr, Vec::new());
 }
 
+fn script(script: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(&["/C", script]);
+        cmd
+    } else {
+        let mut cmd = shell_cmd();
+        cmd.arg("-c").arg(script);
+        cmd
+    }
+}
+
+fn cat_cmd() -> Command {
+    if cfg!(target_os = "windows") { script("findstr x*") } else { script("cat") }
+}
+
+#[test]
+#[cfg_attr(any(target_os = "vxworks"), ignore)]
+fn test_pipeline_output() {
+    let PipelineOutput { statuses, stdout, stderr } = Pipeline::new()
+        .command(script("echo hello"))
+        .command(cat_cmd())
+        .command(script("exit 3"))
+        .output()
+        .unwrap();
+
+    // The other stages may be killed by `SIGPIPE` once the last one exits.
+    assert_eq!(statuses.len(), 3);
+    assert_eq!(statuses[2].code(), Some(3));
+    assert_eq!(stdout, Vec::new());
+    assert_eq!(stderr, Vec::new());
+
+    let output = Pipeline::new().command(script("echo hello")).command(cat_cmd()).output().unwrap();
+    assert_eq!(str::from_utf8(&output.stdout).unwrap().trim(), "hello");
+}
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_stdin_and_stderr_to_stdout() {
+    let mut child = Pipeline::new()
+        .command(script("cat; echo oops >&2"))
+        .command(script("tr a-z A-Z"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr_to_stdout(true)
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"hello\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.statuses.iter().all(|status| status.success()));
+    assert_eq!(output.stdout, b"HELLO\nOOPS\n");
+    assert_eq!(output.stderr, Vec::new());
+}
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_output_stderr_of_every_stage() {
+    // More than fits in a pipe buffer, so the first stage blocks unless its stderr is read.
+    let output = Pipeline::new()
+        .command(script("head -c 1000000 /dev/zero >&2; echo hello"))
+        .command(script("cat; echo oops >&2"))
+        .output()
+        .unwrap();
+
+    assert!(output.statuses.iter().all(|status| status.success()));
+    assert_eq!(output.stdout, b"hello\n");
+    assert_eq!(output.stderr.len(), 1000000 + 5);
+    assert!(output.stderr.ends_with(b"\0oops\n"));
+}
+
+#[test]
+#[cfg_attr(any(windows, target_os = "vxworks"), ignore)]
+fn test_pipeline_early_exit_stops_upstream() {
+    use crate::time::Duration;
+
+    // `yes` only exits once writing fails, which requires the read end of the
+    // pipe to be closed in this process too once `head` exits.
+    let mut child = Pipeline::new()
+        .command(script("yes"))
+        .command(script("head -n1"))
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    for i in 0..2 {
+        if child.children_mut()[i].wait_timeout(Duration::from_secs(60)).unwrap().is_none() {
+            child.kill().unwrap();
+            panic!("`yes | head -n1` did not finish");
+        }
+    }
+    assert_eq!(child.wait_with_output().unwrap().stdout, b"y\n");
+}
+
+#[test]
+fn test_pipeline_errors() {
+    let err = Pipeline::new().spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let mut pipeline = Pipeline::new();
+    pipeline.command(known_command());
+    pipeline.status().unwrap();
+    let err = pipeline.status().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = Pipeline::new()
+        .command(known_command())
+        .command(Command::new("if-this-is-a-binary-then-the-world-has-ended"))
+        .spawn()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
 #[cfg(all(unix, not(target_os = "android")))]
 pub fn env_cmd() -> Command {
     Command::new("env")
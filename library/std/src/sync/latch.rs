@@ -0,0 +1,165 @@
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests;
+
+use crate::fmt;
+use crate::sys_common::latch as sys;
+use crate::time::Duration;
+
+/// A one-shot signal that many threads can wait for.
+///
+/// A latch starts out unset. Once [`set`] is called, all threads blocked in
+/// [`wait`] are woken up, and all future calls to [`wait`] return immediately.
+/// A latch cannot be unset again.
+///
+/// Everything that happened on the setting thread before it called [`set`] is
+/// visible to the threads that observed the latch being set.
+///
+/// [`set`]: Latch::set
+/// [`wait`]: Latch::wait
+///
+/// # Examples
+///
+/// ```
+/// #![feature(sync_latch)]
+///
+/// use std::sync::Latch;
+/// use std::thread;
+///
+/// let ready = Latch::new();
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             ready.wait();
+///             // ...
+///         });
+///     }
+///     // Everything is set up, let the workers go.
+///     ready.set();
+/// });
+/// ```
+// FIXME(sync_latch): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "sync_latch", issue = "none")]
+pub struct Latch {
+    inner: sys::Latch,
+}
+
+impl Latch {
+    /// Creates a new latch that is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_latch)]
+    ///
+    /// use std::sync::Latch;
+    ///
+    /// let latch = Latch::new();
+    /// assert!(!latch.is_set());
+    /// ```
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "sync_latch", issue = "none")]
+    pub const fn new() -> Latch {
+        Latch { inner: sys::Latch::new() }
+    }
+
+    /// Sets the latch, waking up all threads waiting for it.
+    ///
+    /// Setting a latch that is already set has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_latch)]
+    ///
+    /// use std::sync::Latch;
+    ///
+    /// let latch = Latch::new();
+    /// latch.set();
+    /// assert!(latch.is_set());
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_latch", issue = "none")]
+    pub fn set(&self) {
+        self.inner.set()
+    }
+
+    /// Returns whether the latch has been set.
+    ///
+    /// If this returns `true`, everything that happened before the latch was
+    /// set is visible to the current thread, just like after [`wait`].
+    ///
+    /// [`wait`]: Latch::wait
+    #[inline]
+    #[unstable(feature = "sync_latch", issue = "none")]
+    pub fn is_set(&self) -> bool {
+        self.inner.is_set()
+    }
+
+    /// Blocks the current thread until the latch is set.
+    ///
+    /// Returns immediately if it is set already.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_latch)]
+    ///
+    /// use std::sync::Latch;
+    /// use std::thread;
+    ///
+    /// let latch = Latch::new();
+    ///
+    /// thread::scope(|s| {
+    ///     s.spawn(|| latch.set());
+    ///     latch.wait();
+    /// });
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_latch", issue = "none")]
+    pub fn wait(&self) {
+        self.inner.wait()
+    }
+
+    /// Blocks the current thread until the latch is set, for at most
+    /// `timeout`.
+    ///
+    /// Returns whether the latch was set before the timeout passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_latch)]
+    ///
+    /// use std::sync::Latch;
+    /// use std::time::Duration;
+    ///
+    /// let latch = Latch::new();
+    /// assert!(!latch.wait_timeout(Duration::from_millis(10)));
+    ///
+    /// latch.set();
+    /// assert!(latch.wait_timeout(Duration::from_millis(10)));
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_latch", issue = "none")]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.inner.wait_timeout(timeout)
+    }
+}
+
+#[unstable(feature = "sync_latch", issue = "none")]
+impl Default for Latch {
+    /// Creates a new latch that is not set.
+    #[inline]
+    fn default() -> Latch {
+        Latch::new()
+    }
+}
+
+#[unstable(feature = "sync_latch", issue = "none")]
+impl fmt::Debug for Latch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Latch").field("is_set", &self.is_set()).finish()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::{Arc, Latch};
+use crate::thread;
+use crate::time::Duration;
+
+#[test]
+fn smoke() {
+    let l = Latch::new();
+    assert!(!l.is_set());
+    l.set();
+    assert!(l.is_set());
+    l.wait();
+    l.set();
+    assert!(l.is_set());
+}
+
+#[test]
+fn wait_timeout() {
+    let l = Latch::new();
+    assert!(!l.wait_timeout(Duration::from_millis(10)));
+    l.set();
+    assert!(l.wait_timeout(Duration::ZERO));
+}
+
+#[test]
+fn wakes_all_waiters() {
+    const N: usize = 10;
+
+    let l = Arc::new(Latch::new());
+    let data = Arc::new(AtomicUsize::new(0));
+
+    let threads: Vec<_> = (0..N)
+        .map(|i| {
+            let l = l.clone();
+            let data = data.clone();
+            thread::spawn(move || {
+                if i % 2 == 0 {
+                    l.wait();
+                } else {
+                    assert!(l.wait_timeout(Duration::from_secs(60)));
+                }
+                assert_eq!(data.load(Ordering::Relaxed), 42);
+            })
+        })
+        .collect();
+
+    data.store(42, Ordering::Relaxed);
+    l.set();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
@@ -172,6 +172,13 @@
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+#[unstable(feature = "sync_latch", issue = "none")]
+pub use self::latch::Latch;
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+pub use self::rwlock::RwLockUpgradableReadGuard;
+#[unstable(feature = "sync_semaphore", issue = "none")]
+pub use self::semaphore::{Semaphore, SemaphorePermit};
+
 #[unstable(feature = "once_cell", issue = "74465")]
 pub use self::lazy_lock::LazyLock;
 #[unstable(feature = "once_cell", issue = "74465")]
@@ -179,15 +186,19 @@
 
 pub(crate) use self::remutex::{ReentrantMutex, ReentrantMutexGuard};
 
+// FIXME(mpmc_channel): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub mod mpmc;
 pub mod mpsc;
 
 mod barrier;
 mod condvar;
+mod latch;
 mod lazy_lock;
-mod mpmc;
 mod mutex;
 pub(crate) mod once;
 mod once_lock;
 mod poison;
 mod remutex;
 mod rwlock;
+mod semaphore;
@@ -482,6 +482,42 @@ pub(crate) fn is_full(&self) -> bool {
         // when the channel was not full, so it is safe to just return `false`.
         head.wrapping_add(self.one_lap) == tail & !self.mark_bit
     }
+
+    /// Returns `true` if a receive operation can proceed without blocking.
+    pub(crate) fn is_ready_recv(&self) -> bool {
+        !self.is_empty() || self.is_disconnected()
+    }
+
+    /// Returns `true` if a send operation can proceed without blocking.
+    pub(crate) fn is_ready_send(&self) -> bool {
+        !self.is_full() || self.is_disconnected()
+    }
+
+    /// Registers a receive operation waiting to be ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.watch(oper, cx);
+        self.is_ready_recv()
+    }
+
+    /// Registers a send operation waiting to be ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    pub(crate) fn watch_send(&self, oper: Operation, cx: &Context) -> bool {
+        self.senders.watch(oper, cx);
+        self.is_ready_send()
+    }
+
+    /// Unregisters a receive operation waiting to be ready.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.receivers.unwatch(oper);
+    }
+
+    /// Unregisters a send operation waiting to be ready.
+    pub(crate) fn unwatch_send(&self, oper: Operation) {
+        self.senders.unwatch(oper);
+    }
 }
 
 impl<T> Drop for Channel<T> {
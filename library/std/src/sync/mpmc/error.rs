@@ -8,6 +8,7 @@
 /// The error contains the message being sent so it can be recovered.
 ///
 /// [`send_timeout`]: super::Sender::send_timeout
+#[unstable(feature = "mpmc_channel", issue = "none")]
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum SendTimeoutError<T> {
     /// The message could not be sent because the channel is full and the operation timed out.
@@ -20,12 +21,14 @@ pub enum SendTimeoutError<T> {
     Disconnected(T),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for SendTimeoutError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         "SendTimeoutError(..)".fmt(f)
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Display for SendTimeoutError<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -35,8 +38,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T: Send> error::Error for SendTimeoutError<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> From<SendError<T>> for SendTimeoutError<T> {
     fn from(err: SendError<T>) -> SendTimeoutError<T> {
         match err {
@@ -44,3 +49,42 @@ fn from(err: SendError<T>) -> SendTimeoutError<T> {
         }
     }
 }
+
+/// An error returned from the [`try_ready`] method.
+///
+/// None of the operations were ready.
+///
+/// [`try_ready`]: super::Select::try_ready
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TryReadyError;
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Display for TryReadyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "all operations in select would block".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl error::Error for TryReadyError {}
+
+/// An error returned from the [`ready_timeout`] and [`ready_deadline`] methods.
+///
+/// None of the operations became ready before the timeout.
+///
+/// [`ready_timeout`]: super::Select::ready_timeout
+/// [`ready_deadline`]: super::Select::ready_deadline
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ReadyTimeoutError;
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Display for ReadyTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "timed out waiting on select".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl error::Error for ReadyTimeoutError {}
@@ -597,6 +597,37 @@ pub(crate) fn is_empty(&self) -> bool {
     pub(crate) fn is_full(&self) -> bool {
         false
     }
+
+    /// Returns `true` if a receive operation can proceed without blocking.
+    pub(crate) fn is_ready_recv(&self) -> bool {
+        !self.is_empty() || self.is_disconnected()
+    }
+
+    /// Returns `true` if a send operation can proceed without blocking, which it always can.
+    pub(crate) fn is_ready_send(&self) -> bool {
+        true
+    }
+
+    /// Registers a receive operation waiting to be ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        self.receivers.watch(oper, cx);
+        self.is_ready_recv()
+    }
+
+    /// Registers a send operation waiting to be ready, which it always is.
+    pub(crate) fn watch_send(&self, _oper: Operation, _cx: &Context) -> bool {
+        true
+    }
+
+    /// Unregisters a receive operation waiting to be ready.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.receivers.unwatch(oper);
+    }
+
+    /// Unregisters a send operation waiting to be ready.
+    pub(crate) fn unwatch_send(&self, _oper: Operation) {}
 }
 
 impl<T> Drop for Channel<T> {
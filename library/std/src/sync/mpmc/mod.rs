@@ -1,8 +1,37 @@
-//! Multi-producer multi-consumer channels.
-
-// This module is not currently exposed publicly, but is used
-// as the implementation for the channels in `sync::mpsc`. The
-// implementation comes from the crossbeam-channel crate:
+//! Multi-producer, multi-consumer FIFO queue communication primitives.
+//!
+//! This module provides the same channels as [`mpsc`], except that their
+//! [`Receiver`]s can be cloned too, so that several threads can receive
+//! messages from the same channel, each message being received only once.
+//! [`Select`] waits on operations of several channels at once.
+//!
+//! [`mpsc`]: crate::sync::mpsc
+//!
+//! # Examples
+//!
+//! ```
+//! #![feature(mpmc_channel)]
+//! use std::sync::mpmc::channel;
+//! use std::thread;
+//!
+//! let (tx, rx) = channel();
+//! let workers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let rx = rx.clone();
+//!         thread::spawn(move || rx.iter().sum::<u32>())
+//!     })
+//!     .collect();
+//! for i in 1..=100 {
+//!     tx.send(i).unwrap();
+//! }
+//! drop(tx);
+//! let total: u32 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+//! assert_eq!(total, 5050);
+//! ```
+
+// This module is also the implementation of the channels in
+// `sync::mpsc`. The implementation comes from the crossbeam-channel
+// crate:
 //
 // Copyright (c) 2019 The Crossbeam Project Developers
 //
@@ -30,6 +59,9 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests;
+
 mod array;
 mod context;
 mod counter;
@@ -43,11 +75,17 @@
 use crate::fmt;
 use crate::panic::{RefUnwindSafe, UnwindSafe};
 use crate::time::{Duration, Instant};
+use context::Context;
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub use error::*;
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub use select::Select;
+use select::{Operation, SelectHandle};
 
 /// Creates a channel of unbounded capacity.
 ///
 /// This channel has a growable buffer that can hold any number of messages at a time.
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     let (s, r) = counter::new(list::Channel::new());
     let s = Sender { flavor: SenderFlavor::List(s) };
@@ -61,6 +99,7 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
 ///
 /// A special case is zero-capacity channel, which cannot hold any messages. Instead, send and
 /// receive operations must appear at the same time in order to pair up and pass the message over.
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub fn sync_channel<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
     if cap == 0 {
         let (s, r) = counter::new(zero::Channel::new());
@@ -76,6 +115,7 @@ pub fn sync_channel<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
 }
 
 /// The sending side of a channel.
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub struct Sender<T> {
     flavor: SenderFlavor<T>,
 }
@@ -92,10 +132,14 @@ enum SenderFlavor<T> {
     Zero(counter::Sender<zero::Channel<T>>),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Send for Sender<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Sync for Sender<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> UnwindSafe for Sender<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> RefUnwindSafe for Sender<T> {}
 
 impl<T> Sender<T> {
@@ -106,6 +150,7 @@ impl<T> Sender<T> {
     ///
     /// If called on a zero-capacity channel, this method will send the message only if there
     /// happens to be a receive operation on the other side of the channel at the same time.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.try_send(msg),
@@ -122,6 +167,7 @@ pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.send(msg, None),
@@ -133,13 +179,7 @@ pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
             SendTimeoutError::Timeout(_) => unreachable!(),
         })
     }
-}
 
-// The methods below are not used by `sync::mpsc`, but
-// are useful and we'll likely want to expose them
-// eventually
-#[allow(unused)]
-impl<T> Sender<T> {
     /// Waits for a message to be sent into the channel, but only for a limited time.
     ///
     /// If the channel is full and not disconnected, this call will block until the send operation
@@ -148,6 +188,7 @@ impl<T> Sender<T> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
         match Instant::now().checked_add(timeout) {
             Some(deadline) => self.send_deadline(msg, deadline),
@@ -164,6 +205,7 @@ pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutE
     ///
     /// If called on a zero-capacity channel, this method will wait for a receive operation to
     /// appear on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn send_deadline(&self, msg: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.send(msg, Some(deadline)),
@@ -175,6 +217,7 @@ pub fn send_deadline(&self, msg: T, deadline: Instant) -> Result<(), SendTimeout
     /// Returns `true` if the channel is empty.
     ///
     /// Note: Zero-capacity channels are always empty.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_empty(&self) -> bool {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.is_empty(),
@@ -186,6 +229,7 @@ pub fn is_empty(&self) -> bool {
     /// Returns `true` if the channel is full.
     ///
     /// Note: Zero-capacity channels are always full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_full(&self) -> bool {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.is_full(),
@@ -195,6 +239,7 @@ pub fn is_full(&self) -> bool {
     }
 
     /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn len(&self) -> usize {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.len(),
@@ -204,6 +249,7 @@ pub fn len(&self) -> usize {
     }
 
     /// If the channel is bounded, returns its capacity.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn capacity(&self) -> Option<usize> {
         match &self.flavor {
             SenderFlavor::Array(chan) => chan.capacity(),
@@ -213,6 +259,7 @@ pub fn capacity(&self) -> Option<usize> {
     }
 
     /// Returns `true` if senders belong to the same channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn same_channel(&self, other: &Sender<T>) -> bool {
         match (&self.flavor, &other.flavor) {
             (SenderFlavor::Array(ref a), SenderFlavor::Array(ref b)) => a == b,
@@ -223,6 +270,7 @@ pub fn same_channel(&self, other: &Sender<T>) -> bool {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
         unsafe {
@@ -235,6 +283,7 @@ fn drop(&mut self) {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
         let flavor = match &self.flavor {
@@ -247,6 +296,7 @@ fn clone(&self) -> Self {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("Sender { .. }")
@@ -254,6 +304,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 /// The receiving side of a channel.
+#[unstable(feature = "mpmc_channel", issue = "none")]
 pub struct Receiver<T> {
     flavor: ReceiverFlavor<T>,
 }
@@ -270,10 +321,14 @@ enum ReceiverFlavor<T> {
     Zero(counter::Receiver<zero::Channel<T>>),
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Send for Receiver<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 unsafe impl<T: Send> Sync for Receiver<T> {}
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> UnwindSafe for Receiver<T> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> RefUnwindSafe for Receiver<T> {}
 
 impl<T> Receiver<T> {
@@ -284,6 +339,7 @@ impl<T> Receiver<T> {
     ///
     /// If called on a zero-capacity channel, this method will receive a message only if there
     /// happens to be a send operation on the other side of the channel at the same time.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn try_recv(&self) -> Result<T, TryRecvError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.try_recv(),
@@ -301,6 +357,7 @@ pub fn try_recv(&self) -> Result<T, TryRecvError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv(&self) -> Result<T, RecvError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.recv(None),
@@ -318,6 +375,7 @@ pub fn recv(&self) -> Result<T, RecvError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
         match Instant::now().checked_add(timeout) {
             Some(deadline) => self.recv_deadline(deadline),
@@ -334,6 +392,7 @@ pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
     ///
     /// If called on a zero-capacity channel, this method will wait for a send operation to appear
     /// on the other side of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.recv(Some(deadline)),
@@ -341,16 +400,28 @@ pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
             ReceiverFlavor::Zero(chan) => chan.recv(Some(deadline)),
         }
     }
-}
 
-// The methods below are not used by `sync::mpsc`, but
-// are useful and we'll likely want to expose them
-// eventually
-#[allow(unused)]
-impl<T> Receiver<T> {
+    /// Returns an iterator that will block waiting for messages, but never
+    /// [`panic!`]. It will return [`None`] when the channel is empty and
+    /// disconnected.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator that will attempt to yield all pending values.
+    /// It will return `None` if there are no more pending values or if the
+    /// channel is disconnected. The iterator will never [`panic!`] or block
+    /// the user by waiting for values.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+
     /// Returns `true` if the channel is empty.
     ///
     /// Note: Zero-capacity channels are always empty.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_empty(&self) -> bool {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.is_empty(),
@@ -362,6 +433,7 @@ pub fn is_empty(&self) -> bool {
     /// Returns `true` if the channel is full.
     ///
     /// Note: Zero-capacity channels are always full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn is_full(&self) -> bool {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.is_full(),
@@ -371,6 +443,7 @@ pub fn is_full(&self) -> bool {
     }
 
     /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn len(&self) -> usize {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.len(),
@@ -380,6 +453,7 @@ pub fn len(&self) -> usize {
     }
 
     /// If the channel is bounded, returns its capacity.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn capacity(&self) -> Option<usize> {
         match &self.flavor {
             ReceiverFlavor::Array(chan) => chan.capacity(),
@@ -389,6 +463,7 @@ pub fn capacity(&self) -> Option<usize> {
     }
 
     /// Returns `true` if receivers belong to the same channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
     pub fn same_channel(&self, other: &Receiver<T>) -> bool {
         match (&self.flavor, &other.flavor) {
             (ReceiverFlavor::Array(a), ReceiverFlavor::Array(b)) => a == b,
@@ -399,6 +474,7 @@ pub fn same_channel(&self, other: &Receiver<T>) -> bool {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
         unsafe {
@@ -411,6 +487,7 @@ fn drop(&mut self) {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
         let flavor = match &self.flavor {
@@ -423,8 +500,152 @@ fn clone(&self) -> Self {
     }
 }
 
+#[unstable(feature = "mpmc_channel", issue = "none")]
 impl<T> fmt::Debug for Receiver<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("Receiver { .. }")
     }
 }
+
+/// An iterator over messages on a [`Receiver`], created by [`iter`].
+///
+/// This iterator will block whenever [`next`] is called,
+/// waiting for a new message, and [`None`] will be returned
+/// when the channel is empty and disconnected.
+///
+/// [`iter`]: Receiver::iter
+/// [`next`]: Iterator::next
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An iterator that attempts to yield all pending values for a [`Receiver`],
+/// created by [`try_iter`].
+///
+/// [`None`] will be returned when there are no pending values remaining or
+/// if the corresponding channel is disconnected.
+///
+/// This iterator will never block the caller in order to wait for data to
+/// become available. Instead, it will return [`None`].
+///
+/// [`try_iter`]: Receiver::try_iter
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct TryIter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning iterator over messages on a [`Receiver`],
+/// created by [`into_iter`].
+///
+/// This iterator will block whenever [`next`]
+/// is called, waiting for a new message, and [`None`] will be
+/// returned if the corresponding channel is empty and disconnected.
+///
+/// [`into_iter`]: Receiver::into_iter
+/// [`next`]: Iterator::next
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<T> SelectHandle for Sender<T> {
+    fn is_ready(&self) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.is_ready_send(),
+            SenderFlavor::List(chan) => chan.is_ready_send(),
+            SenderFlavor::Zero(chan) => chan.is_ready_send(),
+        }
+    }
+
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.watch_send(oper, cx),
+            SenderFlavor::List(chan) => chan.watch_send(oper, cx),
+            SenderFlavor::Zero(chan) => chan.watch_send(oper, cx),
+        }
+    }
+
+    fn unwatch(&self, oper: Operation) {
+        match &self.flavor {
+            SenderFlavor::Array(chan) => chan.unwatch_send(oper),
+            SenderFlavor::List(chan) => chan.unwatch_send(oper),
+            SenderFlavor::Zero(chan) => chan.unwatch_send(oper),
+        }
+    }
+}
+
+impl<T> SelectHandle for Receiver<T> {
+    fn is_ready(&self) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.is_ready_recv(),
+            ReceiverFlavor::List(chan) => chan.is_ready_recv(),
+            ReceiverFlavor::Zero(chan) => chan.is_ready_recv(),
+        }
+    }
+
+    fn watch(&self, oper: Operation, cx: &Context) -> bool {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.watch_recv(oper, cx),
+            ReceiverFlavor::List(chan) => chan.watch_recv(oper, cx),
+            ReceiverFlavor::Zero(chan) => chan.watch_recv(oper, cx),
+        }
+    }
+
+    fn unwatch(&self, oper: Operation) {
+        match &self.flavor {
+            ReceiverFlavor::Array(chan) => chan.unwatch_recv(oper),
+            ReceiverFlavor::List(chan) => chan.unwatch_recv(oper),
+            ReceiverFlavor::Zero(chan) => chan.unwatch_recv(oper),
+        }
+    }
+}
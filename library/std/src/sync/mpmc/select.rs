@@ -1,3 +1,13 @@
+use super::context::Context;
+use super::error::{ReadyTimeoutError, TryReadyError};
+use super::utils::Backoff;
+use super::{Receiver, Sender};
+
+use crate::cell::Cell;
+use crate::fmt;
+use crate::thread;
+use crate::time::{Duration, Instant};
+
 /// Temporary data that gets initialized during a blocking operation, and is consumed by
 /// `read` or `write`.
 ///
@@ -69,3 +79,336 @@ fn into(self) -> usize {
         }
     }
 }
+
+/// A channel operation that can be waited on by [`Select`].
+pub(crate) trait SelectHandle {
+    /// Returns `true` if the operation can proceed without blocking.
+    fn is_ready(&self) -> bool;
+
+    /// Registers the operation to be notified when it may have become ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    fn watch(&self, oper: Operation, cx: &Context) -> bool;
+
+    /// Unregisters an operation registered by `watch`.
+    fn unwatch(&self, oper: Operation);
+}
+
+/// Waits until one of several channel operations is ready.
+///
+/// Operations are added with [`recv`] and [`send`], which return the index of the operation.
+/// [`ready`] then blocks until one of the operations is ready, meaning that it can proceed
+/// without blocking, and returns its index. A receive operation is also ready when its
+/// channel is disconnected, as receiving would return an error right away, and so is a send
+/// operation.
+///
+/// Another thread may still complete the operation first, so it should be attempted with
+/// [`try_recv`] or [`try_send`], and waited on again if it fails.
+///
+/// When several operations are ready, one of them is picked at random.
+///
+/// [`recv`]: Select::recv
+/// [`send`]: Select::send
+/// [`ready`]: Select::ready
+/// [`try_recv`]: super::Receiver::try_recv
+/// [`try_send`]: super::Sender::try_send
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::{self, Select, TryRecvError};
+/// use std::thread;
+///
+/// let (tx1, rx1) = mpmc::channel();
+/// let (tx2, rx2) = mpmc::channel::<i32>();
+///
+/// thread::spawn(move || tx1.send("hello").unwrap());
+/// drop(tx2);
+///
+/// let mut sel = Select::new();
+/// let first = sel.recv(&rx1);
+/// let second = sel.recv(&rx2);
+///
+/// let mut disconnected = 0;
+/// let msg = loop {
+///     let index = sel.ready();
+///     if index == first {
+///         match rx1.try_recv() {
+///             Ok(msg) => break msg,
+///             Err(TryRecvError::Empty) => continue,
+///             Err(TryRecvError::Disconnected) => unreachable!(),
+///         }
+///     } else if index == second {
+///         assert_eq!(rx2.try_recv(), Err(TryRecvError::Disconnected));
+///         sel.remove(second);
+///         disconnected += 1;
+///     }
+/// };
+/// assert_eq!(msg, "hello");
+/// assert_eq!(disconnected, 1);
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct Select<'a> {
+    /// The operations, along with their indices.
+    handles: Vec<(&'a dyn SelectHandle, usize)>,
+
+    /// The index of the next operation to be added.
+    next_index: usize,
+}
+
+// The operations are only ever used to check whether a channel is ready, which never touches
+// its messages, so a `Select` can be shared regardless of the message types.
+#[unstable(feature = "mpmc_channel", issue = "none")]
+unsafe impl Send for Select<'_> {}
+#[unstable(feature = "mpmc_channel", issue = "none")]
+unsafe impl Sync for Select<'_> {}
+
+impl<'a> Select<'a> {
+    /// Creates an empty list of channel operations.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    #[must_use]
+    pub fn new() -> Select<'a> {
+        Select { handles: Vec::new(), next_index: 0 }
+    }
+
+    /// Adds a receive operation on `r`, and returns its index.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn recv<T>(&mut self, r: &'a Receiver<T>) -> usize {
+        self.add(r)
+    }
+
+    /// Adds a send operation on `s`, and returns its index.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn send<T>(&mut self, s: &'a Sender<T>) -> usize {
+        self.add(s)
+    }
+
+    fn add(&mut self, handle: &'a dyn SelectHandle) -> usize {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.handles.push((handle, index));
+        index
+    }
+
+    /// Removes the operation with the given index.
+    ///
+    /// This is typically used after an operation returns an error because its channel is
+    /// disconnected, so that it isn't reported as ready anymore.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no operation with this index, because it was never added or was
+    /// removed already.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn remove(&mut self, index: usize) {
+        let pos = self
+            .handles
+            .iter()
+            .position(|&(_, i)| i == index)
+            .expect("no operation with this index");
+        self.handles.swap_remove(pos);
+    }
+
+    /// Returns the index of an operation that is ready, without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TryReadyError`] if none of the operations are ready.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_ready(&mut self) -> Result<usize, TryReadyError> {
+        run_ready(&mut self.handles, Timeout::Now).ok_or(TryReadyError)
+    }
+
+    /// Blocks until one of the operations is ready, and returns its index.
+    ///
+    /// If there are no operations, this blocks forever.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready(&mut self) -> usize {
+        run_ready(&mut self.handles, Timeout::Never).unwrap()
+    }
+
+    /// Blocks until one of the operations is ready, but only for a limited time, and returns
+    /// its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadyTimeoutError`] if none of the operations became ready in time.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready_timeout(&mut self, timeout: Duration) -> Result<usize, ReadyTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.ready_deadline(deadline),
+            // So far in the future that it's practically the same as waiting indefinitely.
+            None => Ok(self.ready()),
+        }
+    }
+
+    /// Blocks until one of the operations is ready, but only until a given deadline, and
+    /// returns its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadyTimeoutError`] if none of the operations became ready in time.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Result<usize, ReadyTimeoutError> {
+        run_ready(&mut self.handles, Timeout::At(deadline)).ok_or(ReadyTimeoutError)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl Clone for Select<'_> {
+    fn clone(&self) -> Self {
+        Select { handles: self.handles.clone(), next_index: self.next_index }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl Default for Select<'_> {
+    fn default() -> Self {
+        Select::new()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl fmt::Debug for Select<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Select { .. }")
+    }
+}
+
+/// How long `run_ready` may block.
+enum Timeout {
+    /// Not at all.
+    Now,
+
+    /// Until an operation is ready.
+    Never,
+
+    /// Until an operation is ready, or the deadline is reached.
+    At(Instant),
+}
+
+/// Waits until one of `handles` is ready, and returns its index.
+fn run_ready(handles: &mut [(&dyn SelectHandle, usize)], timeout: Timeout) -> Option<usize> {
+    if handles.is_empty() {
+        match timeout {
+            Timeout::Now => return None,
+            Timeout::Never => loop {
+                thread::park();
+            },
+            Timeout::At(deadline) => {
+                while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+                    if timeout.is_zero() {
+                        break;
+                    }
+                    thread::sleep(timeout);
+                }
+                return None;
+            }
+        }
+    }
+
+    // Start at a random operation, so that none of them is favored.
+    let start = random_index(handles.len());
+    handles.rotate_left(start);
+
+    loop {
+        let backoff = Backoff::new();
+        for _ in 0..SPIN_ATTEMPTS {
+            if let Some(&(_, index)) = handles.iter().find(|(handle, _)| handle.is_ready()) {
+                return Some(index);
+            }
+            backoff.spin_light();
+        }
+
+        if let Timeout::Now = timeout {
+            return None;
+        }
+
+        let ready = Context::with(|cx| {
+            let mut sel = Selected::Waiting;
+            let mut registered = 0;
+
+            // Watch all the operations, unless one of them turns out to be ready.
+            for (handle, _) in handles.iter_mut() {
+                registered += 1;
+                let oper = Operation::hook::<&dyn SelectHandle>(handle);
+                if handle.watch(oper, cx) {
+                    sel = match cx.try_select(Selected::Operation(oper)) {
+                        Ok(()) => Selected::Operation(oper),
+                        Err(s) => s,
+                    };
+                    break;
+                }
+
+                // Stop if one of the operations became ready in the meantime.
+                sel = cx.selected();
+                if sel != Selected::Waiting {
+                    break;
+                }
+            }
+
+            if sel == Selected::Waiting {
+                let deadline = match timeout {
+                    Timeout::Now => unreachable!(),
+                    Timeout::Never => None,
+                    Timeout::At(deadline) => Some(deadline),
+                };
+                sel = cx.wait_until(deadline);
+            }
+
+            for (handle, _) in handles.iter_mut().take(registered) {
+                let oper = Operation::hook::<&dyn SelectHandle>(handle);
+                handle.unwatch(oper);
+            }
+
+            match sel {
+                Selected::Waiting => unreachable!(),
+                Selected::Aborted | Selected::Disconnected => {}
+                Selected::Operation(_) => {
+                    for (handle, index) in handles.iter_mut() {
+                        let oper = Operation::hook::<&dyn SelectHandle>(handle);
+                        if sel == Selected::Operation(oper) {
+                            return Some(*index);
+                        }
+                    }
+                }
+            }
+            None
+        });
+
+        // A notification only means that an operation may be ready, so check again.
+        if let Some(index) = ready {
+            if handles.iter().any(|&(handle, i)| i == index && handle.is_ready()) {
+                return Some(index);
+            }
+        }
+
+        if let Timeout::At(deadline) = timeout {
+            if Instant::now() >= deadline {
+                return None;
+            }
+        }
+    }
+}
+
+/// The number of times the operations are checked before blocking.
+const SPIN_ATTEMPTS: usize = 4;
+
+/// Returns a pseudorandom number in `0..n`.
+fn random_index(n: usize) -> usize {
+    thread_local! {
+        static RNG: Cell<u32> = Cell::new(0x53db1ca7);
+    }
+
+    RNG.try_with(|rng| {
+        // This is the 32-bit variant of Xorshift.
+        let mut x = rng.get();
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        rng.set(x);
+        ((x as u64).wrapping_mul(n as u64) >> 32) as usize
+    })
+    .unwrap_or(0)
+}
@@ -0,0 +1,112 @@
+use super::*;
+use crate::thread;
+use crate::time::{Duration, Instant};
+
+#[test]
+fn smoke() {
+    let (tx, rx) = channel::<i32>();
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv().unwrap(), 1);
+}
+
+#[test]
+fn cloned_receivers() {
+    let (tx, rx) = channel::<u32>();
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || rx.iter().count())
+        })
+        .collect();
+    drop(rx);
+    for i in 0..1000 {
+        tx.send(i).unwrap();
+    }
+    drop(tx);
+    let total: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+    assert_eq!(total, 1000);
+}
+
+#[test]
+fn disconnect_with_cloned_receiver() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    let rx2 = rx.clone();
+    drop(rx);
+    tx.send(1).unwrap();
+    assert_eq!(rx2.recv().unwrap(), 1);
+    drop(rx2);
+    assert!(tx.send(2).is_err());
+}
+
+#[test]
+fn try_iter() {
+    let (tx, rx) = channel::<i32>();
+    for i in 0..3 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), [0, 1, 2]);
+    assert_eq!(rx.try_iter().next(), None);
+}
+
+#[test]
+fn select_try_ready() {
+    let (tx1, rx1) = channel::<i32>();
+    let (_tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let _i2 = sel.recv(&rx2);
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+    tx1.send(1).unwrap();
+    assert_eq!(sel.try_ready(), Ok(i1));
+    assert_eq!(rx1.recv().unwrap(), 1);
+}
+
+#[test]
+fn select_ready() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx2.send(2).unwrap();
+        tx1
+    });
+    let mut sel = Select::new();
+    let _i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    assert_eq!(sel.ready(), i2);
+    assert_eq!(rx2.try_recv().unwrap(), 2);
+    let _tx1 = t.join().unwrap();
+}
+
+#[test]
+fn select_ready_timeout() {
+    let (_tx, rx) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx);
+    let start = Instant::now();
+    assert_eq!(sel.ready_timeout(Duration::from_millis(10)), Err(ReadyTimeoutError));
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+fn select_send() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    let mut sel = Select::new();
+    let i = sel.send(&tx);
+    assert_eq!(sel.ready(), i);
+    tx.send(1).unwrap();
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+    sel.remove(i);
+    let j = sel.recv(&rx);
+    assert_eq!(sel.ready(), j);
+}
+
+#[test]
+fn select_disconnected() {
+    let (tx, rx) = channel::<i32>();
+    let mut sel = Select::new();
+    let i = sel.recv(&rx);
+    drop(tx);
+    assert_eq!(sel.ready(), i);
+    assert!(rx.try_recv().is_err());
+}
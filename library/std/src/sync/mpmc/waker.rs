@@ -63,6 +63,27 @@ pub(crate) fn unregister(&mut self, oper: Operation) -> Option<Entry> {
         }
     }
 
+    /// Returns `true` if there is an entry which can be selected by the current thread.
+    #[inline]
+    pub(crate) fn can_select(&self) -> bool {
+        let thread_id = current_thread_id();
+        self.selectors.iter().any(|entry| {
+            entry.cx.thread_id() != thread_id && entry.cx.selected() == Selected::Waiting
+        })
+    }
+
+    /// Registers an operation waiting to be ready.
+    #[inline]
+    pub(crate) fn watch(&mut self, oper: Operation, cx: &Context) {
+        self.observers.push(Entry { oper, packet: ptr::null_mut(), cx: cx.clone() });
+    }
+
+    /// Unregisters an operation waiting to be ready.
+    #[inline]
+    pub(crate) fn unwatch(&mut self, oper: Operation) {
+        self.observers.retain(|e| e.oper != oper);
+    }
+
     /// Attempts to find another thread's entry, select the operation, and wake it up.
     #[inline]
     pub(crate) fn try_select(&mut self) -> Option<Entry> {
@@ -161,6 +182,24 @@ pub(crate) fn unregister(&self, oper: Operation) -> Option<Entry> {
         entry
     }
 
+    /// Registers an operation waiting to be ready.
+    #[inline]
+    pub(crate) fn watch(&self, oper: Operation, cx: &Context) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.watch(oper, cx);
+        self.is_empty
+            .store(inner.selectors.is_empty() && inner.observers.is_empty(), Ordering::SeqCst);
+    }
+
+    /// Unregisters an operation waiting to be ready.
+    #[inline]
+    pub(crate) fn unwatch(&self, oper: Operation) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unwatch(oper);
+        self.is_empty
+            .store(inner.selectors.is_empty() && inner.observers.is_empty(), Ordering::SeqCst);
+    }
+
     /// Attempts to find one thread (not the current one), select its operation, and wake it up.
     #[inline]
     pub(crate) fn notify(&self) {
@@ -315,4 +315,44 @@ pub(crate) fn is_empty(&self) -> bool {
     pub(crate) fn is_full(&self) -> bool {
         true
     }
+
+    /// Returns `true` if a receive operation can proceed without blocking.
+    pub(crate) fn is_ready_recv(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.senders.can_select() || inner.is_disconnected
+    }
+
+    /// Returns `true` if a send operation can proceed without blocking.
+    pub(crate) fn is_ready_send(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.receivers.can_select() || inner.is_disconnected
+    }
+
+    /// Registers a receive operation waiting to be ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    pub(crate) fn watch_recv(&self, oper: Operation, cx: &Context) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.receivers.watch(oper, cx);
+        inner.senders.can_select() || inner.is_disconnected
+    }
+
+    /// Registers a send operation waiting to be ready.
+    ///
+    /// Returns `true` if the operation is ready already.
+    pub(crate) fn watch_send(&self, oper: Operation, cx: &Context) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        inner.senders.watch(oper, cx);
+        inner.receivers.can_select() || inner.is_disconnected
+    }
+
+    /// Unregisters a receive operation waiting to be ready.
+    pub(crate) fn unwatch_recv(&self, oper: Operation) {
+        self.inner.lock().unwrap().receivers.unwatch(oper);
+    }
+
+    /// Unregisters a send operation waiting to be ready.
+    pub(crate) fn unwatch_send(&self, oper: Operation) {
+        self.inner.lock().unwrap().senders.unwatch(oper);
+    }
 }
@@ -6,6 +6,7 @@
 use crate::ops::{Deref, DerefMut};
 use crate::sync::{poison, LockResult, TryLockError, TryLockResult};
 use crate::sys::locks as sys;
+use crate::time::Duration;
 
 /// A mutual exclusion primitive useful for protecting shared data
 ///
@@ -315,6 +316,60 @@ pub fn try_lock(&self) -> TryLockResult<MutexGuard<'_, T>> {
         }
     }
 
+    /// Attempts to acquire this lock, blocking the current thread for at most
+    /// `timeout`.
+    ///
+    /// If the lock could not be acquired before the timeout passed, then
+    /// [`Err`] is returned. Otherwise, an RAII guard is returned. The lock
+    /// will be unlocked when the guard is dropped.
+    ///
+    /// The wait may last slightly longer than `timeout` before giving up,
+    /// depending on the precision of the platform's clocks and timers.
+    ///
+    /// # Errors
+    ///
+    /// If another user of this mutex panicked while holding the mutex, then
+    /// this call will return the [`Poisoned`] error if the mutex would
+    /// otherwise be acquired.
+    ///
+    /// If the mutex could not be acquired before the timeout passed, then
+    /// this call will return the [`WouldBlock`] error.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::{Mutex, TryLockError};
+    /// use std::time::Duration;
+    ///
+    /// let mutex = Mutex::new(0);
+    ///
+    /// let guard = mutex.lock().unwrap();
+    /// assert!(matches!(
+    ///     mutex.lock_timeout(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    /// drop(guard);
+    ///
+    /// *mutex.lock_timeout(Duration::from_millis(10)).unwrap() += 1;
+    /// assert_eq!(*mutex.lock().unwrap(), 1);
+    /// ```
+    // FIXME(lock_timeout): open a tracking issue and use it instead of `issue = "none"`.
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn lock_timeout(&self, timeout: Duration) -> TryLockResult<MutexGuard<'_, T>> {
+        unsafe {
+            if self.inner.lock_timeout(timeout) {
+                Ok(MutexGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Immediately drops the guard, and consequently unlocks the mutex.
     ///
     /// This function is equivalent to calling [`drop`] on the guard but is more self-documenting.
@@ -1,7 +1,8 @@
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::channel;
-use crate::sync::{Arc, Condvar, Mutex};
+use crate::sync::{Arc, Condvar, Mutex, TryLockError};
 use crate::thread;
+use crate::time::Duration;
 
 struct Packet<T>(Arc<(Mutex<T>, Condvar)>);
 
@@ -57,6 +58,34 @@ fn try_lock() {
     *m.try_lock().unwrap() = ();
 }
 
+#[test]
+fn lock_timeout() {
+    let m = Arc::new(Mutex::new(0));
+    let guard = m.lock().unwrap();
+
+    let m2 = m.clone();
+    thread::spawn(move || {
+        assert!(matches!(
+            m2.lock_timeout(Duration::from_millis(10)),
+            Err(TryLockError::WouldBlock)
+        ));
+    })
+    .join()
+    .unwrap();
+
+    let (tx, rx) = channel();
+    let m2 = m.clone();
+    let t = thread::spawn(move || {
+        tx.send(()).unwrap();
+        *m2.lock_timeout(Duration::from_secs(60)).unwrap() += 1;
+    });
+    rx.recv().unwrap();
+    drop(guard);
+    t.join().unwrap();
+
+    assert_eq!(*m.lock_timeout(Duration::ZERO).unwrap(), 1);
+}
+
 #[test]
 fn test_into_inner() {
     let m = Mutex::new(NonCopy(10));
@@ -3,10 +3,12 @@
 
 use crate::cell::UnsafeCell;
 use crate::fmt;
+use crate::mem;
 use crate::ops::{Deref, DerefMut};
 use crate::ptr::NonNull;
 use crate::sync::{poison, LockResult, TryLockError, TryLockResult};
 use crate::sys::locks as sys;
+use crate::time::Duration;
 
 /// A reader-writer lock
 ///
@@ -144,6 +146,40 @@ impl<T: ?Sized> !Send for RwLockWriteGuard<'_, T> {}
 #[stable(feature = "rwlock_guard_sync", since = "1.23.0")]
 unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
 
+/// RAII structure used to release the upgradable read access of a lock when
+/// dropped.
+///
+/// An upgradable read lock shares the lock with plain readers, but at most one
+/// upgradable reader can hold the lock at a time. It can be turned into a write
+/// lock with [`RwLockUpgradableReadGuard::upgrade`] without letting any writer
+/// in between.
+///
+/// On some platforms an upgradable read lock holds the lock exclusively from
+/// the start, which also keeps plain readers out. See
+/// [`RwLock::upgradable_read`] for details.
+///
+/// This structure is created by the [`upgradable_read`] and
+/// [`try_upgradable_read`] methods on [`RwLock`].
+///
+/// [`upgradable_read`]: RwLock::upgradable_read
+/// [`try_upgradable_read`]: RwLock::try_upgradable_read
+#[must_use = "if unused the RwLock will immediately unlock"]
+#[must_not_suspend = "holding a RwLockUpgradableReadGuard across suspend \
+                      points can cause deadlocks, delays, \
+                      and cause Futures to not implement `Send`"]
+// FIXME(rwlock_upgradable_read): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+#[clippy::has_significant_drop]
+pub struct RwLockUpgradableReadGuard<'a, T: ?Sized + 'a> {
+    lock: &'a RwLock<T>,
+}
+
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+impl<T: ?Sized> !Send for RwLockUpgradableReadGuard<'_, T> {}
+
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+unsafe impl<T: ?Sized + Sync> Sync for RwLockUpgradableReadGuard<'_, T> {}
+
 impl<T> RwLock<T> {
     /// Creates a new instance of an `RwLock<T>` which is unlocked.
     ///
@@ -260,6 +296,54 @@ pub fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, T>> {
         }
     }
 
+    /// Attempts to acquire this `RwLock` with shared read access, blocking the
+    /// current thread for at most `timeout`.
+    ///
+    /// If the access could not be granted before the timeout passed, then
+    /// `Err` is returned. Otherwise, an RAII guard is returned which will
+    /// release the shared access when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the timeout passed.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::RwLock;
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let w = lock.write().unwrap();
+    /// assert!(lock.read_timeout(Duration::from_millis(10)).is_err());
+    /// drop(w);
+    ///
+    /// assert_eq!(*lock.read_timeout(Duration::from_millis(10)).unwrap(), 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn read_timeout(&self, timeout: Duration) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        unsafe {
+            if self.inner.read_timeout(timeout) {
+                Ok(RwLockReadGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Locks this `RwLock` with exclusive write access, blocking the current
     /// thread until it can be acquired.
     ///
@@ -349,6 +433,160 @@ pub fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, T>> {
         }
     }
 
+    /// Attempts to lock this `RwLock` with exclusive write access, blocking the
+    /// current thread for at most `timeout`.
+    ///
+    /// If the lock could not be acquired before the timeout passed, then `Err`
+    /// is returned. Otherwise, an RAII guard is returned which will release the
+    /// lock when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired before the timeout passed.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::RwLock;
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let r = lock.read().unwrap();
+    /// assert!(lock.write_timeout(Duration::from_millis(10)).is_err());
+    /// drop(r);
+    ///
+    /// *lock.write_timeout(Duration::from_millis(10)).unwrap() = 2;
+    /// assert_eq!(*lock.read().unwrap(), 2);
+    /// ```
+    #[inline]
+    #[unstable(feature = "lock_timeout", issue = "none")]
+    pub fn write_timeout(&self, timeout: Duration) -> TryLockResult<RwLockWriteGuard<'_, T>> {
+        unsafe {
+            if self.inner.write_timeout(timeout) {
+                Ok(RwLockWriteGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
+    /// Locks this `RwLock` with upgradable read access, blocking the current
+    /// thread until it can be acquired.
+    ///
+    /// Upgradable read access is shared with plain readers, but not with
+    /// writers or other upgradable readers. The returned guard can later be
+    /// turned into a write guard with [`RwLockUpgradableReadGuard::upgrade`],
+    /// which guarantees that no writer modifies the data in between.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Upgradable read locks are only shared with plain readers where `RwLock`
+    /// is implemented with futexes: currently on Linux, Android, FreeBSD,
+    /// OpenBSD, DragonFly BSD, Fuchsia, Hermit and WebAssembly with atomics.
+    /// Everywhere else, including macOS and Windows, this locks the `RwLock`
+    /// exclusively right away, like [`write`] does, so plain readers are kept
+    /// out until the guard is dropped. Upgrading such a guard never blocks.
+    ///
+    /// [`write`]: RwLock::write
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `RwLock` is poisoned. An
+    /// `RwLock` is poisoned whenever a writer panics while holding an exclusive
+    /// lock. The failure will occur immediately after the lock has been
+    /// acquired.
+    ///
+    /// # Panics
+    ///
+    /// This function might panic when called if the lock is already held by the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable_read)]
+    ///
+    /// use std::sync::{RwLock, RwLockUpgradableReadGuard};
+    ///
+    /// let lock = RwLock::new(vec![1, 2]);
+    ///
+    /// let v = lock.upgradable_read().unwrap();
+    /// if !v.contains(&3) {
+    ///     let mut v = RwLockUpgradableReadGuard::upgrade(v);
+    ///     v.push(3);
+    /// }
+    /// assert_eq!(*lock.read().unwrap(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+    pub fn upgradable_read(&self) -> LockResult<RwLockUpgradableReadGuard<'_, T>> {
+        unsafe {
+            self.inner.upgradable_read();
+            RwLockUpgradableReadGuard::new(self)
+        }
+    }
+
+    /// Attempts to acquire this `RwLock` with upgradable read access.
+    ///
+    /// If the access could not be granted at this time, then `Err` is returned.
+    /// Otherwise, an RAII guard is returned which will release the access when
+    /// it is dropped.
+    ///
+    /// This function does not block.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the `RwLock` is
+    /// poisoned. An `RwLock` is poisoned whenever a writer panics while holding
+    /// an exclusive lock. `Poisoned` will only be returned if the lock would
+    /// have otherwise been acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the `RwLock` could
+    /// not be acquired because it was already locked exclusively or by another
+    /// upgradable reader. On the platforms where upgradable read locks are not
+    /// shared with plain readers, as listed for [`upgradable_read`], this also
+    /// happens if the `RwLock` is locked by any reader.
+    ///
+    /// [`Poisoned`]: TryLockError::Poisoned
+    /// [`WouldBlock`]: TryLockError::WouldBlock
+    /// [`upgradable_read`]: RwLock::upgradable_read
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable_read)]
+    ///
+    /// use std::sync::RwLock;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let u = lock.try_upgradable_read().unwrap();
+    /// assert_eq!(*u, 1);
+    /// assert!(lock.try_upgradable_read().is_err());
+    /// ```
+    #[inline]
+    #[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+    pub fn try_upgradable_read(&self) -> TryLockResult<RwLockUpgradableReadGuard<'_, T>> {
+        unsafe {
+            if self.inner.try_upgradable_read() {
+                Ok(RwLockUpgradableReadGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Determines whether the lock is poisoned.
     ///
     /// If another thread is active, the lock can still become poisoned at any
@@ -537,6 +775,59 @@ unsafe fn new(lock: &'rwlock RwLock<T>) -> LockResult<RwLockWriteGuard<'rwlock,
     }
 }
 
+impl<'rwlock, T: ?Sized> RwLockUpgradableReadGuard<'rwlock, T> {
+    /// Create a new instance of `RwLockUpgradableReadGuard<T>` from a `RwLock<T>`.
+    // SAFETY: if and only if `lock.inner.upgradable_read()` (or
+    // `lock.inner.try_upgradable_read()`) has been successfully called from the
+    // same thread before instantiating this object.
+    unsafe fn new(lock: &'rwlock RwLock<T>) -> LockResult<RwLockUpgradableReadGuard<'rwlock, T>> {
+        poison::map_result(lock.poison.borrow(), |()| RwLockUpgradableReadGuard { lock })
+    }
+
+    /// Upgrades the upgradable read access to exclusive write access, blocking
+    /// the current thread until all other readers have released the lock.
+    ///
+    /// No writer can acquire the lock between the upgradable read lock and the
+    /// returned write lock, so anything observed through `orig` still holds.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `RwLockUpgradableReadGuard::upgrade(...)`. A method would interfere with
+    /// methods of the same name on the contents of the `RwLock` used through
+    /// `Deref`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(rwlock_upgradable_read)]
+    ///
+    /// use std::sync::{RwLock, RwLockUpgradableReadGuard};
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let n = lock.upgradable_read().unwrap();
+    /// let mut n = RwLockUpgradableReadGuard::upgrade(n);
+    /// *n += 1;
+    /// drop(n);
+    ///
+    /// assert_eq!(*lock.read().unwrap(), 2);
+    /// ```
+    #[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+    pub fn upgrade(orig: Self) -> RwLockWriteGuard<'rwlock, T> {
+        let lock = orig.lock;
+        mem::forget(orig);
+        // SAFETY: the conditions of `RwLockUpgradableReadGuard::new` were
+        // satisfied when `orig` was created, and it is not unlocked by its drop.
+        unsafe { lock.inner.upgrade() };
+        // Any poisoning was already reported when `orig` was created, and no
+        // writer can have held the lock since.
+        let poison = match lock.poison.guard() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        RwLockWriteGuard { lock, poison }
+    }
+}
+
 #[stable(feature = "std_debug", since = "1.16.0")]
 impl<T: fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -565,6 +856,20 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+impl<T: fmt::Debug> fmt::Debug for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+impl<T: ?Sized + fmt::Display> fmt::Display for RwLockUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
     type Target = T;
@@ -585,6 +890,16 @@ fn deref(&self) -> &T {
     }
 }
 
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+impl<T: ?Sized> Deref for RwLockUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the conditions of `RwLockUpgradableReadGuard::new` were satisfied when created.
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
     fn deref_mut(&mut self) -> &mut T {
@@ -613,3 +928,13 @@ fn drop(&mut self) {
         }
     }
 }
+
+#[unstable(feature = "rwlock_upgradable_read", issue = "none")]
+impl<T: ?Sized> Drop for RwLockUpgradableReadGuard<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the conditions of `RwLockUpgradableReadGuard::new` were satisfied when created.
+        unsafe {
+            self.lock.inner.upgradable_unlock();
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::channel;
-use crate::sync::{Arc, RwLock, RwLockReadGuard, TryLockError};
+use crate::sync::{Arc, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, TryLockError};
 use crate::thread;
+use crate::time::Duration;
 use rand::Rng;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -181,6 +182,101 @@ fn test_rwlock_try_write() {
     drop(read_guard);
 }
 
+#[test]
+fn test_rwlock_timeouts() {
+    let lock = Arc::new(RwLock::new(0));
+
+    let write_guard = lock.write().unwrap();
+    assert!(matches!(lock.read_timeout(Duration::from_millis(10)), Err(TryLockError::WouldBlock)));
+    assert!(matches!(lock.write_timeout(Duration::from_millis(10)), Err(TryLockError::WouldBlock)));
+
+    let (tx, rx) = channel();
+    let lock2 = lock.clone();
+    let t = thread::spawn(move || {
+        tx.send(()).unwrap();
+        *lock2.write_timeout(Duration::from_secs(60)).unwrap() += 1;
+    });
+    rx.recv().unwrap();
+    drop(write_guard);
+    t.join().unwrap();
+
+    let read_guard = lock.read_timeout(Duration::ZERO).unwrap();
+    assert_eq!(*read_guard, 1);
+    assert!(matches!(lock.write_timeout(Duration::ZERO), Err(TryLockError::WouldBlock)));
+    drop(read_guard);
+}
+
+#[test]
+fn test_rwlock_write_timeout_lets_readers_in() {
+    let lock = Arc::new(RwLock::new(0));
+    let read_guard = lock.read().unwrap();
+
+    // A reader that queues up behind the waiting writer gets in once the writer gives up,
+    // without waiting for the first reader to leave.
+    let lock2 = lock.clone();
+    let writer = thread::spawn(move || lock2.write_timeout(Duration::from_millis(200)).is_ok());
+    thread::sleep(Duration::from_millis(50));
+    let (tx, rx) = channel();
+    let lock2 = lock.clone();
+    let reader = thread::spawn(move || tx.send(*lock2.read().unwrap()).unwrap());
+    assert_eq!(rx.recv_timeout(Duration::from_secs(60)), Ok(0));
+    assert!(!writer.join().unwrap());
+    reader.join().unwrap();
+
+    drop(lock.try_read().unwrap());
+    drop(read_guard);
+}
+
+#[test]
+fn test_rwlock_upgradable_read() {
+    let lock = RwLock::new(vec![1]);
+
+    let guard = lock.upgradable_read().unwrap();
+    assert_eq!(*guard, [1]);
+    assert!(matches!(lock.try_upgradable_read(), Err(TryLockError::WouldBlock)));
+    assert!(matches!(lock.try_write(), Err(TryLockError::WouldBlock)));
+
+    let mut guard = RwLockUpgradableReadGuard::upgrade(guard);
+    guard.push(2);
+    assert!(matches!(lock.try_read(), Err(TryLockError::WouldBlock)));
+    drop(guard);
+
+    drop(lock.try_upgradable_read().unwrap());
+    assert_eq!(*lock.read().unwrap(), [1, 2]);
+}
+
+#[test]
+fn test_rwlock_upgrade_contended() {
+    const N: usize = 10;
+    const M: usize = if cfg!(miri) { 20 } else { 200 };
+
+    let lock = Arc::new(RwLock::new(0));
+
+    let (tx, rx) = channel::<()>();
+    for i in 0..N {
+        let tx = tx.clone();
+        let lock = lock.clone();
+        thread::spawn(move || {
+            for _ in 0..M {
+                if i % 2 == 0 {
+                    let guard = lock.upgradable_read().unwrap();
+                    let n = *guard;
+                    let mut guard = RwLockUpgradableReadGuard::upgrade(guard);
+                    // No writer got in between.
+                    assert_eq!(*guard, n);
+                    *guard += 1;
+                } else {
+                    drop(lock.read().unwrap());
+                }
+            }
+            drop(tx);
+        });
+    }
+    drop(tx);
+    let _ = rx.recv();
+    assert_eq!(*lock.read().unwrap(), N / 2 * M);
+}
+
 #[test]
 fn test_into_inner() {
     let m = RwLock::new(NonCopy(10));
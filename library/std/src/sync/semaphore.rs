@@ -0,0 +1,283 @@
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests;
+
+use crate::fmt;
+use crate::sys_common::semaphore as sys;
+use crate::time::Duration;
+
+/// A counting semaphore.
+///
+/// A semaphore holds a number of permits. [`acquire`] takes one of them,
+/// blocking the current thread until one is available, and returns a
+/// [`SemaphorePermit`] which gives the permit back when it is dropped. This
+/// makes a semaphore a convenient way to limit how many threads can use some
+/// resource at the same time.
+///
+/// Permits can also be added with [`add_permits`], or taken out of
+/// circulation with [`SemaphorePermit::forget`], which allows a semaphore to
+/// be used as a signal between threads.
+///
+/// [`acquire`]: Semaphore::acquire
+/// [`add_permits`]: Semaphore::add_permits
+///
+/// # Platform-specific behavior
+///
+/// Permits are always taken and given back without locking. Threads that have
+/// to wait for a permit sleep on a futex on Linux, Android, FreeBSD, OpenBSD,
+/// DragonFly, Fuchsia, Hermit and WebAssembly with atomics. On other platforms,
+/// including macOS and Windows, they sleep on a [`Mutex`] and [`Condvar`],
+/// which makes contended waits slower.
+///
+/// [`Mutex`]: crate::sync::Mutex
+/// [`Condvar`]: crate::sync::Condvar
+///
+/// # Examples
+///
+/// ```
+/// #![feature(sync_semaphore)]
+///
+/// use std::sync::Semaphore;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+///
+/// // At most two threads get to do the work at a time.
+/// let semaphore = Semaphore::new(2);
+/// let active = AtomicUsize::new(0);
+///
+/// thread::scope(|s| {
+///     for _ in 0..10 {
+///         s.spawn(|| {
+///             let _permit = semaphore.acquire();
+///             assert!(active.fetch_add(1, Ordering::SeqCst) < 2);
+///             // ...
+///             active.fetch_sub(1, Ordering::SeqCst);
+///         });
+///     }
+/// });
+/// ```
+// FIXME(sync_semaphore): open a tracking issue and use it instead of `issue = "none"`.
+#[unstable(feature = "sync_semaphore", issue = "none")]
+pub struct Semaphore {
+    inner: sys::Semaphore,
+}
+
+/// RAII structure used to give a permit back to its [`Semaphore`] when
+/// dropped.
+///
+/// This structure is created by the [`acquire`], [`try_acquire`] and
+/// [`acquire_timeout`] methods on [`Semaphore`].
+///
+/// [`acquire`]: Semaphore::acquire
+/// [`try_acquire`]: Semaphore::try_acquire
+/// [`acquire_timeout`]: Semaphore::acquire_timeout
+#[must_use = "if unused the permit will immediately be released"]
+#[unstable(feature = "sync_semaphore", issue = "none")]
+#[clippy::has_significant_drop]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    /// The largest number of permits a semaphore can hold.
+    ///
+    /// [`Semaphore::add_permits`] panics if it would exceed this, but a
+    /// [`SemaphorePermit`] that is dropped when the semaphore is already full
+    /// is silently discarded instead.
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub const MAX_PERMITS: usize = u32::MAX as usize;
+
+    /// Creates a new semaphore with the given number of permits available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` exceeds [`Semaphore::MAX_PERMITS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(3);
+    /// assert_eq!(semaphore.available_permits(), 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub const fn new(permits: usize) -> Semaphore {
+        assert!(permits <= Self::MAX_PERMITS, "too many semaphore permits");
+        Semaphore { inner: sys::Semaphore::new(permits as u32) }
+    }
+
+    /// Acquires a permit, blocking the current thread until one is available.
+    ///
+    /// The permit is given back when the returned [`SemaphorePermit`] is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(1);
+    ///
+    /// let permit = semaphore.acquire();
+    /// assert_eq!(semaphore.available_permits(), 0);
+    /// drop(permit);
+    /// assert_eq!(semaphore.available_permits(), 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn acquire(&self) -> SemaphorePermit<'_> {
+        self.inner.acquire();
+        SemaphorePermit { semaphore: self }
+    }
+
+    /// Attempts to acquire a permit without blocking.
+    ///
+    /// Returns [`None`] if no permit is available right now.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(1);
+    ///
+    /// let permit = semaphore.try_acquire().unwrap();
+    /// assert!(semaphore.try_acquire().is_none());
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.inner.try_acquire().then(|| SemaphorePermit { semaphore: self })
+    }
+
+    /// Acquires a permit, blocking the current thread for at most `timeout`.
+    ///
+    /// Returns [`None`] if no permit became available before the timeout
+    /// passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    /// use std::time::Duration;
+    ///
+    /// let semaphore = Semaphore::new(0);
+    /// assert!(semaphore.acquire_timeout(Duration::from_millis(10)).is_none());
+    ///
+    /// semaphore.add_permits(1);
+    /// assert!(semaphore.acquire_timeout(Duration::from_millis(10)).is_some());
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn acquire_timeout(&self, timeout: Duration) -> Option<SemaphorePermit<'_>> {
+        self.inner.acquire_timeout(timeout).then(|| SemaphorePermit { semaphore: self })
+    }
+
+    /// Adds `n` permits to the semaphore, waking up threads waiting for them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this would make the number of permits exceed
+    /// [`Semaphore::MAX_PERMITS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    /// use std::thread;
+    ///
+    /// let semaphore = Semaphore::new(0);
+    ///
+    /// thread::scope(|s| {
+    ///     s.spawn(|| semaphore.acquire().forget());
+    ///     semaphore.add_permits(1);
+    /// });
+    /// assert_eq!(semaphore.available_permits(), 0);
+    /// ```
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn add_permits(&self, n: usize) {
+        let added = u32::try_from(n).is_ok_and(|n| self.inner.release(n));
+        assert!(added, "semaphore permit count overflow");
+    }
+
+    /// Returns the number of permits that can currently be acquired without
+    /// blocking.
+    ///
+    /// If other threads are using the semaphore, this can change at any time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(2);
+    /// let _permit = semaphore.acquire();
+    /// assert_eq!(semaphore.available_permits(), 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn available_permits(&self) -> usize {
+        self.inner.available() as usize
+    }
+}
+
+#[unstable(feature = "sync_semaphore", issue = "none")]
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore").field("permits", &self.available_permits()).finish()
+    }
+}
+
+impl SemaphorePermit<'_> {
+    /// Consumes the permit without giving it back to the semaphore.
+    ///
+    /// This permanently reduces the number of permits of the semaphore by
+    /// one, until it is restored with [`Semaphore::add_permits`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(sync_semaphore)]
+    ///
+    /// use std::sync::Semaphore;
+    ///
+    /// let semaphore = Semaphore::new(1);
+    /// semaphore.acquire().forget();
+    /// assert_eq!(semaphore.available_permits(), 0);
+    /// ```
+    #[unstable(feature = "sync_semaphore", issue = "none")]
+    pub fn forget(self) {
+        crate::mem::forget(self);
+    }
+}
+
+#[unstable(feature = "sync_semaphore", issue = "none")]
+impl Drop for SemaphorePermit<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // Don't panic in drop, which would abort if we're already unwinding.
+        // The count saturates at `MAX_PERMITS` instead.
+        let _ = self.semaphore.inner.release(1);
+    }
+}
+
+#[unstable(feature = "sync_semaphore", issue = "none")]
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,105 @@
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::mpsc::channel;
+use crate::sync::{Arc, Semaphore};
+use crate::thread;
+use crate::time::Duration;
+
+#[test]
+fn smoke() {
+    let s = Semaphore::new(2);
+    let a = s.acquire();
+    let b = s.try_acquire().unwrap();
+    assert!(s.try_acquire().is_none());
+    assert_eq!(s.available_permits(), 0);
+    drop((a, b));
+    assert_eq!(s.available_permits(), 2);
+}
+
+#[test]
+fn forget_and_add_permits() {
+    let s = Semaphore::new(1);
+    s.acquire().forget();
+    assert!(s.try_acquire().is_none());
+    s.add_permits(3);
+    assert_eq!(s.available_permits(), 3);
+}
+
+#[test]
+#[should_panic = "semaphore permit count overflow"]
+fn add_permits_overflow() {
+    let s = Semaphore::new(Semaphore::MAX_PERMITS);
+    s.add_permits(1);
+}
+
+#[test]
+fn drop_permit_when_full() {
+    let s = Semaphore::new(Semaphore::MAX_PERMITS);
+    let permit = s.acquire();
+    s.add_permits(1);
+    drop(permit);
+    assert_eq!(s.available_permits(), Semaphore::MAX_PERMITS);
+}
+
+#[test]
+fn acquire_timeout() {
+    let s = Arc::new(Semaphore::new(0));
+    assert!(s.acquire_timeout(Duration::from_millis(10)).is_none());
+
+    let (tx, rx) = channel();
+    let s2 = s.clone();
+    let t = thread::spawn(move || {
+        tx.send(()).unwrap();
+        s2.acquire_timeout(Duration::from_secs(60)).unwrap().forget();
+    });
+    rx.recv().unwrap();
+    s.add_permits(1);
+    t.join().unwrap();
+    assert_eq!(s.available_permits(), 0);
+}
+
+#[test]
+fn limits_concurrency() {
+    const N: usize = 8;
+    const M: usize = if cfg!(miri) { 20 } else { 200 };
+    const PERMITS: usize = 3;
+
+    let s = Arc::new(Semaphore::new(PERMITS));
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let threads: Vec<_> = (0..N)
+        .map(|_| {
+            let s = s.clone();
+            let active = active.clone();
+            thread::spawn(move || {
+                for _ in 0..M {
+                    let _permit = s.acquire();
+                    assert!(active.fetch_add(1, Ordering::SeqCst) < PERMITS);
+                    thread::yield_now();
+                    active.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(s.available_permits(), PERMITS);
+}
+
+#[test]
+fn wakes_all_added_permits() {
+    const N: usize = 5;
+
+    let s = Arc::new(Semaphore::new(0));
+    let threads: Vec<_> = (0..N)
+        .map(|_| {
+            let s = s.clone();
+            thread::spawn(move || s.acquire().forget())
+        })
+        .collect();
+    s.add_permits(N);
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(s.available_permits(), 0);
+}
@@ -0,0 +1,65 @@
+//! Fallbacks for lock operations that not all platforms support natively.
+
+use crate::thread;
+use crate::time::{Duration, Instant};
+
+/// Calls `try_lock` until it succeeds or `timeout` has passed, backing off
+/// between the attempts.
+///
+/// Returns whether `try_lock` succeeded.
+pub fn try_lock_until(timeout: Duration, mut try_lock: impl FnMut() -> bool) -> bool {
+    const MAX_INTERVAL: Duration = Duration::from_millis(1);
+
+    let deadline = Deadline::after(Some(timeout));
+
+    if try_lock() {
+        return true;
+    }
+
+    // Spin and yield for a bit first, in case the lock is released quickly.
+    for i in 0..16 {
+        if i < 10 {
+            crate::hint::spin_loop();
+        } else {
+            thread::yield_now();
+        }
+        if try_lock() {
+            return true;
+        }
+    }
+
+    let mut interval = Duration::from_micros(10);
+    loop {
+        let Some(remaining) = deadline.time_left() else { return try_lock() };
+        thread::sleep(interval.min(remaining.unwrap_or(MAX_INTERVAL)));
+        if try_lock() {
+            return true;
+        }
+        interval = (interval * 2).min(MAX_INTERVAL);
+    }
+}
+
+/// The point in time at which a wait with an optional timeout ends.
+#[derive(Clone, Copy)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Starts a wait that ends after `timeout`, or never if it is `None`.
+    ///
+    /// A timeout that overflows the clock is treated as an infinite one.
+    pub fn after(timeout: Option<Duration>) -> Deadline {
+        Deadline(timeout.and_then(|timeout| Instant::now().checked_add(timeout)))
+    }
+
+    /// Returns the timeout for the next sleep of the wait, which is `None` if the
+    /// wait has no deadline, or returns `None` if the deadline has passed.
+    pub fn time_left(self) -> Option<Option<Duration>> {
+        match self.0 {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => Some(Some(remaining)),
+                _ => None,
+            },
+            None => Some(None),
+        }
+    }
+}
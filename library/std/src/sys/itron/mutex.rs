@@ -4,7 +4,9 @@
     abi,
     error::{expect_success, expect_success_aborting, fail, ItronError},
     spin::SpinIdOnceCell,
+    time::with_tmos,
 };
+use crate::time::Duration;
 
 pub struct Mutex {
     /// The ID of the underlying mutex object
@@ -57,6 +59,17 @@ pub fn try_lock(&self) -> bool {
             }
         }
     }
+
+    pub fn lock_timeout(&self, timeout: Duration) -> bool {
+        let mtx = self.raw();
+        match with_tmos(timeout, |tmo| unsafe { abi::tloc_mtx(mtx, tmo) }) {
+            abi::E_TMOUT => false,
+            er => {
+                expect_success(er, &"tloc_mtx");
+                true
+            }
+        }
+    }
 }
 
 impl Drop for Mutex {
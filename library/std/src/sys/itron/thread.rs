@@ -366,3 +366,5 @@ unsafe fn terminate_and_delete_current_task() -> ! {
 pub fn available_parallelism() -> io::Result<crate::num::NonZeroUsize> {
     super::unsupported()
 }
+
+pub use crate::sys_common::thread::{affinity, set_affinity, set_priority};
@@ -1,5 +1,7 @@
 use super::waitqueue::{try_lock_or_false, SpinMutex, WaitQueue, WaitVariable};
+use crate::sys::common::locks::try_lock_until;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Duration;
 
 /// FIXME: `UnsafeList` is not movable.
 struct AllocatedMutex(SpinMutex<WaitVariable<bool>>);
@@ -56,4 +58,11 @@ pub fn try_lock(&self) -> bool {
             true
         }
     }
+
+    // Waiting on the queue with a timeout would require removing ourselves
+    // from it again, so poll `try_lock` instead.
+    #[inline]
+    pub fn lock_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_lock())
+    }
 }
@@ -2,7 +2,9 @@
 mod tests;
 
 use crate::num::NonZeroUsize;
+use crate::sys::common::locks::try_lock_until;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Duration;
 
 use super::waitqueue::{
     try_lock_or_false, NotifiedTcs, SpinMutex, SpinMutexGuard, WaitQueue, WaitVariable,
@@ -126,6 +128,37 @@ unsafe fn __read_unlock(
         }
     }
 
+    #[inline]
+    pub fn read_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || unsafe { self.try_read() })
+    }
+
+    #[inline]
+    pub fn write_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_write())
+    }
+
+    // An upgradable reader holds the write lock from the start, as there is
+    // no way to turn a read lock into a write lock without releasing it.
+
+    #[inline]
+    pub fn upgradable_read(&self) {
+        self.write()
+    }
+
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        self.try_write()
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        unsafe { self.write_unlock() }
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {}
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         let lock = &*self.inner;
@@ -6,6 +6,8 @@
         spin::SpinIdOnceCell,
     },
 };
+use crate::sys::common::locks::try_lock_until;
+use crate::time::Duration;
 
 pub struct RwLock {
     /// The ID of the underlying mutex object
@@ -70,6 +72,39 @@ pub fn try_write(&self) -> bool {
         }
     }
 
+    // The SOLID extension has no timed variants of `rwl_loc_*`.
+
+    #[inline]
+    pub fn read_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_read())
+    }
+
+    #[inline]
+    pub fn write_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_write())
+    }
+
+    // Nor can it upgrade a read lock, so an upgradable reader holds the
+    // write lock from the start.
+
+    #[inline]
+    pub fn upgradable_read(&self) {
+        self.write()
+    }
+
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        self.try_write()
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        unsafe { self.write_unlock() }
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {}
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         let rwl = self.raw();
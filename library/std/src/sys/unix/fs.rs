@@ -93,7 +93,7 @@
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "l4re"))]
 use libc::{dirent64, fstat64, ftruncate64, lseek64, lstat64, off64_t, open64, stat64};
 
-pub use crate::sys_common::fs::try_exists;
+pub use crate::sys_common::fs::{copy_symlink, set_dir_times, try_exists};
 
 pub struct File(FileDesc);
 
@@ -1182,6 +1182,26 @@ pub fn set_permissions(&self, perm: FilePermissions) -> io::Result<()> {
         Ok(())
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        file_lock::lock(self.as_raw_fd(), true)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        file_lock::lock(self.as_raw_fd(), false)
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        file_lock::try_lock(self.as_raw_fd(), true)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        file_lock::try_lock(self.as_raw_fd(), false)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        file_lock::unlock(self.as_raw_fd())
+    }
+
     pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
         #[cfg(not(any(target_os = "redox", target_os = "espidf", target_os = "horizon")))]
         let to_timespec = |time: Option<SystemTime>| {
@@ -1238,6 +1258,115 @@ fn ts_to_tv(ts: &libc::timespec) -> libc::timeval {
     }
 }
 
+/// Advisory whole-file locks. `flock` locks belong to the open file description, so they
+/// behave like the locks of other platforms. Where `flock` is missing, POSIX record locks
+/// (`fcntl` with `F_SETLK`) are used instead.
+mod file_lock {
+    use crate::io;
+    use crate::sys::{cvt, cvt_r};
+    use libc::c_int;
+
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "watchos",
+        ))] {
+            pub fn lock(fd: c_int, exclusive: bool) -> io::Result<()> {
+                cvt_r(|| unsafe { libc::flock(fd, operation(exclusive)) })?;
+                Ok(())
+            }
+
+            pub fn try_lock(fd: c_int, exclusive: bool) -> io::Result<bool> {
+                match cvt(unsafe { libc::flock(fd, operation(exclusive) | libc::LOCK_NB) }) {
+                    Ok(_) => Ok(true),
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+                    Err(e) => Err(e),
+                }
+            }
+
+            pub fn unlock(fd: c_int) -> io::Result<()> {
+                cvt(unsafe { libc::flock(fd, libc::LOCK_UN) })?;
+                Ok(())
+            }
+
+            fn operation(exclusive: bool) -> c_int {
+                if exclusive { libc::LOCK_EX } else { libc::LOCK_SH }
+            }
+        } else if #[cfg(any(
+            target_os = "aix",
+            target_os = "emscripten",
+            target_os = "haiku",
+            target_os = "illumos",
+            target_os = "nto",
+            target_os = "solaris",
+        ))] {
+            // Record locks belong to the process rather than the file handle: they never
+            // conflict with other locks of the same process, and closing any handle to the
+            // file releases them. A shared lock needs the file to be open for reading, and an
+            // exclusive lock needs it to be open for writing.
+
+            pub fn lock(fd: c_int, exclusive: bool) -> io::Result<()> {
+                set_lock(fd, libc::F_SETLKW, lock_type(exclusive))
+            }
+
+            pub fn try_lock(fd: c_int, exclusive: bool) -> io::Result<bool> {
+                match set_lock(fd, libc::F_SETLK, lock_type(exclusive)) {
+                    Ok(()) => Ok(true),
+                    // POSIX allows either error for a lock held by another process.
+                    Err(e) if matches!(e.raw_os_error(), Some(libc::EAGAIN | libc::EACCES)) => {
+                        Ok(false)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+
+            pub fn unlock(fd: c_int) -> io::Result<()> {
+                set_lock(fd, libc::F_SETLK, libc::F_UNLCK as c_int)
+            }
+
+            fn lock_type(exclusive: bool) -> c_int {
+                if exclusive { libc::F_WRLCK as c_int } else { libc::F_RDLCK as c_int }
+            }
+
+            fn set_lock(fd: c_int, cmd: c_int, ty: c_int) -> io::Result<()> {
+                let mut flock: libc::flock = unsafe { crate::mem::zeroed() };
+                flock.l_type = ty as _;
+                flock.l_whence = libc::SEEK_SET as _;
+                // A zero `l_start` and `l_len` cover the whole file, however much it grows.
+                cvt_r(|| unsafe { libc::fcntl(fd, cmd, &flock) })?;
+                Ok(())
+            }
+        } else {
+            pub fn lock(_fd: c_int, _exclusive: bool) -> io::Result<()> {
+                Err(unsupported())
+            }
+
+            pub fn try_lock(_fd: c_int, _exclusive: bool) -> io::Result<bool> {
+                Err(unsupported())
+            }
+
+            pub fn unlock(_fd: c_int) -> io::Result<()> {
+                Err(unsupported())
+            }
+
+            fn unsupported() -> io::Error {
+                io::const_io_error!(
+                    io::ErrorKind::Unsupported,
+                    "file locking is not supported on this platform",
+                )
+            }
+        }
+    }
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder {
         DirBuilder { mode: 0o777 }
@@ -1441,6 +1570,35 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
     })
 }
 
+pub fn rename_noreplace(old: &Path, new: &Path) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        const RENAME_NOREPLACE: libc::c_uint = 1;
+        let result = run_path_with_cstr(old, |old| {
+            run_path_with_cstr(new, |new| {
+                cvt(unsafe {
+                    libc::syscall(
+                        libc::SYS_renameat2,
+                        libc::AT_FDCWD,
+                        old.as_ptr(),
+                        libc::AT_FDCWD,
+                        new.as_ptr(),
+                        RENAME_NOREPLACE,
+                    )
+                })
+                .map(|_| ())
+            })
+        });
+        match result {
+            // `renameat2` is only available since Linux 3.15, and `RENAME_NOREPLACE` isn't
+            // supported by every file system.
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ENOSYS | libc::EINVAL)) => {}
+            result => return result,
+        }
+    }
+    crate::sys_common::fs::rename_noreplace(old, new)
+}
+
 pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     run_path_with_cstr(p, |p| cvt_r(|| unsafe { libc::chmod(p.as_ptr(), perm.mode) }).map(|_| ()))
 }
@@ -1521,6 +1679,119 @@ pub fn link(original: &Path, link: &Path) -> io::Result<()> {
     })
 }
 
+/// Creates an unnamed file in `dir`, to be given a name with `link_anonymous` later. If
+/// `private` is set, only its owner can access it. Returns `None` if unnamed files aren't
+/// supported.
+#[cfg(target_os = "linux")]
+pub fn create_anonymous(dir: &Path, private: bool) -> io::Result<Option<File>> {
+    let flags = libc::O_TMPFILE | libc::O_RDWR | libc::O_CLOEXEC;
+    let mode = if private { 0o600 } else { 0o666 };
+    let result =
+        run_path_with_cstr(dir, |dir| cvt_r(|| unsafe { open64(dir.as_ptr(), flags, mode) }));
+    let fd = match result {
+        Ok(fd) => fd,
+        // Linux before 3.11 fails with `EISDIR`, and file systems that don't support
+        // `O_TMPFILE` with `EOPNOTSUPP`.
+        Err(e) if matches!(e.raw_os_error(), Some(libc::EISDIR | libc::EOPNOTSUPP)) => {
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let file = File(unsafe { FileDesc::from_raw_fd(fd) });
+    // The file is linked through `/proc`, which may not be mounted.
+    if lstat(&anonymous_path(&file)).is_err() {
+        return Ok(None);
+    }
+    Ok(Some(file))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub use crate::sys_common::fs::create_anonymous;
+
+/// Gives the name `link` to a file created by `create_anonymous`, failing if it exists.
+#[cfg(target_os = "linux")]
+pub fn link_anonymous(file: &File, link: &Path) -> io::Result<()> {
+    // Linking the file descriptor itself, with `AT_EMPTY_PATH`, needs `CAP_DAC_READ_SEARCH`.
+    run_path_with_cstr(&anonymous_path(file), |original| {
+        run_path_with_cstr(link, |link| {
+            cvt(unsafe {
+                libc::linkat(
+                    libc::AT_FDCWD,
+                    original.as_ptr(),
+                    libc::AT_FDCWD,
+                    link.as_ptr(),
+                    libc::AT_SYMLINK_FOLLOW,
+                )
+            })
+            .map(|_| ())
+        })
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn anonymous_path(file: &File) -> PathBuf {
+    PathBuf::from(format!("/proc/self/fd/{}", file.as_raw_fd()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub use crate::sys_common::fs::link_anonymous;
+
+/// Creates a new file at `p`, failing if it exists, that only its owner can access.
+pub fn create_private_file(p: &Path) -> io::Result<File> {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    opts.write(true);
+    opts.create_new(true);
+    opts.mode(0o600);
+    File::open(p, &opts)
+}
+
+/// Creates a new directory at `p` that only its owner can access.
+pub fn create_private_dir(p: &Path) -> io::Result<()> {
+    let mut builder = DirBuilder::new();
+    builder.set_mode(0o700);
+    builder.mkdir(p)
+}
+
+/// Makes `to` share the data of `from`, on file systems that support it.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn reflink(from: &File, to: &File) -> io::Result<()> {
+    // `_IOW(0x94, 9, int)`, which not every version of the libc crate defines.
+    #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    ))]
+    const FICLONE: libc::c_ulong = 0x80049409;
+    #[cfg(not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    )))]
+    const FICLONE: libc::c_ulong = 0x40049409;
+
+    cvt(unsafe { libc::ioctl(to.as_raw_fd(), FICLONE as _, from.as_raw_fd()) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub use crate::sys_common::fs::reflink;
+
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    let dir = File::open(p, &opts)?;
+    match dir.fsync() {
+        // Some file systems can't sync directories, and fail with `EINVAL`.
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => Ok(()),
+        result => result,
+    }
+}
+
 pub fn stat(p: &Path) -> io::Result<FileAttr> {
     run_path_with_cstr(p, |p| {
         cfg_has_statx! {
@@ -1789,7 +2060,7 @@ pub fn chroot(dir: &Path) -> io::Result<()> {
     run_path_with_cstr(dir, |dir| cvt(unsafe { libc::chroot(dir.as_ptr()) }).map(|_| ()))
 }
 
-pub use remove_dir_impl::remove_dir_all;
+pub use remove_dir_impl::{remove_dir_all, OpenDir};
 
 // Fallback for REDOX, ESP-ID, Horizon, and Miri
 #[cfg(any(
@@ -1800,7 +2071,7 @@ pub fn chroot(dir: &Path) -> io::Result<()> {
     miri
 ))]
 mod remove_dir_impl {
-    pub use crate::sys_common::fs::remove_dir_all;
+    pub use crate::sys_common::fs::{remove_dir_all, OpenDir};
 }
 
 // Modern implementation using openat(), unlinkat() and fdopendir()
@@ -1820,6 +2091,7 @@ mod remove_dir_impl {
     use crate::path::{Path, PathBuf};
     use crate::sys::common::small_c_string::run_path_with_cstr;
     use crate::sys::{cvt, cvt_r};
+    use libc::c_int;
 
     #[cfg(not(any(
         all(target_os = "linux", target_env = "gnu"),
@@ -1873,17 +2145,21 @@ pub unsafe fn unlinkat(dirfd: c_int, pathname: *const c_char, flags: c_int) -> c
     }
 
     pub fn openat_nofollow_dironly(parent_fd: Option<RawFd>, p: &CStr) -> io::Result<OwnedFd> {
+        openat_dironly(parent_fd, p, libc::O_NOFOLLOW)
+    }
+
+    fn openat_dironly(parent_fd: Option<RawFd>, p: &CStr, flags: c_int) -> io::Result<OwnedFd> {
         let fd = cvt_r(|| unsafe {
             openat(
                 parent_fd.unwrap_or(libc::AT_FDCWD),
                 p.as_ptr(),
-                libc::O_CLOEXEC | libc::O_RDONLY | libc::O_NOFOLLOW | libc::O_DIRECTORY,
+                libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY | flags,
             )
         })?;
         Ok(unsafe { OwnedFd::from_raw_fd(fd) })
     }
 
-    fn fdreaddir(dir_fd: OwnedFd) -> io::Result<(ReadDir, RawFd)> {
+    fn fdreaddir(dir_fd: OwnedFd, root: PathBuf) -> io::Result<(ReadDir, RawFd)> {
         let ptr = unsafe { fdopendir(dir_fd.as_raw_fd()) };
         if ptr.is_null() {
             return Err(io::Error::last_os_error());
@@ -1891,13 +2167,54 @@ fn fdreaddir(dir_fd: OwnedFd) -> io::Result<(ReadDir, RawFd)> {
         let dirp = Dir(ptr);
         // file descriptor is automatically closed by libc::closedir() now, so give up ownership
         let new_parent_fd = dir_fd.into_raw_fd();
-        // a valid root is not needed because we do not call any functions involving the full path
-        // of the DirEntrys.
-        let dummy_root = PathBuf::new();
-        let inner = InnerReadDir { dirp, root: dummy_root };
+        let inner = InnerReadDir { dirp, root };
         Ok((ReadDir::new(inner), new_parent_fd))
     }
 
+    /// A directory being walked by `fs::WalkDir`.
+    ///
+    /// Subdirectories are opened relative to their parent with `openat`, so that the walk
+    /// can't be redirected elsewhere by replacing a directory with a symlink while it runs.
+    #[derive(Debug)]
+    pub struct OpenDir {
+        entries: ReadDir,
+        // The file descriptor of the directory, owned by `entries`. `None` if `openat` isn't
+        // available, in which case subdirectories are opened by path.
+        fd: Option<RawFd>,
+    }
+
+    impl OpenDir {
+        pub fn open(path: &Path) -> io::Result<OpenDir> {
+            #[cfg(all(target_os = "macos", not(target_arch = "aarch64")))]
+            if !macos_weak::has_openat() {
+                return Ok(OpenDir { entries: super::readdir(path)?, fd: None });
+            }
+            let fd = run_path_with_cstr(path, |p| openat_dironly(None, p, 0))?;
+            let (entries, fd) = fdreaddir(fd, path.to_path_buf())?;
+            Ok(OpenDir { entries, fd: Some(fd) })
+        }
+
+        /// Opens the subdirectory `entry` of this directory. Symlinks are only followed if
+        /// `follow_symlinks` is set, and fail to open with `ELOOP` otherwise.
+        pub fn open_child(&self, entry: &DirEntry, follow_symlinks: bool) -> io::Result<OpenDir> {
+            let Some(parent_fd) = self.fd else {
+                return Ok(OpenDir { entries: super::readdir(&entry.path())?, fd: None });
+            };
+            let flags = if follow_symlinks { 0 } else { libc::O_NOFOLLOW };
+            let fd = openat_dironly(Some(parent_fd), entry.name_cstr(), flags)?;
+            let (entries, fd) = fdreaddir(fd, entry.path())?;
+            Ok(OpenDir { entries, fd: Some(fd) })
+        }
+    }
+
+    impl Iterator for OpenDir {
+        type Item = io::Result<DirEntry>;
+
+        fn next(&mut self) -> Option<io::Result<DirEntry>> {
+            self.entries.next()
+        }
+    }
+
     #[cfg(any(
         target_os = "solaris",
         target_os = "illumos",
@@ -1941,7 +2258,9 @@ fn remove_dir_all_recursive(parent_fd: Option<RawFd>, path: &CStr) -> io::Result
         };
 
         // open the directory passing ownership of the fd
-        let (dir, fd) = fdreaddir(fd)?;
+        // a valid root is not needed because we do not call any functions involving the full path
+        // of the DirEntrys.
+        let (dir, fd) = fdreaddir(fd, PathBuf::new())?;
         for child in dir {
             let child = child?;
             let child_name = child.name_cstr();
@@ -41,11 +41,13 @@
     AtomicU32,
     Ordering::{Acquire, Relaxed, Release},
 };
+use crate::sys::common::locks::try_lock_until;
 use crate::sys::futex::zircon::{
     zx_futex_wait, zx_futex_wake_single_owner, zx_handle_t, zx_thread_self, ZX_ERR_BAD_HANDLE,
     ZX_ERR_BAD_STATE, ZX_ERR_INVALID_ARGS, ZX_ERR_TIMED_OUT, ZX_ERR_WRONG_TYPE, ZX_OK,
     ZX_TIME_INFINITE,
 };
+use crate::time::Duration;
 
 // The lowest two bits of a `zx_handle_t` are always set, so the lowest bit is used to mark the
 // mutex as contested by clearing it.
@@ -101,6 +103,11 @@ pub fn lock(&self) {
         }
     }
 
+    #[inline]
+    pub fn lock_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_lock())
+    }
+
     /// # Safety
     /// `thread_self` must be the handle for the current thread.
     #[cold]
@@ -2,7 +2,9 @@
     AtomicU32,
     Ordering::{Acquire, Relaxed, Release},
 };
+use crate::sys::common::locks::Deadline;
 use crate::sys::futex::{futex_wait, futex_wake};
+use crate::time::Duration;
 
 pub struct Mutex {
     /// 0: unlocked
@@ -25,12 +27,21 @@ pub fn try_lock(&self) -> bool {
     #[inline]
     pub fn lock(&self) {
         if self.futex.compare_exchange(0, 1, Acquire, Relaxed).is_err() {
-            self.lock_contended();
+            self.lock_contended(None);
         }
     }
 
+    #[inline]
+    pub fn lock_timeout(&self, timeout: Duration) -> bool {
+        self.futex.compare_exchange(0, 1, Acquire, Relaxed).is_ok()
+            || self.lock_contended(Some(timeout))
+    }
+
+    /// Returns false if the timeout passed before the lock could be locked.
     #[cold]
-    fn lock_contended(&self) {
+    fn lock_contended(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+
         // Spin first to speed things up if the lock is released quickly.
         let mut state = self.spin();
 
@@ -38,7 +49,7 @@ fn lock_contended(&self) {
         // without marking it as contended.
         if state == 0 {
             match self.futex.compare_exchange(0, 1, Acquire, Relaxed) {
-                Ok(_) => return, // Locked!
+                Ok(_) => return true, // Locked!
                 Err(s) => state = s,
             }
         }
@@ -49,11 +60,14 @@ fn lock_contended(&self) {
             // to be friendlier for the caches.
             if state != 2 && self.futex.swap(2, Acquire) == 0 {
                 // We changed it from 0 to 2, so we just successfully locked it.
-                return;
+                return true;
             }
 
+            // Leaving the state at 2 is fine, at worst it causes a spurious wake up.
+            let Some(timeout) = deadline.time_left() else { return false };
+
             // Wait for the futex to change state, assuming it is still 2.
-            futex_wait(&self.futex, 2, None);
+            futex_wait(&self.futex, 2, timeout);
 
             // Spin again after waking up.
             state = self.spin();
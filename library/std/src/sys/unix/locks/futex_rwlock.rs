@@ -2,26 +2,38 @@
     AtomicU32,
     Ordering::{Acquire, Relaxed, Release},
 };
+use crate::sys::common::locks::Deadline;
 use crate::sys::futex::{futex_wait, futex_wake, futex_wake_all};
+use crate::time::Duration;
 
 pub struct RwLock {
-    // The state consists of a 30-bit reader counter, a 'readers waiting' flag, and a 'writers waiting' flag.
-    // Bits 0..30:
+    // The state consists of a 27-bit reader counter, an 'upgradable' flag,
+    // an 'upgradable readers waiting' flag, an 'upgrading' flag, a 'readers
+    // waiting' flag, and a 'writers waiting' flag.
+    // Bits 0..27:
     //   0: Unlocked
-    //   1..=0x3FFF_FFFE: Locked by N readers
-    //   0x3FFF_FFFF: Write locked
+    //   1..=0x7FF_FFFE: Locked by N readers
+    //   0x7FF_FFFF: Write locked
+    // Bit 27: One of the readers is the upgradable reader.
+    // Bit 28: Would-be upgradable readers are waiting on this futex for the
+    //         upgradable reader to leave.
+    // Bit 29: The upgradable reader is waiting on the writer_notify futex
+    //         for the other readers to leave.
     // Bit 30: Readers are waiting on this futex.
     // Bit 31: Writers are waiting on the writer_notify futex.
     state: AtomicU32,
-    // The 'condition variable' to notify writers through.
+    // The 'condition variable' to notify writers and the upgrading reader through.
     // Incremented on every signal.
     writer_notify: AtomicU32,
 }
 
 const READ_LOCKED: u32 = 1;
-const MASK: u32 = (1 << 30) - 1;
+const MASK: u32 = (1 << 27) - 1;
 const WRITE_LOCKED: u32 = MASK;
 const MAX_READERS: u32 = MASK - 1;
+const UPGRADABLE: u32 = 1 << 27;
+const UPGRADABLE_WAITING: u32 = 1 << 28;
+const UPGRADING: u32 = 1 << 29;
 const READERS_WAITING: u32 = 1 << 30;
 const WRITERS_WAITING: u32 = 1 << 31;
 
@@ -35,6 +47,16 @@ fn is_write_locked(state: u32) -> bool {
     state & MASK == WRITE_LOCKED
 }
 
+#[inline]
+fn is_upgradable(state: u32) -> bool {
+    state & UPGRADABLE != 0
+}
+
+#[inline]
+fn is_upgrading(state: u32) -> bool {
+    state & UPGRADING != 0
+}
+
 #[inline]
 fn has_readers_waiting(state: u32) -> bool {
     state & READERS_WAITING != 0
@@ -53,7 +75,13 @@ fn is_read_lockable(state: u32) -> bool {
     // and there's no writers waiting. The only situation when this happens is after unlocking,
     // at which point the unlocking thread might be waking up writers, which have priority over readers.
     // The unlocking thread will clear the readers waiting bit and wake up readers, if necessary.
-    state & MASK < MAX_READERS && !has_readers_waiting(state) && !has_writers_waiting(state)
+    //
+    // New readers are also kept out while an upgradable reader is waiting for the
+    // existing readers to leave, as it would otherwise never get the write lock.
+    state & MASK < MAX_READERS
+        && !is_upgrading(state)
+        && !has_readers_waiting(state)
+        && !has_writers_waiting(state)
 }
 
 #[inline]
@@ -83,34 +111,57 @@ pub fn read(&self) {
                 .compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
                 .is_err()
         {
-            self.read_contended();
+            self.read_contended(None, false);
         }
     }
 
+    #[inline]
+    pub fn read_timeout(&self, timeout: Duration) -> bool {
+        let state = self.state.load(Relaxed);
+        (is_read_lockable(state)
+            && self
+                .state
+                .compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
+                .is_ok())
+            || self.read_contended(Some(timeout), false)
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         let state = self.state.fetch_sub(READ_LOCKED, Release) - READ_LOCKED;
 
         // It's impossible for a reader to be waiting on a read-locked RwLock,
-        // except if there is also a writer waiting.
-        debug_assert!(!has_readers_waiting(state) || has_writers_waiting(state));
+        // except if there is also a writer or an upgrading reader waiting.
+        debug_assert!(
+            !has_readers_waiting(state) || has_writers_waiting(state) || is_upgrading(state)
+        );
 
         // Wake up a writer if we were the last reader and there's a writer waiting.
         if is_unlocked(state) && has_writers_waiting(state) {
             self.wake_writer_or_readers(state);
         }
+
+        // Wake up the upgrading reader if it is the only reader left.
+        if is_upgrading(state) && state & MASK == READ_LOCKED {
+            self.wake_upgrader();
+        }
     }
 
+    /// Returns false if the timeout passed before the lock could be read locked.
+    ///
+    /// If `upgradable` is true, this locks it as the upgradable reader.
     #[cold]
-    fn read_contended(&self) {
+    fn read_contended(&self, timeout: Option<Duration>, upgradable: bool) -> bool {
+        let deadline = Deadline::after(timeout);
+        let locked = if upgradable { READ_LOCKED | UPGRADABLE } else { READ_LOCKED };
+
         let mut state = self.spin_read();
 
         loop {
             // If we can lock it, lock it.
-            if is_read_lockable(state) {
-                match self.state.compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
-                {
-                    Ok(_) => return, // Locked!
+            if is_read_lockable(state) && !(upgradable && is_upgradable(state)) {
+                match self.state.compare_exchange_weak(state, state + locked, Acquire, Relaxed) {
+                    Ok(_) => return true, // Locked!
                     Err(s) => {
                         state = s;
                         continue;
@@ -123,18 +174,29 @@ fn read_contended(&self) {
                 panic!("too many active read locks on RwLock");
             }
 
-            // Make sure the readers waiting bit is set before we go to sleep.
-            if !has_readers_waiting(state) {
+            // Make sure the right waiting bit is set before we go to sleep. If only the other
+            // upgradable reader keeps us out, we wait for it to leave without keeping out
+            // plain readers in the meantime.
+            let waiting =
+                if is_read_lockable(state) { UPGRADABLE_WAITING } else { READERS_WAITING };
+            if state & waiting == 0 {
                 if let Err(s) =
-                    self.state.compare_exchange(state, state | READERS_WAITING, Relaxed, Relaxed)
+                    self.state.compare_exchange(state, state | waiting, Relaxed, Relaxed)
                 {
                     state = s;
                     continue;
                 }
             }
 
+            let timeout = match deadline.time_left() {
+                Some(timeout) => timeout,
+                // The waiting bit is left set, which at worst causes the next
+                // unlocking thread to try to wake up nobody.
+                None => return false,
+            };
+
             // Wait for the state to change.
-            futex_wait(&self.state, state | READERS_WAITING, None);
+            futex_wait(&self.state, state | waiting, timeout);
 
             // Spin again after waking up.
             state = self.spin_read();
@@ -151,10 +213,16 @@ pub fn try_write(&self) -> bool {
     #[inline]
     pub fn write(&self) {
         if self.state.compare_exchange_weak(0, WRITE_LOCKED, Acquire, Relaxed).is_err() {
-            self.write_contended();
+            self.write_contended(None);
         }
     }
 
+    #[inline]
+    pub fn write_timeout(&self, timeout: Duration) -> bool {
+        self.state.compare_exchange_weak(0, WRITE_LOCKED, Acquire, Relaxed).is_ok()
+            || self.write_contended(Some(timeout))
+    }
+
     #[inline]
     pub unsafe fn write_unlock(&self) {
         let state = self.state.fetch_sub(WRITE_LOCKED, Release) - WRITE_LOCKED;
@@ -166,8 +234,11 @@ pub unsafe fn write_unlock(&self) {
         }
     }
 
+    /// Returns false if the timeout passed before the lock could be write locked.
     #[cold]
-    fn write_contended(&self) {
+    fn write_contended(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+
         let mut state = self.spin_write();
 
         let mut other_writers_waiting = 0;
@@ -181,7 +252,7 @@ fn write_contended(&self) {
                     Acquire,
                     Relaxed,
                 ) {
-                    Ok(_) => return, // Locked!
+                    Ok(_) => return true, // Locked!
                     Err(s) => {
                         state = s;
                         continue;
@@ -214,14 +285,137 @@ fn write_contended(&self) {
                 continue;
             }
 
+            let timeout = match deadline.time_left() {
+                Some(timeout) => timeout,
+                None => {
+                    self.abandon_write();
+                    return false;
+                }
+            };
+
             // Wait for the state to change.
-            futex_wait(&self.writer_notify, seq, None);
+            futex_wait(&self.writer_notify, seq, timeout);
 
             // Spin again after waking up.
             state = self.spin_write();
         }
     }
 
+    #[inline]
+    pub fn upgradable_read(&self) {
+        if !self.try_upgradable_read() {
+            self.read_contended(None, true);
+        }
+    }
+
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        self.state
+            .fetch_update(Acquire, Relaxed, |s| {
+                (is_read_lockable(s) && !is_upgradable(s)).then(|| s + (READ_LOCKED | UPGRADABLE))
+            })
+            .is_ok()
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        let state = self.state.fetch_sub(READ_LOCKED | UPGRADABLE, Release);
+
+        // Let another thread become the upgradable reader. This clears the
+        // upgradable readers waiting bit.
+        if state & UPGRADABLE_WAITING != 0 {
+            self.wake_upgradable_readers();
+        }
+        let state = (state - (READ_LOCKED | UPGRADABLE)) & !UPGRADABLE_WAITING;
+
+        // Wake up a writer if we were the last reader and there's a writer waiting.
+        if is_unlocked(state) && has_writers_waiting(state) {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    /// Turns the upgradable read lock held by this thread into a write lock,
+    /// waiting for all other readers to leave first.
+    pub unsafe fn upgrade(&self) {
+        let mut state = self.state.load(Relaxed);
+
+        loop {
+            // If we're the only reader left, take the write lock.
+            // The readers and writers waiting bits are kept, so that `write_unlock`
+            // wakes up the threads that queued up behind us.
+            if state & MASK == READ_LOCKED {
+                let upgraded = (state - READ_LOCKED + WRITE_LOCKED)
+                    & !(UPGRADABLE | UPGRADABLE_WAITING | UPGRADING);
+                match self.state.compare_exchange_weak(state, upgraded, Acquire, Relaxed) {
+                    Ok(_) => {
+                        // We're the writer now, so the threads waiting to become the
+                        // upgradable reader can queue up as readers instead.
+                        if state & UPGRADABLE_WAITING != 0 {
+                            futex_wake_all(&self.state);
+                        }
+                        return;
+                    }
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // Stop new readers from coming in while we wait.
+            if !is_upgrading(state) {
+                if let Err(s) =
+                    self.state.compare_exchange(state, state | UPGRADING, Relaxed, Relaxed)
+                {
+                    state = s;
+                    continue;
+                }
+            }
+
+            // Examine the notification counter before we check if `state` has changed,
+            // to make sure we don't miss any notifications.
+            let seq = self.writer_notify.load(Acquire);
+
+            state = self.state.load(Relaxed);
+            if state & MASK == READ_LOCKED {
+                continue;
+            }
+
+            // Wait for the last other reader to leave.
+            futex_wait(&self.writer_notify, seq, None);
+
+            state = self.state.load(Relaxed);
+        }
+    }
+
+    /// Stops waiting for the write lock after a timeout.
+    ///
+    /// There's no telling whether other writers are still waiting, so the
+    /// writers waiting bit is cleared and all writers are woken up, to set it
+    /// again if they are. This also hands over a wake up that may have been
+    /// meant for us. Readers that only queued up behind the writers are let in
+    /// if the lock is read locked, instead of waiting for it to be unlocked.
+    #[cold]
+    fn abandon_write(&self) {
+        let mut state = self.state.load(Relaxed);
+        let wake_readers = loop {
+            let wake_readers =
+                !is_write_locked(state) && has_readers_waiting(state) && !is_upgrading(state);
+            let clear =
+                if wake_readers { WRITERS_WAITING | READERS_WAITING } else { WRITERS_WAITING };
+            match self.state.compare_exchange_weak(state, state & !clear, Relaxed, Relaxed) {
+                Ok(_) => break wake_readers,
+                Err(s) => state = s,
+            }
+        };
+
+        self.writer_notify.fetch_add(1, Release);
+        futex_wake_all(&self.writer_notify);
+        if wake_readers {
+            futex_wake_all(&self.state);
+        }
+    }
+
     /// Wake up waiting threads after unlocking.
     ///
     /// If both are waiting, this will wake up only one writer, but will fall
@@ -290,6 +484,21 @@ fn wake_writer(&self) -> bool {
         // well in case both readers and writers were waiting.
     }
 
+    #[cold]
+    fn wake_upgrader(&self) {
+        // Writers wait on the same futex, and there's no waking up only the
+        // upgrading reader, so wake them all. The writers just go back to sleep.
+        self.writer_notify.fetch_add(1, Release);
+        futex_wake_all(&self.writer_notify);
+    }
+
+    #[cold]
+    fn wake_upgradable_readers(&self) {
+        if self.state.fetch_and(!UPGRADABLE_WAITING, Relaxed) & UPGRADABLE_WAITING != 0 {
+            futex_wake_all(&self.state);
+        }
+    }
+
     /// Spin for a while, but stop directly at the given condition.
     #[inline]
     fn spin_until(&self, f: impl Fn(u32) -> bool) -> u32 {
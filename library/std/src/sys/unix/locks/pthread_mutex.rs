@@ -1,7 +1,9 @@
 use crate::cell::UnsafeCell;
 use crate::mem::{forget, MaybeUninit};
+use crate::sys::common::locks::try_lock_until;
 use crate::sys::cvt_nz;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Duration;
 
 struct AllocatedMutex(UnsafeCell<libc::pthread_mutex_t>);
 
@@ -117,6 +119,13 @@ pub unsafe fn unlock(&self) {
     pub unsafe fn try_lock(&self) -> bool {
         libc::pthread_mutex_trylock(raw(self)) == 0
     }
+
+    // `pthread_mutex_timedlock` is not available everywhere and measures its
+    // deadline against the realtime clock, so poll with `try_lock` instead.
+    #[inline]
+    pub unsafe fn lock_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || unsafe { self.try_lock() })
+    }
 }
 
 pub(super) struct PthreadMutexAttr<'a>(pub &'a mut MaybeUninit<libc::pthread_mutexattr_t>);
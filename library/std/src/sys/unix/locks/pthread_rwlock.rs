@@ -1,7 +1,9 @@
 use crate::cell::UnsafeCell;
 use crate::mem::forget;
 use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sys::common::locks::try_lock_until;
 use crate::sys_common::lazy_box::{LazyBox, LazyInit};
+use crate::time::Duration;
 
 struct AllocatedRwLock {
     inner: UnsafeCell<libc::pthread_rwlock_t>,
@@ -176,6 +178,38 @@ pub unsafe fn try_write(&self) -> bool {
         }
     }
 
+    #[inline]
+    pub fn read_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_read())
+    }
+
+    #[inline]
+    pub fn write_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || unsafe { self.try_write() })
+    }
+
+    // pthread has no notion of an upgradable read lock, so an upgradable
+    // reader holds the write lock from the start and upgrading is a no-op.
+    // This excludes plain readers as well, but keeps the semantics sound.
+
+    #[inline]
+    pub fn upgradable_read(&self) {
+        self.write()
+    }
+
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        unsafe { self.try_write() }
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        self.write_unlock()
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {}
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         let lock = &*self.inner;
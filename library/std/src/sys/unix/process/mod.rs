@@ -3,6 +3,9 @@
 pub use crate::ffi::OsString as EnvKey;
 pub use crate::sys_common::process::CommandEnvs;
 
+#[cfg(target_os = "linux")]
+pub use self::process_inner::pidfd;
+
 #[cfg_attr(any(target_os = "espidf", target_os = "horizon"), allow(unused))]
 mod process_common;
 
@@ -104,9 +104,25 @@ pub struct Command {
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
+    stderr_to_stdout: bool,
     #[cfg(target_os = "linux")]
     create_pidfd: bool,
     pgroup: Option<pid_t>,
+    setsid: bool,
+    reset_sigmask: bool,
+    reset_sigdispositions: bool,
+    rlimits: Vec<Rlimit>,
+    close_fds_above: Option<c_int>,
+    #[cfg(target_os = "linux")]
+    pdeathsig: Option<c_int>,
+}
+
+// A resource limit to set in the child, as passed to `setrlimit`.
+#[derive(Copy, Clone, Debug)]
+pub struct Rlimit {
+    pub resource: c_int,
+    pub soft: u64,
+    pub hard: u64,
 }
 
 // Create a new type for argv, so that we can make it `Send` and `Sync`
@@ -196,7 +212,13 @@ pub fn new(program: &OsStr) -> Command {
             stdin: None,
             stdout: None,
             stderr: None,
+            stderr_to_stdout: false,
             pgroup: None,
+            setsid: false,
+            reset_sigmask: false,
+            reset_sigdispositions: false,
+            rlimits: Vec::new(),
+            close_fds_above: None,
         }
     }
 
@@ -220,8 +242,15 @@ pub fn new(program: &OsStr) -> Command {
             stdin: None,
             stdout: None,
             stderr: None,
+            stderr_to_stdout: false,
             create_pidfd: false,
             pgroup: None,
+            setsid: false,
+            reset_sigmask: false,
+            reset_sigdispositions: false,
+            rlimits: Vec::new(),
+            close_fds_above: None,
+            pdeathsig: None,
         }
     }
 
@@ -260,6 +289,39 @@ pub fn groups(&mut self, groups: &[gid_t]) {
     pub fn pgroup(&mut self, pgroup: pid_t) {
         self.pgroup = Some(pgroup);
     }
+    pub fn setsid(&mut self, setsid: bool) {
+        self.setsid = setsid;
+    }
+    pub fn reset_sigmask(&mut self, reset: bool) {
+        self.reset_sigmask = reset;
+    }
+    pub fn reset_sigdispositions(&mut self, reset: bool) {
+        self.reset_sigdispositions = reset;
+    }
+    pub fn rlimit(&mut self, resource: c_int, soft: u64, hard: u64) {
+        self.rlimits.retain(|limit| limit.resource != resource);
+        self.rlimits.push(Rlimit { resource, soft, hard });
+    }
+    pub fn close_fds_above(&mut self, fd: c_int) {
+        self.close_fds_above = Some(fd);
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn pdeathsig(&mut self, signal: c_int) {
+        self.pdeathsig = Some(signal);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[allow(dead_code)]
+    pub fn get_pdeathsig(&self) -> Option<c_int> {
+        self.pdeathsig
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[allow(dead_code)]
+    pub fn get_pdeathsig(&self) -> Option<c_int> {
+        None
+    }
 
     #[cfg(target_os = "linux")]
     pub fn create_pidfd(&mut self, val: bool) {
@@ -332,6 +394,26 @@ pub fn get_groups(&self) -> Option<&[gid_t]> {
     pub fn get_pgroup(&self) -> Option<pid_t> {
         self.pgroup
     }
+    #[allow(dead_code)]
+    pub fn get_setsid(&self) -> bool {
+        self.setsid
+    }
+    #[allow(dead_code)]
+    pub fn get_reset_sigmask(&self) -> bool {
+        self.reset_sigmask
+    }
+    #[allow(dead_code)]
+    pub fn get_reset_sigdispositions(&self) -> bool {
+        self.reset_sigdispositions
+    }
+    #[allow(dead_code)]
+    pub fn get_rlimits(&self) -> &[Rlimit] {
+        &self.rlimits
+    }
+    #[allow(dead_code)]
+    pub fn get_close_fds_above(&self) -> Option<c_int> {
+        self.close_fds_above
+    }
 
     pub fn get_closures(&mut self) -> &mut Vec<Box<dyn FnMut() -> io::Result<()> + Send + Sync>> {
         &mut self.closures
@@ -353,6 +435,10 @@ pub fn stderr(&mut self, stderr: Stdio) {
         self.stderr = Some(stderr);
     }
 
+    pub fn stderr_to_stdout(&mut self, merge: bool) {
+        self.stderr_to_stdout = merge;
+    }
+
     pub fn env_mut(&mut self) -> &mut CommandEnv {
         &mut self.env
     }
@@ -384,7 +470,12 @@ pub fn setup_io(
         let stderr = self.stderr.as_ref().unwrap_or(&default);
         let (their_stdin, our_stdin) = stdin.to_child_stdio(true)?;
         let (their_stdout, our_stdout) = stdout.to_child_stdio(false)?;
-        let (their_stderr, our_stderr) = stderr.to_child_stdio(false)?;
+        let (their_stderr, our_stderr) = if self.stderr_to_stdout {
+            // Stdout is set up first, so this ends up where stdout goes, like `2>&1`.
+            (their_stdout.duplicate_as_target(libc::STDOUT_FILENO), None)
+        } else {
+            stderr.to_child_stdio(false)?
+        };
         let ours = StdioPipes { stdin: our_stdin, stdout: our_stdout, stderr: our_stderr };
         let theirs = ChildPipes { stdin: their_stdin, stdout: their_stdout, stderr: their_stderr };
         Ok((ours, theirs))
@@ -498,6 +589,19 @@ fn from(file: File) -> Stdio {
 }
 
 impl ChildStdio {
+    // Returns the stdio that refers to the same file as this one does once it is set up as the
+    // child's `target_fd`.
+    fn duplicate_as_target(&self, target_fd: c_int) -> ChildStdio {
+        match *self {
+            ChildStdio::Inherit => ChildStdio::Explicit(target_fd),
+            ChildStdio::Explicit(fd) => ChildStdio::Explicit(fd),
+            ChildStdio::Owned(ref fd) => ChildStdio::Explicit(fd.as_raw_fd()),
+
+            #[cfg(target_os = "fuchsia")]
+            ChildStdio::Null => ChildStdio::Null,
+        }
+    }
+
     pub fn fd(&self) -> Option<c_int> {
         match *self {
             ChildStdio::Inherit => None,
@@ -544,13 +648,34 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             if self.stderr.is_some() {
                 debug_command.field("stderr", &self.stderr);
             }
+            if self.stderr_to_stdout {
+                debug_command.field("stderr_to_stdout", &self.stderr_to_stdout);
+            }
             if self.pgroup.is_some() {
                 debug_command.field("pgroup", &self.pgroup);
             }
+            if self.setsid {
+                debug_command.field("setsid", &self.setsid);
+            }
+            if self.reset_sigmask {
+                debug_command.field("reset_sigmask", &self.reset_sigmask);
+            }
+            if self.reset_sigdispositions {
+                debug_command.field("reset_sigdispositions", &self.reset_sigdispositions);
+            }
+            if !self.rlimits.is_empty() {
+                debug_command.field("rlimits", &self.rlimits);
+            }
+            if self.close_fds_above.is_some() {
+                debug_command.field("close_fds_above", &self.close_fds_above);
+            }
 
             #[cfg(target_os = "linux")]
             {
                 debug_command.field("create_pidfd", &self.create_pidfd);
+                if self.pdeathsig.is_some() {
+                    debug_command.field("pdeathsig", &self.pdeathsig);
+                }
             }
 
             debug_command.finish()
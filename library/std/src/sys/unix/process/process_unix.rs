@@ -2,9 +2,12 @@
 use crate::io::{self, Error, ErrorKind};
 use crate::mem;
 use crate::num::NonZeroI32;
+use crate::os::unix::io::AsRawFd;
 use crate::sys;
 use crate::sys::cvt;
 use crate::sys::process::process_common::*;
+use crate::sys_common::process::wait_timeout_by_polling;
+use crate::time::Duration;
 use core::ffi::NonZero_c_int;
 
 #[cfg(target_os = "linux")]
@@ -80,13 +83,15 @@ pub fn spawn(
         // The child calls `mem::forget` to leak the lock, which is crucial because
         // releasing a lock is not async-signal-safe.
         let env_lock = sys::os::env_read_lock();
+        let forked_from =
+            ForkedFrom { parent: unsafe { libc::getpid() }, error_pipe: output.as_raw_fd() };
         let (pid, pidfd) = unsafe { self.do_fork()? };
 
         if pid == 0 {
             crate::panic::always_abort();
             mem::forget(env_lock); // avoid non-async-signal-safe unlocking
             drop(input);
-            let Err(err) = unsafe { self.do_exec(theirs, envp.as_ref()) };
+            let Err(err) = unsafe { self.do_exec(theirs, envp.as_ref(), Some(forked_from)) };
             let errno = err.raw_os_error().unwrap_or(libc::EINVAL) as u32;
             let errno = errno.to_be_bytes();
             let bytes = [
@@ -270,7 +275,7 @@ pub fn exec(&mut self, default: Stdio) -> io::Error {
                     // environment lock before we try to exec.
                     let _lock = sys::os::env_read_lock();
 
-                    let Err(e) = self.do_exec(theirs, envp.as_ref());
+                    let Err(e) = self.do_exec(theirs, envp.as_ref(), None);
                     e
                 }
             }
@@ -312,8 +317,10 @@ unsafe fn do_exec(
         &mut self,
         stdio: ChildPipes,
         maybe_envp: Option<&CStringArray>,
+        forked_from: Option<ForkedFrom>,
     ) -> Result<!, io::Error> {
-        use crate::sys::{self, cvt_r};
+        use crate::mem::MaybeUninit;
+        use crate::sys::{self, cvt_nz, cvt_r};
 
         if let Some(fd) = stdio.stdin.fd() {
             cvt_r(|| libc::dup2(fd, libc::STDIN_FILENO))?;
@@ -325,6 +332,14 @@ unsafe fn do_exec(
             cvt_r(|| libc::dup2(fd, libc::STDERR_FILENO))?;
         }
 
+        // Set the limits before dropping privileges, which might prevent raising them.
+        #[cfg(not(target_os = "l4re"))]
+        for limit in self.get_rlimits() {
+            let rlim =
+                libc::rlimit { rlim_cur: to_rlim(limit.soft), rlim_max: to_rlim(limit.hard) };
+            cvt(libc::setrlimit(limit.resource as _, &rlim))?;
+        }
+
         #[cfg(not(target_os = "l4re"))]
         {
             if let Some(_g) = self.get_groups() {
@@ -354,21 +369,54 @@ unsafe fn do_exec(
             cvt(libc::chdir(cwd.as_ptr()))?;
         }
 
+        if self.get_setsid() {
+            cvt(libc::setsid())?;
+        }
+
         if let Some(pgroup) = self.get_pgroup() {
             cvt(libc::setpgid(0, pgroup))?;
         }
 
+        // This has to come after changing credentials, which clears the parent death signal.
+        #[cfg(target_os = "linux")]
+        if let Some(signal) = self.get_pdeathsig() {
+            cvt(libc::prctl(libc::PR_SET_PDEATHSIG, signal as libc::c_ulong, 0, 0, 0))?;
+            // If the parent already exited, the signal won't ever be sent.
+            if let Some(forked_from) = &forked_from {
+                if libc::getppid() != forked_from.parent {
+                    libc::raise(signal);
+                }
+            }
+        }
+
         // emscripten has no signal support.
         #[cfg(not(target_os = "emscripten"))]
         {
-            // Inherit the signal mask from the parent rather than resetting it (i.e. do not call
-            // pthread_sigmask).
+            // Unless requested, inherit the signal mask from the parent rather than resetting it
+            // (i.e. do not call pthread_sigmask).
+            if self.get_reset_sigmask() {
+                let mut set = MaybeUninit::<libc::sigset_t>::uninit();
+                cvt(sigemptyset(set.as_mut_ptr()))?;
+                cvt_nz(libc::pthread_sigmask(
+                    libc::SIG_SETMASK,
+                    set.as_ptr(),
+                    crate::ptr::null_mut(),
+                ))?;
+            }
 
             // If #[unix_sigpipe] is specified, don't reset SIGPIPE to SIG_DFL.
             // If #[unix_sigpipe] is not specified, reset SIGPIPE to SIG_DFL for backward compatibility.
             //
             // #[unix_sigpipe] is an opportunity to change the default here.
-            if !crate::sys::unix_sigpipe_attr_specified() {
+            if self.get_reset_sigdispositions() {
+                // Signals can't be numbered higher than this on any supported platform.
+                // Resetting the others fails with `EINVAL`, which is fine.
+                for signal in 1..=128 {
+                    let mut action: libc::sigaction = mem::zeroed();
+                    action.sa_sigaction = libc::SIG_DFL;
+                    libc::sigaction(signal, &action, crate::ptr::null_mut());
+                }
+            } else if !crate::sys::unix_sigpipe_attr_specified() {
                 #[cfg(target_os = "android")] // see issue #88585
                 {
                     let mut action: libc::sigaction = mem::zeroed();
@@ -385,6 +433,11 @@ unsafe fn do_exec(
             }
         }
 
+        if let Some(fd) = self.get_close_fds_above() {
+            // Keep the pipe used to report errors, it is closed on exec anyway.
+            close_fds_above(fd, forked_from.map(|forked_from| forked_from.error_pipe))?;
+        }
+
         for callback in self.get_closures().iter_mut() {
             callback()?;
         }
@@ -452,10 +505,25 @@ fn posix_spawn(
             || !self.get_closures().is_empty()
             || self.get_groups().is_some()
             || self.get_create_pidfd()
+            || !self.get_rlimits().is_empty()
+            || self.get_pdeathsig().is_some()
         {
             return Ok(None);
         }
 
+        // glibc 2.26+ posix_spawn() can start a new session. Other implementations can too,
+        // but there is no reliable way to find out at runtime.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        const POSIX_SPAWN_SETSID: c_int = 0x80;
+        if self.get_setsid() {
+            #[cfg(all(target_os = "linux", target_env = "gnu"))]
+            if sys::os::glibc_version().map_or(true, |version| version < (2, 26)) {
+                return Ok(None);
+            }
+            #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+            return Ok(None);
+        }
+
         // Only glibc 2.24+ posix_spawn() supports returning ENOENT directly.
         #[cfg(all(target_os = "linux", target_env = "gnu"))]
         {
@@ -526,6 +594,21 @@ fn posix_spawn_file_actions_addchdir_np(
             None => None,
         };
 
+        // glibc 2.34+ and FreeBSD 13.1+ can close a range of file descriptors.
+        weak! {
+            fn posix_spawn_file_actions_addclosefrom_np(
+                *mut libc::posix_spawn_file_actions_t,
+                libc::c_int
+            ) -> libc::c_int
+        }
+        let addclosefrom = match self.get_close_fds_above() {
+            Some(fd) => match posix_spawn_file_actions_addclosefrom_np.get() {
+                Some(f) => Some((f, fd.max(libc::STDERR_FILENO).saturating_add(1))),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
         let pgroup = self.get_pgroup();
 
         // Safety: -1 indicates we don't have a pidfd.
@@ -586,20 +669,43 @@ fn drop(&mut self) {
             if let Some((f, cwd)) = addchdir {
                 cvt_nz(f(file_actions.0.as_mut_ptr(), cwd.as_ptr()))?;
             }
+            // This must come after the stdio file actions, which may duplicate higher descriptors.
+            if let Some((f, first)) = addclosefrom {
+                cvt_nz(f(file_actions.0.as_mut_ptr(), first))?;
+            }
+
+            #[cfg(all(target_os = "linux", target_env = "gnu"))]
+            if self.get_setsid() {
+                flags |= POSIX_SPAWN_SETSID;
+            }
 
             if let Some(pgroup) = pgroup {
                 flags |= libc::POSIX_SPAWN_SETPGROUP;
                 cvt_nz(libc::posix_spawnattr_setpgroup(attrs.0.as_mut_ptr(), pgroup))?;
             }
 
-            // Inherit the signal mask from this process rather than resetting it (i.e. do not call
-            // posix_spawnattr_setsigmask).
+            // Unless requested, inherit the signal mask from this process rather than resetting it
+            // (i.e. do not call posix_spawnattr_setsigmask).
+            if self.get_reset_sigmask() {
+                let mut empty_set = MaybeUninit::<libc::sigset_t>::uninit();
+                cvt(sigemptyset(empty_set.as_mut_ptr()))?;
+                cvt_nz(libc::posix_spawnattr_setsigmask(attrs.0.as_mut_ptr(), empty_set.as_ptr()))?;
+                flags |= libc::POSIX_SPAWN_SETSIGMASK;
+            }
 
             // If #[unix_sigpipe] is specified, don't reset SIGPIPE to SIG_DFL.
             // If #[unix_sigpipe] is not specified, reset SIGPIPE to SIG_DFL for backward compatibility.
             //
             // #[unix_sigpipe] is an opportunity to change the default here.
-            if !unix_sigpipe_attr_specified() {
+            if self.get_reset_sigdispositions() {
+                let mut full_set = MaybeUninit::<libc::sigset_t>::uninit();
+                cvt(libc::sigfillset(full_set.as_mut_ptr()))?;
+                cvt_nz(libc::posix_spawnattr_setsigdefault(
+                    attrs.0.as_mut_ptr(),
+                    full_set.as_ptr(),
+                ))?;
+                flags |= libc::POSIX_SPAWN_SETSIGDEF;
+            } else if !unix_sigpipe_attr_specified() {
                 let mut default_set = MaybeUninit::<libc::sigset_t>::uninit();
                 cvt(sigemptyset(default_set.as_mut_ptr()))?;
                 cvt(sigaddset(default_set.as_mut_ptr(), libc::SIGPIPE))?;
@@ -633,6 +739,73 @@ fn drop(&mut self) {
     }
 }
 
+// What the child needs to know about the parent it was forked from.
+#[derive(Clone, Copy)]
+struct ForkedFrom {
+    parent: pid_t,
+    // The pipe for reporting errors from the child before it executes the program.
+    error_pipe: c_int,
+}
+
+// Converts a resource limit to `rlim_t`, with `u64::MAX` meaning no limit.
+#[cfg(not(target_os = "l4re"))]
+fn to_rlim(limit: u64) -> libc::rlim_t {
+    if limit == u64::MAX { libc::RLIM_INFINITY } else { limit as libc::rlim_t }
+}
+
+// Closes all file descriptors greater than `fd`, but never stdio, and not `keep`.
+//
+// Like the rest of the code that runs between fork and exec, this must not allocate.
+unsafe fn close_fds_above(fd: c_int, keep: Option<c_int>) -> io::Result<()> {
+    let first = fd.max(libc::STDERR_FILENO).saturating_add(1);
+    match keep {
+        Some(keep) if keep >= first => {
+            close_fd_range(first, keep - 1)?;
+            close_fd_range(keep.saturating_add(1), c_int::MAX)
+        }
+        _ => close_fd_range(first, c_int::MAX),
+    }
+}
+
+// Closes all open file descriptors from `first` to `last`, inclusive.
+unsafe fn close_fd_range(first: c_int, last: c_int) -> io::Result<()> {
+    if first > last {
+        return Ok(());
+    }
+
+    // Linux 5.9+ can do this in one go.
+    #[cfg(target_os = "linux")]
+    {
+        let flags: libc::c_uint = 0;
+        if libc::syscall(libc::SYS_close_range, first as libc::c_uint, last as libc::c_uint, flags)
+            == 0
+        {
+            return Ok(());
+        }
+        // Older kernels return ENOSYS, and seccomp filters (like Docker's default profile)
+        // commonly return EPERM for syscalls they don't know about.
+        let err = io::Error::last_os_error();
+        if !matches!(err.raw_os_error(), Some(libc::ENOSYS | libc::EPERM)) {
+            return Err(err);
+        }
+    }
+
+    // Otherwise, try every descriptor below the limit on open files. Descriptors that were
+    // opened before the limit was lowered are missed, but that's as good as it gets portably.
+    let mut limit: libc::rlimit = mem::zeroed();
+    cvt(libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit))?;
+    // Don't loop forever when the limit is infinite, this is what Linux allows at most by default.
+    let max = if limit.rlim_cur == libc::RLIM_INFINITY || limit.rlim_cur > (1 << 20) {
+        1 << 20
+    } else {
+        limit.rlim_cur as c_int
+    };
+    for fd in first..=last.min(max - 1) {
+        libc::close(fd);
+    }
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Processes
 ////////////////////////////////////////////////////////////////////////////////
@@ -706,6 +879,35 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    #[cfg(target_os = "linux")]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        use crate::sys_common::AsInner;
+
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+        // As long as the child hasn't been reaped, its pid can't be reused, so
+        // opening a pidfd for it here doesn't race with anything.
+        let opened;
+        let pidfd = match &self.pidfd {
+            Some(pidfd) => pidfd.as_inner(),
+            None => match pidfd::open(self.pid) {
+                Ok(pidfd) => {
+                    opened = pidfd;
+                    &opened
+                }
+                // Kernels before 5.3 don't support pidfds.
+                Err(_) => return wait_timeout_by_polling(self, timeout),
+            },
+        };
+        if pidfd::poll_exited(pidfd, timeout)? { self.try_wait() } else { Ok(None) }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        wait_timeout_by_polling(self, timeout)
+    }
 }
 
 /// Unix exit statuses
@@ -726,6 +928,20 @@ pub fn new(status: c_int) -> ExitStatus {
         ExitStatus(status)
     }
 
+    /// Converts the result of `waitid` into the wait status `waitpid` would have returned.
+    #[cfg(target_os = "linux")]
+    pub fn from_waitid_siginfo(info: &libc::siginfo_t) -> ExitStatus {
+        let status = unsafe { info.si_status() };
+        match info.si_code {
+            libc::CLD_EXITED => ExitStatus((status & 0xff) << 8),
+            libc::CLD_KILLED => ExitStatus(status),
+            libc::CLD_DUMPED => ExitStatus(status | 0x80),
+            libc::CLD_CONTINUED => ExitStatus(0xffff),
+            libc::CLD_STOPPED | libc::CLD_TRAPPED => ExitStatus(((status & 0xff) << 8) | 0x7f),
+            _ => unreachable!("waitid() should only return the above codes"),
+        }
+    }
+
     fn exited(&self) -> bool {
         libc::WIFEXITED(self.0)
     }
@@ -913,6 +1129,10 @@ fn take_pidfd(&mut self) -> io::Result<PidFd> {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[path = "process_unix/pidfd.rs"]
+pub mod pidfd;
+
 #[cfg(test)]
 #[path = "process_unix/tests.rs"]
 mod tests;
@@ -0,0 +1,112 @@
+//! Operations on Linux process file descriptors.
+//!
+//! See [`pidfd_open(2)`], [`pidfd_send_signal(2)`] and [`waitid(2)`].
+//!
+//! [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
+//! [`pidfd_send_signal(2)`]: https://man7.org/linux/man-pages/man2/pidfd_send_signal.2.html
+//! [`waitid(2)`]: https://man7.org/linux/man-pages/man2/waitid.2.html
+
+use super::ExitStatus;
+use crate::io;
+use crate::mem;
+use crate::os::unix::io::{AsRawFd, FromRawFd};
+use crate::ptr;
+use crate::sys::fd::FileDesc;
+use crate::sys::{cvt, cvt_r};
+use crate::time::{Duration, Instant};
+use libc::{c_int, pid_t};
+
+// Not all versions of the libc crate define this yet.
+const P_PIDFD: libc::idtype_t = 3;
+
+/// Opens a pidfd referring to the process `pid`.
+pub fn open(pid: pid_t) -> io::Result<FileDesc> {
+    let fd = cvt(unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) })?;
+    // Safety: `pidfd_open` returned a new, owned file descriptor.
+    Ok(unsafe { FileDesc::from_raw_fd(fd as c_int) })
+}
+
+/// Sends `signal` to the process referred to by `pidfd`.
+pub fn send_signal(pidfd: &FileDesc, signal: c_int) -> io::Result<()> {
+    cvt(unsafe {
+        libc::syscall(
+            libc::SYS_pidfd_send_signal,
+            pidfd.as_raw_fd(),
+            signal,
+            ptr::null::<libc::siginfo_t>(),
+            0,
+        )
+    })
+    .map(drop)
+}
+
+/// Waits for the process referred to by `pidfd` to exit, and reaps it.
+pub fn wait(pidfd: &FileDesc) -> io::Result<ExitStatus> {
+    loop {
+        if let Some(status) = waitid(pidfd, 0)? {
+            return Ok(status);
+        }
+    }
+}
+
+/// Reaps the process referred to by `pidfd` if it has exited.
+pub fn try_wait(pidfd: &FileDesc) -> io::Result<Option<ExitStatus>> {
+    waitid(pidfd, libc::WNOHANG)
+}
+
+/// Waits at most `timeout` for the process referred to by `pidfd` to exit,
+/// and reaps it if it did.
+pub fn wait_timeout(pidfd: &FileDesc, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    if poll_exited(pidfd, timeout)? { try_wait(pidfd) } else { Ok(None) }
+}
+
+/// Waits at most `timeout` for the process referred to by `pidfd` to exit,
+/// without reaping it.
+///
+/// Returns whether the process exited.
+pub fn poll_exited(pidfd: &FileDesc, timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now().checked_add(timeout);
+    let mut pollfd = libc::pollfd { fd: pidfd.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+    loop {
+        // The pidfd becomes readable once the process has exited.
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        };
+        // Round up, so that we don't spin on sub-millisecond timeouts.
+        let millis = remaining
+            .as_millis()
+            .saturating_add(if remaining.subsec_nanos() % 1_000_000 > 0 { 1 } else { 0 })
+            .min(c_int::MAX as u128) as c_int;
+        match cvt(unsafe { libc::poll(&mut pollfd, 1, millis) }) {
+            Ok(0) if remaining.is_zero() => return Ok(false),
+            Ok(0) => {}
+            Ok(_) => return Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn waitid(pidfd: &FileDesc, flags: c_int) -> io::Result<Option<ExitStatus>> {
+    // Safety: `siginfo_t` is a plain C struct, for which all zeroes is a valid value.
+    let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+    let id = pidfd.as_raw_fd() as libc::id_t;
+    match cvt_r(|| unsafe { libc::waitid(P_PIDFD, id, &mut info, libc::WEXITED | flags) }) {
+        Ok(_) => {}
+        // Kernels before 5.4 don't know `P_PIDFD`.
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+            return Err(io::const_io_error!(
+                io::ErrorKind::Unsupported,
+                "waiting on a pidfd requires Linux 5.4 or later",
+            ));
+        }
+        Err(e) => return Err(e),
+    }
+    // With `WNOHANG`, a zero pid means that the process hasn't exited yet.
+    if unsafe { info.si_pid() } == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(ExitStatus::from_waitid_siginfo(&info)))
+    }
+}
@@ -60,3 +60,126 @@ fn test_command_fork_no_unwind() {
             || signal == libc::SIGSEGV
     );
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_pidfd_wait() {
+    use crate::os::linux::process::PidFd;
+    use crate::time::Duration;
+
+    let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+    let pidfd = match PidFd::open(child.id()) {
+        Ok(pidfd) => pidfd,
+        // Kernels before 5.3 don't support pidfds.
+        Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => {
+            child.kill().unwrap();
+            child.wait().unwrap();
+            return;
+        }
+        Err(e) => panic!("failed to open pidfd: {e}"),
+    };
+    assert!(pidfd.try_wait().unwrap().is_none());
+    assert!(pidfd.wait_timeout(Duration::from_millis(10)).unwrap().is_none());
+    pidfd.send_signal(libc::SIGTERM).unwrap();
+    let status = pidfd.wait().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+
+    // The child was reaped through the pidfd, so it can't be waited on again.
+    assert!(child.try_wait().is_err());
+    assert_eq!(pidfd.kill().unwrap_err().raw_os_error(), Some(libc::ESRCH));
+}
+
+#[test]
+#[cfg_attr(any(target_os = "vxworks", target_os = "emscripten"), ignore)]
+fn test_command_setsid() {
+    // Both with and without posix_spawn.
+    for force_fork in [false, true] {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30").setsid(true);
+        if force_fork {
+            unsafe { cmd.pre_exec(|| Ok(())) };
+        }
+        let mut child = cmd.spawn().unwrap();
+        let pid = child.id() as libc::pid_t;
+        let sid = unsafe { libc::getsid(pid) };
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(sid, pid);
+    }
+}
+
+#[test]
+#[cfg_attr(any(target_os = "vxworks", target_os = "emscripten"), ignore)]
+fn test_command_rlimit() {
+    let mut limit: libc::rlimit = unsafe { crate::mem::zeroed() };
+    assert_eq!(unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) }, 0);
+    let hard = if limit.rlim_max == libc::RLIM_INFINITY { u64::MAX } else { limit.rlim_max as u64 };
+    let output = Command::new("sh")
+        .args(["-c", "ulimit -n"])
+        .rlimit(libc::RLIMIT_NOFILE as i32, 64, hard)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"64\n");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_command_close_fds_above() {
+    use crate::fs::File;
+    use crate::os::unix::io::AsRawFd;
+
+    // The descriptor is `O_CLOEXEC` so that other tests spawning processes concurrently don't
+    // inherit it, only our children clear the flag. Descriptors are closed before `pre_exec`
+    // callbacks run, so a failure there just means the descriptor is already gone.
+    let file = File::open("/dev/null").unwrap();
+    let fd = file.as_raw_fd();
+    assert!(fd > 2);
+    let script = format!("test -e /proc/self/fd/{fd}");
+    let command = || {
+        let mut command = Command::new("sh");
+        command.args(["-c", &script]);
+        unsafe {
+            command.pre_exec(move || {
+                libc::fcntl(fd, libc::F_SETFD, 0);
+                Ok(())
+            });
+        }
+        command
+    };
+
+    let status = command().status().unwrap();
+    assert!(status.success());
+    let status = command().close_fds_above(2).status().unwrap();
+    assert_eq!(status.code(), Some(1));
+    let status = command().close_fds_above(fd).status().unwrap();
+    assert!(status.success());
+    drop(file);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_command_reset_signal_mask() {
+    use crate::thread;
+
+    // Block a signal on a separate thread, so that it doesn't affect other tests.
+    thread::spawn(|| unsafe {
+        let mut set: libc::sigset_t = crate::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGUSR1);
+        assert_eq!(libc::pthread_sigmask(libc::SIG_BLOCK, &set, crate::ptr::null_mut()), 0);
+
+        let blocked = |reset: bool| {
+            let output = Command::new("sh")
+                .args(["-c", "grep SigBlk /proc/self/status"])
+                .reset_signal_mask(reset)
+                .output()
+                .unwrap();
+            output.stdout != b"SigBlk:\t0000000000000000\n"
+        };
+        assert!(blocked(false));
+        assert!(!blocked(true));
+    })
+    .join()
+    .unwrap();
+}
@@ -3,6 +3,7 @@
 use crate::num::NonZeroI32;
 use crate::sys::process::process_common::*;
 use crate::sys::unix::unsupported::*;
+use crate::time::Duration;
 use core::ffi::NonZero_c_int;
 
 use libc::{c_int, pid_t};
@@ -53,6 +54,10 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         unsupported()
     }
+
+    pub fn wait_timeout(&mut self, _timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        unsupported()
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
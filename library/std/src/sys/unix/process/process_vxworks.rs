@@ -4,7 +4,9 @@
 use crate::sys;
 use crate::sys::cvt;
 use crate::sys::process::process_common::*;
+use crate::sys_common::process::wait_timeout_by_polling;
 use crate::sys_common::thread;
+use crate::time::Duration;
 use core::ffi::NonZero_c_int;
 use libc::RTP_ID;
 use libc::{self, c_char, c_int};
@@ -179,6 +181,10 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        wait_timeout_by_polling(self, timeout)
+    }
 }
 
 /// Unix exit statuses
@@ -306,22 +306,27 @@ pub fn available_parallelism() -> io::Result<NonZeroUsize> {
             target_os = "illumos",
         ))] {
             #[cfg(any(target_os = "android", target_os = "linux"))]
-            {
+            let quota = {
                 let quota = cgroups::quota().max(1);
-                let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
-                unsafe {
-                    if libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set) == 0 {
-                        let count = libc::CPU_COUNT(&set) as usize;
-                        let count = count.min(quota);
-                        // SAFETY: affinity mask can't be empty and the quota gets clamped to a minimum of 1
-                        return Ok(NonZeroUsize::new_unchecked(count));
-                    }
+                // The kernel limits the affinity mask to the cgroup's cpuset already, so that only
+                // has to be read when the mask can't be queried.
+                let count = match affinity_mask() {
+                    Ok(mask) => Some(mask.iter().map(|word| word.count_ones() as usize).sum()),
+                    Err(_) => cgroups::cpuset(),
+                };
+                if let Some(count) = count {
+                    // SAFETY: neither the affinity mask nor the cpuset can be empty, and the quota
+                    // gets clamped to a minimum of 1
+                    return Ok(unsafe { NonZeroUsize::new_unchecked(count.min(quota)) });
                 }
-            }
+                quota
+            };
+            #[cfg(not(any(target_os = "android", target_os = "linux")))]
+            let quota = usize::MAX;
             match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) } {
                 -1 => Err(io::Error::last_os_error()),
                 0 => Err(io::const_io_error!(io::ErrorKind::NotFound, "The number of hardware threads is not known for the target platform")),
-                cpus => Ok(unsafe { NonZeroUsize::new_unchecked(cpus as usize) }),
+                cpus => Ok(unsafe { NonZeroUsize::new_unchecked((cpus as usize).min(quota)) }),
             }
         } else if #[cfg(any(target_os = "freebsd", target_os = "dragonfly", target_os = "netbsd"))] {
             use crate::ptr;
@@ -412,10 +417,94 @@ pub fn available_parallelism() -> io::Result<NonZeroUsize> {
     }
 }
 
+// Reads the affinity mask of the calling thread. The kernel fails with `EINVAL` if the mask is
+// smaller than the number of CPUs it supports, and `cpu_set_t` only fits 1024, so this retries
+// with larger masks until it fits.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn affinity_mask() -> io::Result<Vec<libc::c_ulong>> {
+    let mut len = mem::size_of::<libc::cpu_set_t>() / mem::size_of::<libc::c_ulong>();
+    loop {
+        let mut mask = vec![0; len];
+        let size = len * mem::size_of::<libc::c_ulong>();
+        // A thread id of zero refers to the calling thread.
+        let res = unsafe { libc::sched_getaffinity(0, size, mask.as_mut_ptr().cast()) };
+        match crate::sys::cvt(res) {
+            Ok(_) => return Ok(mask),
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => len *= 2,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    const BITS: usize = libc::c_ulong::BITS as usize;
+    // Sized for every CPU the kernel supports.
+    let mut mask = affinity_mask()?;
+    mask.fill(0);
+    for &cpu in cpus {
+        let Some(word) = mask.get_mut(cpu / BITS) else {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "CPU index is larger than the supported number of CPUs",
+            ));
+        };
+        *word |= 1 << (cpu % BITS);
+    }
+    let size = mask.len() * mem::size_of::<libc::c_ulong>();
+    crate::sys::cvt(unsafe { libc::sched_setaffinity(0, size, mask.as_ptr().cast()) })?;
+    Ok(())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn affinity() -> io::Result<Vec<usize>> {
+    const BITS: usize = libc::c_ulong::BITS as usize;
+    let mask = affinity_mask()?;
+    Ok((0..mask.len() * BITS).filter(|&cpu| mask[cpu / BITS] & (1 << (cpu % BITS)) != 0).collect())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub use crate::sys_common::thread::{affinity, set_affinity};
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "watchos",
+))]
+pub fn set_priority(priority: crate::thread::Priority) -> io::Result<()> {
+    use crate::thread::Priority;
+
+    let (policy, sched_priority) = match priority {
+        Priority::Normal => (libc::SCHED_OTHER, 0),
+        Priority::Fifo(sched_priority) => (libc::SCHED_FIFO, sched_priority),
+        Priority::RoundRobin(sched_priority) => (libc::SCHED_RR, sched_priority),
+    };
+    // Some platforms have private fields in `sched_param`, so start from zeroes.
+    let mut param: libc::sched_param = unsafe { mem::zeroed() };
+    param.sched_priority = sched_priority;
+    crate::sys::cvt_nz(unsafe { libc::pthread_setschedparam(libc::pthread_self(), policy, &param) })
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "watchos",
+)))]
+pub use crate::sys_common::thread::set_priority;
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod cgroups {
     //! Currently not covered
     //! * cgroup v2 in non-standard mountpoints
+    //! * cgroup v1 cpusets in non-standard mountpoints
     //! * paths containing control characters or spaces, since those would be escaped in procfs
     //!   output and we don't unescape
     use crate::borrow::Cow;
@@ -445,35 +534,7 @@ pub(super) fn quota() -> usize {
         }
 
         let _: Option<()> = try {
-            let mut buf = Vec::with_capacity(128);
-            // find our place in the cgroup hierarchy
-            File::open("/proc/self/cgroup").ok()?.read_to_end(&mut buf).ok()?;
-            let (cgroup_path, version) =
-                buf.split(|&c| c == b'\n').fold(None, |previous, line| {
-                    let mut fields = line.splitn(3, |&c| c == b':');
-                    // 2nd field is a list of controllers for v1 or empty for v2
-                    let version = match fields.nth(1) {
-                        Some(b"") => Cgroup::V2,
-                        Some(controllers)
-                            if from_utf8(controllers)
-                                .is_ok_and(|c| c.split(',').any(|c| c == "cpu")) =>
-                        {
-                            Cgroup::V1
-                        }
-                        _ => return previous,
-                    };
-
-                    // already-found v1 trumps v2 since it explicitly specifies its controllers
-                    if previous.is_some() && version == Cgroup::V2 {
-                        return previous;
-                    }
-
-                    let path = fields.last()?;
-                    // skip leading slash
-                    Some((path[1..].to_owned(), version))
-                })?;
-            let cgroup_path = PathBuf::from(OsString::from_vec(cgroup_path));
-
+            let (cgroup_path, version) = find_cgroup("cpu")?;
             quota = match version {
                 Cgroup::V1 => quota_v1(cgroup_path),
                 Cgroup::V2 => quota_v2(cgroup_path),
@@ -483,6 +544,77 @@ pub(super) fn quota() -> usize {
         quota
     }
 
+    /// Returns the number of CPUs in the cgroup's cpuset, or `None` if it cannot be determined.
+    pub(super) fn cpuset() -> Option<usize> {
+        if cfg!(miri) {
+            // Attempting to open a file fails under default flags due to isolation.
+            return None;
+        }
+
+        let (cgroup_path, version) = find_cgroup("cpuset")?;
+        // The effective cpus already take the ancestors of the cgroup into account, so unlike
+        // the quota these don't have to be read all the way up the hierarchy.
+        let (mut path, names): (PathBuf, &[&str]) = match version {
+            Cgroup::V1 => {
+                ("/sys/fs/cgroup/cpuset".into(), &["cpuset.effective_cpus", "cpuset.cpus"])
+            }
+            Cgroup::V2 => ("/sys/fs/cgroup".into(), &["cpuset.cpus.effective"]),
+        };
+        path.push(cgroup_path);
+
+        let mut read_buf = String::with_capacity(20);
+        names.iter().find_map(|name| {
+            read_buf.clear();
+            File::open(path.join(name)).ok()?.read_to_string(&mut read_buf).ok()?;
+            count_cpu_list(read_buf.trim())
+        })
+    }
+
+    /// Counts the CPUs in a list like `0-3,8`, or returns `None` if it is empty or malformed.
+    fn count_cpu_list(list: &str) -> Option<usize> {
+        let mut count = 0;
+        for range in list.split(',') {
+            count += match range.split_once('-') {
+                Some((first, last)) => {
+                    last.parse::<usize>().ok()?.checked_sub(first.parse().ok()?)? + 1
+                }
+                None => range.parse::<usize>().map(|_| 1).ok()?,
+            };
+        }
+        if count > 0 { Some(count) } else { None }
+    }
+
+    /// Finds our place in the cgroup hierarchy of the given controller, returning the path of
+    /// the cgroup relative to the controller's mountpoint.
+    fn find_cgroup(controller: &str) -> Option<(PathBuf, Cgroup)> {
+        let mut buf = Vec::with_capacity(128);
+        File::open("/proc/self/cgroup").ok()?.read_to_end(&mut buf).ok()?;
+        let (cgroup_path, version) = buf.split(|&c| c == b'\n').fold(None, |previous, line| {
+            let mut fields = line.splitn(3, |&c| c == b':');
+            // 2nd field is a list of controllers for v1 or empty for v2
+            let version = match fields.nth(1) {
+                Some(b"") => Cgroup::V2,
+                Some(controllers)
+                    if from_utf8(controllers)
+                        .is_ok_and(|c| c.split(',').any(|c| c == controller)) =>
+                {
+                    Cgroup::V1
+                }
+                _ => return previous,
+            };
+
+            // already-found v1 trumps v2 since it explicitly specifies its controllers
+            if previous.is_some() && version == Cgroup::V2 {
+                return previous;
+            }
+
+            let path = fields.last()?;
+            // skip leading slash
+            Some((path[1..].to_owned(), version))
+        })?;
+        Some((PathBuf::from(OsString::from_vec(cgroup_path)), version))
+    }
+
     fn quota_v2(group_path: PathBuf) -> usize {
         let mut quota = usize::MAX;
 
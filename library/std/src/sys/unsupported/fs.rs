@@ -6,6 +6,11 @@
 use crate::sys::time::SystemTime;
 use crate::sys::unsupported;
 
+pub use crate::sys_common::fs::{
+    copy_symlink, create_anonymous, create_private_dir, create_private_file, link_anonymous,
+    reflink, rename_noreplace, set_dir_times, sync_dir, OpenDir,
+};
+
 pub struct File(!);
 
 pub struct FileAttr(!);
@@ -249,6 +254,26 @@ pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
     pub fn set_times(&self, _times: FileTimes) -> io::Result<()> {
         self.0
     }
+
+    pub fn lock(&self) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.0
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.0
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        self.0
+    }
 }
 
 impl DirBuilder {
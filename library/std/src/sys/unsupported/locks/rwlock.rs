@@ -1,4 +1,5 @@
 use crate::cell::Cell;
+use crate::time::Duration;
 
 pub struct RwLock {
     // This platform has no threads, so we can use a Cell here.
@@ -53,6 +54,39 @@ pub fn try_write(&self) -> bool {
         }
     }
 
+    // Without other threads, nobody could unlock it while we wait.
+
+    #[inline]
+    pub fn read_timeout(&self, _timeout: Duration) -> bool {
+        self.try_read()
+    }
+
+    #[inline]
+    pub fn write_timeout(&self, _timeout: Duration) -> bool {
+        self.try_write()
+    }
+
+    // There are no other readers to share the lock with,
+    // so an upgradable reader is simply a writer.
+
+    #[inline]
+    pub fn upgradable_read(&self) {
+        self.write()
+    }
+
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        self.try_write()
+    }
+
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        unsafe { self.write_unlock() }
+    }
+
+    #[inline]
+    pub unsafe fn upgrade(&self) {}
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         self.mode.set(self.mode.get() - 1);
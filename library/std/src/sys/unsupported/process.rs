@@ -8,6 +8,7 @@
 use crate::sys::pipe::AnonPipe;
 use crate::sys::unsupported;
 use crate::sys_common::process::{CommandEnv, CommandEnvs};
+use crate::time::Duration;
 
 pub use crate::ffi::OsString as EnvKey;
 
@@ -52,6 +53,8 @@ pub fn stdout(&mut self, _stdout: Stdio) {}
 
     pub fn stderr(&mut self, _stderr: Stdio) {}
 
+    pub fn stderr_to_stdout(&mut self, _merge: bool) {}
+
     pub fn get_program(&self) -> &OsStr {
         panic!("unsupported")
     }
@@ -193,6 +196,10 @@ pub fn wait(&mut self) -> io::Result<ExitStatus> {
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         self.0
     }
+
+    pub fn wait_timeout(&mut self, _timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        self.0
+    }
 }
 
 pub struct CommandArgs<'a> {
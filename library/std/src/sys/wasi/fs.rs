@@ -17,7 +17,10 @@
 use crate::sys::unsupported;
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 
-pub use crate::sys_common::fs::try_exists;
+pub use crate::sys_common::fs::{
+    copy_symlink, create_anonymous, create_private_dir, create_private_file, link_anonymous,
+    reflink, rename_noreplace, set_dir_times, sync_dir, try_exists, OpenDir,
+};
 
 pub struct File {
     fd: WasiFd,
@@ -492,6 +495,26 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
         )
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
     pub fn read_link(&self, file: &Path) -> io::Result<PathBuf> {
         read_link(&self.fd, file)
     }
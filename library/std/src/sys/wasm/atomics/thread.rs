@@ -44,6 +44,8 @@ pub fn available_parallelism() -> io::Result<NonZeroUsize> {
     unsupported()
 }
 
+pub use crate::sys_common::thread::{affinity, set_affinity, set_priority};
+
 pub mod guard {
     pub type Guard = !;
     pub unsafe fn current() -> Option<Guard> {
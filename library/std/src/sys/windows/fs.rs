@@ -19,6 +19,11 @@
 use super::path::maybe_verbatim;
 use super::to_u16s;
 
+pub use crate::sys_common::fs::{
+    create_anonymous, create_private_dir, create_private_file, link_anonymous, reflink, sync_dir,
+    OpenDir,
+};
+
 pub struct File {
     handle: Handle,
 }
@@ -587,6 +592,66 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
         Ok(())
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        self.acquire_lock(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.acquire_lock(0)
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.try_acquire_lock(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.try_acquire_lock(0)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe {
+            c::UnlockFile(self.handle.as_raw_handle(), 0, 0, c::DWORD::MAX, c::DWORD::MAX)
+        })?;
+        Ok(())
+    }
+
+    /// Locks the whole file, waiting for the lock to become available unless `flags`
+    /// contains `LOCKFILE_FAIL_IMMEDIATELY`.
+    fn acquire_lock(&self, flags: c::DWORD) -> io::Result<()> {
+        // If the file was opened for asynchronous I/O, the lock request completes in the
+        // background (even with `LOCKFILE_FAIL_IMMEDIATELY`), so an event is needed to wait
+        // for its result.
+        let event = Handle::new_event(true, false)?;
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        overlapped.hEvent = event.as_raw_handle();
+        let res = cvt(unsafe {
+            c::LockFileEx(
+                self.handle.as_raw_handle(),
+                flags,
+                0,
+                c::DWORD::MAX,
+                c::DWORD::MAX,
+                &mut overlapped,
+            )
+        });
+        match res {
+            Ok(_) => Ok(()),
+            Err(e) if e.raw_os_error() == Some(c::ERROR_IO_PENDING as i32) => {
+                self.handle.overlapped_result(&mut overlapped, true).map(drop)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Locks the whole file if the lock is available, returning whether it was.
+    fn try_acquire_lock(&self, flags: c::DWORD) -> io::Result<bool> {
+        match self.acquire_lock(flags | c::LOCKFILE_FAIL_IMMEDIATELY) {
+            Ok(()) => Ok(true),
+            Err(e) if e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Get only basic file information such as attributes and file times.
     fn basic_info(&self) -> io::Result<c::FILE_BASIC_INFO> {
         unsafe {
@@ -1062,6 +1127,13 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn rename_noreplace(old: &Path, new: &Path) -> io::Result<()> {
+    let old = maybe_verbatim(old)?;
+    let new = maybe_verbatim(new)?;
+    cvt(unsafe { c::MoveFileExW(old.as_ptr(), new.as_ptr(), c::MOVEFILE_WRITE_THROUGH) })?;
+    Ok(())
+}
+
 pub fn rmdir(p: &Path) -> io::Result<()> {
     let p = maybe_verbatim(p)?;
     cvt(unsafe { c::RemoveDirectoryW(p.as_ptr()) })?;
@@ -1190,6 +1262,13 @@ pub fn symlink(original: &Path, link: &Path) -> io::Result<()> {
     symlink_inner(original, link, false)
 }
 
+/// Creates a symlink at `to` with the same target as the symlink `from`, and to a
+/// directory if `from` is.
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let dir = lstat(from)?.file_type().is_symlink_dir();
+    symlink_inner(&readlink(from)?, to, dir)
+}
+
 pub fn symlink_inner(original: &Path, link: &Path, dir: bool) -> io::Result<()> {
     let original = to_u16s(original)?;
     let link = maybe_verbatim(link)?;
@@ -1327,6 +1406,14 @@ fn get_path(f: &File) -> io::Result<PathBuf> {
     )
 }
 
+pub fn set_dir_times(p: &Path, times: FileTimes) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.access_mode(c::FILE_WRITE_ATTRIBUTES);
+    // This flag is so we can open directories
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    File::open(p, &opts)?.set_times(times)
+}
+
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     let mut opts = OpenOptions::new();
     // No read or write permissions are necessary
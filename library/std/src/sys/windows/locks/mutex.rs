@@ -16,6 +16,8 @@
 
 use crate::cell::UnsafeCell;
 use crate::sys::c;
+use crate::sys::common::locks::try_lock_until;
+use crate::time::Duration;
 
 pub struct Mutex {
     srwlock: UnsafeCell<c::SRWLOCK>,
@@ -47,6 +49,12 @@ pub fn try_lock(&self) -> bool {
         unsafe { c::TryAcquireSRWLockExclusive(raw(self)) != 0 }
     }
 
+    // SRWLock has no timed acquisition, so poll `try_lock` instead.
+    #[inline]
+    pub fn lock_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_lock())
+    }
+
     #[inline]
     pub unsafe fn unlock(&self) {
         c::ReleaseSRWLockExclusive(raw(self));
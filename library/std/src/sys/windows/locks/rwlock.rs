@@ -1,5 +1,7 @@
 use crate::cell::UnsafeCell;
 use crate::sys::c;
+use crate::sys::common::locks::try_lock_until;
+use crate::time::Duration;
 
 pub struct RwLock {
     inner: UnsafeCell<c::SRWLOCK>,
@@ -30,6 +32,30 @@ pub fn try_write(&self) -> bool {
         unsafe { c::TryAcquireSRWLockExclusive(self.inner.get()) != 0 }
     }
     #[inline]
+    pub fn read_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_read())
+    }
+    #[inline]
+    pub fn write_timeout(&self, timeout: Duration) -> bool {
+        try_lock_until(timeout, || self.try_write())
+    }
+    // SRWLock cannot atomically turn a shared lock into an exclusive one, so an
+    // upgradable reader holds the exclusive lock from the start.
+    #[inline]
+    pub fn upgradable_read(&self) {
+        self.write()
+    }
+    #[inline]
+    pub fn try_upgradable_read(&self) -> bool {
+        self.try_write()
+    }
+    #[inline]
+    pub unsafe fn upgradable_unlock(&self) {
+        self.write_unlock()
+    }
+    #[inline]
+    pub unsafe fn upgrade(&self) {}
+    #[inline]
     pub unsafe fn read_unlock(&self) {
         c::ReleaseSRWLockShared(self.inner.get())
     }
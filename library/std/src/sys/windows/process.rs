@@ -21,6 +21,7 @@
 use crate::sys::c;
 use crate::sys::c::NonZeroDWORD;
 use crate::sys::cvt;
+use crate::sys::dur2timeout;
 use crate::sys::fs::{File, OpenOptions};
 use crate::sys::handle::Handle;
 use crate::sys::path;
@@ -28,6 +29,7 @@
 use crate::sys::stdio;
 use crate::sys_common::process::{CommandEnv, CommandEnvs};
 use crate::sys_common::IntoInner;
+use crate::time::Duration;
 
 use libc::{c_void, EXIT_FAILURE, EXIT_SUCCESS};
 
@@ -165,6 +167,7 @@ pub struct Command {
     stdin: Option<Stdio>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
+    stderr_to_stdout: bool,
     force_quotes_enabled: bool,
 }
 
@@ -194,6 +197,7 @@ pub fn new(program: &OsStr) -> Command {
             stdin: None,
             stdout: None,
             stderr: None,
+            stderr_to_stdout: false,
             force_quotes_enabled: false,
         }
     }
@@ -216,6 +220,9 @@ pub fn stdout(&mut self, stdout: Stdio) {
     pub fn stderr(&mut self, stderr: Stdio) {
         self.stderr = Some(stderr);
     }
+    pub fn stderr_to_stdout(&mut self, merge: bool) {
+        self.stderr_to_stdout = merge;
+    }
     pub fn creation_flags(&mut self, flags: u32) {
         self.flags = flags;
     }
@@ -309,7 +316,14 @@ pub fn spawn(
         let stderr = self.stderr.as_ref().unwrap_or(&default);
         let stdin = stdin.to_handle(c::STD_INPUT_HANDLE, &mut pipes.stdin)?;
         let stdout = stdout.to_handle(c::STD_OUTPUT_HANDLE, &mut pipes.stdout)?;
-        let stderr = stderr.to_handle(c::STD_ERROR_HANDLE, &mut pipes.stderr)?;
+        let is_set = |stdio: &Handle| !stdio.as_raw_handle().is_null();
+        let stderr = if !self.stderr_to_stdout {
+            stderr.to_handle(c::STD_ERROR_HANDLE, &mut pipes.stderr)?
+        } else if is_set(&stdout) {
+            stdout.duplicate(0, true, c::DUPLICATE_SAME_ACCESS)?
+        } else {
+            unsafe { Handle::from_raw_handle(ptr::null_mut()) }
+        };
 
         let mut si = zeroed_startupinfo();
         si.cb = mem::size_of::<c::STARTUPINFO>() as c::DWORD;
@@ -318,7 +332,6 @@ pub fn spawn(
         // then set the `hStd` fields in `STARTUPINFO`.
         // Otherwise skip this and allow the OS to apply its default behaviour.
         // This provides more consistent behaviour between Win7 and Win8+.
-        let is_set = |stdio: &Handle| !stdio.as_raw_handle().is_null();
         if is_set(&stderr) || is_set(&stdout) || is_set(&stdin) {
             si.dwFlags |= c::STARTF_USESTDHANDLES;
             si.hStdInput = stdin.as_raw_handle();
@@ -638,6 +651,21 @@ pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
         }
     }
 
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        unsafe {
+            match c::WaitForSingleObject(self.handle.as_raw_handle(), dur2timeout(timeout)) {
+                c::WAIT_OBJECT_0 => {}
+                c::WAIT_TIMEOUT => {
+                    return Ok(None);
+                }
+                _ => return Err(io::Error::last_os_error()),
+            }
+            let mut status = 0;
+            cvt(c::GetExitCodeProcess(self.handle.as_raw_handle(), &mut status))?;
+            Ok(Some(ExitStatus(status)))
+        }
+    }
+
     pub fn handle(&self) -> &Handle {
         &self.handle
     }
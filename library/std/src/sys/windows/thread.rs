@@ -113,6 +113,8 @@ pub fn available_parallelism() -> io::Result<NonZeroUsize> {
     }
 }
 
+pub use crate::sys_common::thread::{affinity, set_affinity, set_priority};
+
 #[cfg_attr(test, allow(dead_code))]
 pub mod guard {
     pub type Guard = !;
@@ -49,3 +49,93 @@ pub fn try_exists(path: &Path) -> io::Result<bool> {
         Err(error) => Err(error),
     }
 }
+
+/// Renames `old` to `new`, failing if `new` exists, by hard linking `old` to `new` first.
+pub fn rename_noreplace(old: &Path, new: &Path) -> io::Result<()> {
+    fs::hard_link(old, new)?;
+    // If this fails, `new` is already in place, but the caller has to know that `old` is
+    // still there to clean it up.
+    fs::remove_file(old)
+}
+
+pub fn create_anonymous(
+    _dir: &Path,
+    _private: bool,
+) -> io::Result<Option<crate::sys::fs::File>> {
+    Ok(None)
+}
+
+pub fn link_anonymous(_file: &crate::sys::fs::File, _link: &Path) -> io::Result<()> {
+    Err(io::const_io_error!(ErrorKind::Unsupported, "unnamed files are not supported"))
+}
+
+pub fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+pub fn reflink(_from: &crate::sys::fs::File, _to: &crate::sys::fs::File) -> io::Result<()> {
+    Err(io::const_io_error!(ErrorKind::Unsupported, "reflinks are not supported on this platform"))
+}
+
+/// Creates a symlink at `to` with the same target as the symlink `from`.
+pub fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    crate::sys::fs::symlink(&crate::sys::fs::readlink(from)?, to)
+}
+
+pub fn set_dir_times(path: &Path, times: crate::sys::fs::FileTimes) -> io::Result<()> {
+    let mut opts = crate::sys::fs::OpenOptions::new();
+    opts.read(true);
+    crate::sys::fs::File::open(path, &opts)?.set_times(times)
+}
+
+/// Creates a new file at `path`, failing if it exists, that only its owner can access on
+/// platforms that support it.
+pub fn create_private_file(path: &Path) -> io::Result<crate::sys::fs::File> {
+    let mut opts = crate::sys::fs::OpenOptions::new();
+    opts.read(true);
+    opts.write(true);
+    opts.create_new(true);
+    crate::sys::fs::File::open(path, &opts)
+}
+
+/// Creates a new directory at `path` that only its owner can access on platforms that
+/// support it.
+pub fn create_private_dir(path: &Path) -> io::Result<()> {
+    crate::sys::fs::DirBuilder::new().mkdir(path)
+}
+
+/// A directory being walked by `fs::WalkDir`, on platforms that open subdirectories by path.
+#[derive(Debug)]
+pub struct OpenDir(crate::sys::fs::ReadDir);
+
+impl OpenDir {
+    pub fn open(path: &Path) -> io::Result<OpenDir> {
+        crate::sys::fs::readdir(path).map(OpenDir)
+    }
+
+    /// Opens the subdirectory `entry` of this directory. Unless `follow_symlinks` is set,
+    /// symlinks fail to open. Symlinks are looked for before opening the path, so one that
+    /// replaces the directory in between is still followed.
+    pub fn open_child(
+        &self,
+        entry: &crate::sys::fs::DirEntry,
+        follow_symlinks: bool,
+    ) -> io::Result<OpenDir> {
+        let path = entry.path();
+        if !follow_symlinks && fs::symlink_metadata(&path)?.file_type().is_symlink() {
+            return Err(io::const_io_error!(
+                ErrorKind::FilesystemLoop,
+                "not following a symlink to a directory",
+            ));
+        }
+        OpenDir::open(&path)
+    }
+}
+
+impl Iterator for OpenDir {
+    type Item = io::Result<crate::sys::fs::DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<crate::sys::fs::DirEntry>> {
+        self.0.next()
+    }
+}
@@ -0,0 +1,75 @@
+use crate::sync::atomic::{
+    AtomicU32,
+    Ordering::{Acquire, Release},
+};
+use crate::sys::common::locks::Deadline;
+use crate::sys::futex::{futex_wait, futex_wake_all};
+use crate::time::Duration;
+
+const UNSET: u32 = 0;
+// Not set yet, and there might be threads sleeping on the futex.
+const WAITING: u32 = 1;
+const SET: u32 = 2;
+
+pub struct Latch {
+    state: AtomicU32,
+}
+
+impl Latch {
+    #[inline]
+    pub const fn new() -> Self {
+        Self { state: AtomicU32::new(UNSET) }
+    }
+
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.state.load(Acquire) == SET
+    }
+
+    #[inline]
+    pub fn set(&self) {
+        // Release pairs with the Acquire loads in `is_set` and `wait_contended`,
+        // so that everything before `set` is visible after a successful wait.
+        if self.state.swap(SET, Release) == WAITING {
+            futex_wake_all(&self.state);
+        }
+    }
+
+    #[inline]
+    pub fn wait(&self) {
+        if !self.is_set() {
+            self.wait_contended(None);
+        }
+    }
+
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        self.is_set() || self.wait_contended(Some(timeout))
+    }
+
+    /// Returns false if the timeout passed before the latch was set.
+    #[cold]
+    fn wait_contended(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+
+        let mut state = self.state.load(Acquire);
+        loop {
+            if state == SET {
+                return true;
+            }
+
+            // Make sure `set` knows to wake us up.
+            if state == UNSET {
+                if let Err(s) = self.state.compare_exchange(UNSET, WAITING, Acquire, Acquire) {
+                    state = s;
+                    continue;
+                }
+            }
+
+            let Some(timeout) = deadline.time_left() else { return false };
+
+            futex_wait(&self.state, WAITING, timeout);
+            state = self.state.load(Acquire);
+        }
+    }
+}
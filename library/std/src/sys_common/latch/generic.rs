@@ -0,0 +1,36 @@
+//! Latch implementation based on a Mutex and Condvar.
+
+use crate::sync::{Condvar, Mutex};
+use crate::time::Duration;
+
+pub struct Latch {
+    set: Mutex<bool>,
+    cvar: Condvar,
+}
+
+impl Latch {
+    #[inline]
+    pub const fn new() -> Self {
+        Self { set: Mutex::new(false), cvar: Condvar::new() }
+    }
+
+    pub fn is_set(&self) -> bool {
+        *self.set.lock().unwrap()
+    }
+
+    pub fn set(&self) {
+        *self.set.lock().unwrap() = true;
+        self.cvar.notify_all();
+    }
+
+    pub fn wait(&self) {
+        let set = self.set.lock().unwrap();
+        let _set = self.cvar.wait_while(set, |set| !*set).unwrap();
+    }
+
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let set = self.set.lock().unwrap();
+        let (set, _) = self.cvar.wait_timeout_while(set, timeout, |set| !*set).unwrap();
+        *set
+    }
+}
@@ -23,10 +23,12 @@
 pub mod backtrace;
 pub mod fs;
 pub mod io;
+pub mod latch;
 pub mod lazy_box;
 pub mod memchr;
 pub mod once;
 pub mod process;
+pub mod semaphore;
 pub mod thread;
 pub mod thread_info;
 pub mod thread_local_dtor;
@@ -8,6 +8,8 @@
 use crate::io;
 use crate::sys::pipe::read2;
 use crate::sys::process::{EnvKey, ExitStatus, Process, StdioPipes};
+use crate::thread;
+use crate::time::{Duration, Instant};
 
 // Stores a set of changes to an environment
 #[derive(Clone)]
@@ -155,3 +157,31 @@ pub fn wait_with_output(
     let status = process.wait()?;
     Ok((status, stdout, stderr))
 }
+
+/// Waits at most `timeout` for `process` to exit, by checking on it with
+/// `try_wait` at increasing intervals.
+///
+/// This is for platforms that have no way to wait for a child with a timeout.
+pub fn wait_timeout_by_polling(
+    process: &mut Process,
+    timeout: Duration,
+) -> io::Result<Option<ExitStatus>> {
+    const MAX_INTERVAL: Duration = Duration::from_millis(100);
+
+    let deadline = Instant::now().checked_add(timeout);
+    let mut interval = Duration::from_millis(1);
+    loop {
+        if let Some(status) = process.try_wait()? {
+            return Ok(Some(status));
+        }
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => MAX_INTERVAL,
+        };
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        thread::sleep(interval.min(remaining));
+        interval = (interval * 2).min(MAX_INTERVAL);
+    }
+}
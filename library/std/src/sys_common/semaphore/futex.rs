@@ -0,0 +1,91 @@
+use crate::sync::atomic::{
+    AtomicU32,
+    Ordering::{Acquire, Relaxed, SeqCst},
+};
+use crate::sys::common::locks::Deadline;
+use crate::sys::futex::{futex_wait, futex_wake, futex_wake_all};
+use crate::time::Duration;
+
+pub struct Semaphore {
+    /// The number of permits that can be acquired without blocking.
+    /// Waiting threads sleep on this futex while it is zero.
+    permits: AtomicU32,
+    /// The number of threads that are (about to be) waiting on `permits`.
+    waiters: AtomicU32,
+}
+
+impl Semaphore {
+    #[inline]
+    pub const fn new(permits: u32) -> Self {
+        Self { permits: AtomicU32::new(permits), waiters: AtomicU32::new(0) }
+    }
+
+    #[inline]
+    pub fn available(&self) -> u32 {
+        self.permits.load(Relaxed)
+    }
+
+    #[inline]
+    pub fn try_acquire(&self) -> bool {
+        self.permits.fetch_update(Acquire, Relaxed, |p| p.checked_sub(1)).is_ok()
+    }
+
+    #[inline]
+    pub fn acquire(&self) {
+        if !self.try_acquire() {
+            self.acquire_contended(None);
+        }
+    }
+
+    #[inline]
+    pub fn acquire_timeout(&self, timeout: Duration) -> bool {
+        self.try_acquire() || self.acquire_contended(Some(timeout))
+    }
+
+    /// Returns false if the timeout passed before a permit could be acquired.
+    #[cold]
+    fn acquire_contended(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+
+        loop {
+            // Always take an available permit, even if the deadline has passed,
+            // so that a wake up meant for us is never lost.
+            let permits = self.permits.load(Relaxed);
+            if permits > 0 {
+                match self.permits.compare_exchange_weak(permits, permits - 1, Acquire, Relaxed) {
+                    Ok(_) => return true, // Acquired!
+                    Err(_) => continue,
+                }
+            }
+
+            let Some(timeout) = deadline.time_left() else { return false };
+
+            // Announce ourselves before going to sleep. This pairs with the
+            // `SeqCst` operations in `release`: either it sees us waiting, or
+            // `futex_wait` sees the new permits and returns immediately.
+            self.waiters.fetch_add(1, SeqCst);
+            futex_wait(&self.permits, 0, timeout);
+            self.waiters.fetch_sub(1, Relaxed);
+        }
+    }
+
+    /// Adds `n` permits, or returns false without adding any if that would
+    /// overflow the count.
+    pub fn release(&self, n: u32) -> bool {
+        if self.permits.fetch_update(SeqCst, Relaxed, |p| p.checked_add(n)).is_err() {
+            return false;
+        }
+
+        // Every waiter takes a single permit, so waking up more than `n` of
+        // them is never useful.
+        let waiters = self.waiters.load(SeqCst);
+        if waiters > n {
+            for _ in 0..n {
+                futex_wake(&self.permits);
+            }
+        } else if waiters > 0 {
+            futex_wake_all(&self.permits);
+        }
+        true
+    }
+}
@@ -0,0 +1,109 @@
+//! Semaphore implementation for platforms without futexes.
+//!
+//! Permits are taken and given back with atomic operations like in the futex
+//! based implementation, but threads that have to wait for a permit sleep on
+//! a Condvar instead, which makes waiting and waking up slower.
+
+use crate::sync::atomic::{
+    AtomicU32,
+    Ordering::{Acquire, Relaxed, SeqCst},
+};
+use crate::sync::{Condvar, Mutex};
+use crate::sys::common::locks::Deadline;
+use crate::time::Duration;
+
+pub struct Semaphore {
+    /// The number of permits that can be acquired without blocking.
+    permits: AtomicU32,
+    /// The number of threads that are (about to be) waiting on `cvar`.
+    waiters: AtomicU32,
+    /// Only locked by threads that go to sleep, and by threads waking them up.
+    lock: Mutex<()>,
+    cvar: Condvar,
+}
+
+impl Semaphore {
+    #[inline]
+    pub const fn new(permits: u32) -> Self {
+        Self {
+            permits: AtomicU32::new(permits),
+            waiters: AtomicU32::new(0),
+            lock: Mutex::new(()),
+            cvar: Condvar::new(),
+        }
+    }
+
+    #[inline]
+    pub fn available(&self) -> u32 {
+        self.permits.load(Relaxed)
+    }
+
+    #[inline]
+    pub fn try_acquire(&self) -> bool {
+        self.permits.fetch_update(Acquire, Relaxed, |p| p.checked_sub(1)).is_ok()
+    }
+
+    #[inline]
+    pub fn acquire(&self) {
+        if !self.try_acquire() {
+            self.acquire_contended(None);
+        }
+    }
+
+    #[inline]
+    pub fn acquire_timeout(&self, timeout: Duration) -> bool {
+        self.try_acquire() || self.acquire_contended(Some(timeout))
+    }
+
+    /// Returns false if the timeout passed before a permit could be acquired.
+    #[cold]
+    fn acquire_contended(&self, timeout: Option<Duration>) -> bool {
+        let deadline = Deadline::after(timeout);
+
+        let mut guard = self.lock.lock().unwrap();
+        // Announce ourselves before checking the permits again. This pairs with
+        // the `SeqCst` operations in `release`: either it sees us waiting and
+        // notifies us once we're asleep, or we see the new permits.
+        self.waiters.fetch_add(1, SeqCst);
+        let acquired = loop {
+            // Always take an available permit, even if the deadline has passed,
+            // so that a wake up meant for us is never lost.
+            if self.permits.fetch_update(SeqCst, Relaxed, |p| p.checked_sub(1)).is_ok() {
+                break true; // Acquired!
+            }
+
+            guard = match deadline.time_left() {
+                Some(Some(remaining)) => self.cvar.wait_timeout(guard, remaining).unwrap().0,
+                Some(None) => self.cvar.wait(guard).unwrap(),
+                None => break false,
+            };
+        };
+        self.waiters.fetch_sub(1, Relaxed);
+        acquired
+    }
+
+    /// Adds `n` permits, or returns false without adding any if that would
+    /// overflow the count.
+    pub fn release(&self, n: u32) -> bool {
+        if self.permits.fetch_update(SeqCst, Relaxed, |p| p.checked_add(n)).is_err() {
+            return false;
+        }
+
+        let waiters = self.waiters.load(SeqCst);
+        if waiters > 0 {
+            // A waiter that hasn't seen the new permits holds the lock until
+            // it is asleep, so the notification can't get lost.
+            drop(self.lock.lock().unwrap());
+            // Every waiter takes a single permit, so waking up more than `n`
+            // of them is never useful.
+            if waiters > n {
+                for _ in 0..n {
+                    self.cvar.notify_one();
+                }
+            } else {
+                self.cvar.notify_all();
+            }
+        }
+        true
+    }
+}
@@ -1,4 +1,5 @@
 use crate::env;
+use crate::io;
 use crate::sync::atomic::{self, Ordering};
 use crate::sys::thread as imp;
 
@@ -16,3 +17,30 @@ pub fn min_stack() -> usize {
     MIN.store(amt + 1, Ordering::Relaxed);
     amt
 }
+
+// Fallbacks for platforms that can't control on which CPUs, or with which
+// priority, threads run.
+
+#[allow(dead_code)] // not used on all platforms
+pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+    Err(io::const_io_error!(
+        io::ErrorKind::Unsupported,
+        "Setting the CPU affinity of threads is not supported on the target platform",
+    ))
+}
+
+#[allow(dead_code)] // not used on all platforms
+pub fn affinity() -> io::Result<Vec<usize>> {
+    Err(io::const_io_error!(
+        io::ErrorKind::Unsupported,
+        "Getting the CPU affinity of threads is not supported on the target platform",
+    ))
+}
+
+#[allow(dead_code)] // not used on all platforms
+pub fn set_priority(_priority: crate::thread::Priority) -> io::Result<()> {
+    Err(io::const_io_error!(
+        io::ErrorKind::Unsupported,
+        "Setting the scheduling priority of threads is not supported on the target platform",
+    ))
+}
@@ -296,6 +296,10 @@ pub struct Builder {
     name: Option<String>,
     // The size of the stack for the spawned thread in bytes
     stack_size: Option<usize>,
+    // The CPUs the spawned thread is allowed to run on
+    affinity: Option<Vec<usize>>,
+    // The scheduling policy and priority of the spawned thread
+    priority: Option<Priority>,
 }
 
 impl Builder {
@@ -319,7 +323,7 @@ impl Builder {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Builder {
-        Builder { name: None, stack_size: None }
+        Builder { name: None, stack_size: None, affinity: None, priority: None }
     }
 
     /// Names the thread-to-be. Currently the name is used for identification
@@ -375,6 +379,66 @@ pub fn stack_size(mut self, size: usize) -> Builder {
         self
     }
 
+    /// Restricts the thread-to-be to run only on the given CPUs.
+    ///
+    /// CPUs are identified by their index, starting at zero. See
+    /// [`set_affinity`] for details, including on which platforms this is
+    /// supported.
+    ///
+    /// The affinity is applied before the thread runs any code. If that
+    /// fails, the thread does not run its closure and [`spawn`] returns the
+    /// error.
+    ///
+    /// [`spawn`]: Builder::spawn
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread;
+    ///
+    /// let handler = thread::Builder::new().affinity(&[0]).spawn(|| {
+    ///     assert_eq!(thread::affinity().unwrap(), [0]);
+    /// }).unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    // FIXME(thread_scheduling): open a tracking issue and use it instead of `issue = "none"`.
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    pub fn affinity(mut self, cpus: &[usize]) -> Builder {
+        self.affinity = Some(cpus.to_vec());
+        self
+    }
+
+    /// Sets the scheduling policy and priority of the thread-to-be.
+    ///
+    /// See [`set_priority`] for details, including on which platforms this is
+    /// supported.
+    ///
+    /// The priority is applied before the thread runs any code. If that
+    /// fails, the thread does not run its closure and [`spawn`] returns the
+    /// error.
+    ///
+    /// [`spawn`]: Builder::spawn
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_scheduling)]
+    /// use std::thread::{self, Priority};
+    ///
+    /// let handler = thread::Builder::new().priority(Priority::Fifo(10)).spawn(|| {
+    ///     // latency-sensitive code
+    /// }).unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    pub fn priority(mut self, priority: Priority) -> Builder {
+        self.priority = Some(priority);
+        self
+    }
+
     /// Spawns a new thread by taking ownership of the `Builder`, and returns an
     /// [`io::Result`] to its [`JoinHandle`].
     ///
@@ -499,7 +563,7 @@ unsafe fn spawn_unchecked_<'a, 'scope, F, T>(
         T: Send + 'a,
         'scope: 'a,
     {
-        let Builder { name, stack_size } = self;
+        let Builder { name, stack_size, affinity, priority } = self;
 
         let stack_size = stack_size.unwrap_or_else(thread::min_stack);
 
@@ -542,12 +606,33 @@ fn drop(&mut self) {
             }
         }
 
+        // The scheduling settings are applied by the new thread itself, which
+        // reports back whether that worked before running any user code.
+        let (their_scheduling, scheduling_result) = if affinity.is_some() || priority.is_some() {
+            let (tx, rx) = crate::sync::mpsc::sync_channel(1);
+            (Some((affinity, priority, tx)), Some(rx))
+        } else {
+            (None, None)
+        };
+
         let f = MaybeDangling::new(f);
         let main = move || {
             if let Some(name) = their_thread.cname() {
                 imp::Thread::set_name(name);
             }
 
+            if let Some((affinity, priority, tx)) = their_scheduling {
+                let result = affinity
+                    .map_or(Ok(()), |cpus| imp::set_affinity(&cpus))
+                    .and_then(|()| priority.map_or(Ok(()), imp::set_priority));
+                let failed = result.is_err();
+                let _ = tx.send(result);
+                if failed {
+                    // `f` and `their_packet` are dropped without running `f`.
+                    return;
+                }
+            }
+
             crate::io::set_output_capture(output_capture);
 
             // SAFETY: we constructed `f` initialized.
@@ -576,31 +661,35 @@ fn drop(&mut self) {
             scope_data.increment_num_running_threads();
         }
 
-        Ok(JoinInner {
-            // SAFETY:
-            //
-            // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
-            // through FFI or otherwise used with low-level threading primitives that have no
-            // notion of or way to enforce lifetimes.
-            //
-            // As mentioned in the `Safety` section of this function's documentation, the caller of
-            // this function needs to guarantee that the passed-in lifetime is sufficiently long
-            // for the lifetime of the thread.
-            //
-            // Similarly, the `sys` implementation must guarantee that no references to the closure
-            // exist after the thread has terminated, which is signaled by `Thread::join`
-            // returning.
-            native: unsafe {
-                imp::Thread::new(
-                    stack_size,
-                    mem::transmute::<Box<dyn FnOnce() + 'a>, Box<dyn FnOnce() + 'static>>(
-                        Box::new(main),
-                    ),
-                )?
-            },
-            thread: my_thread,
-            packet: my_packet,
-        })
+        // SAFETY:
+        //
+        // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
+        // through FFI or otherwise used with low-level threading primitives that have no
+        // notion of or way to enforce lifetimes.
+        //
+        // As mentioned in the `Safety` section of this function's documentation, the caller of
+        // this function needs to guarantee that the passed-in lifetime is sufficiently long
+        // for the lifetime of the thread.
+        //
+        // Similarly, the `sys` implementation must guarantee that no references to the closure
+        // exist after the thread has terminated, which is signaled by `Thread::join`
+        // returning.
+        let native = unsafe {
+            imp::Thread::new(
+                stack_size,
+                mem::transmute::<Box<dyn FnOnce() + 'a>, Box<dyn FnOnce() + 'static>>(Box::new(
+                    main,
+                )),
+            )?
+        };
+
+        if let Some(Ok(Err(e))) = scheduling_result.map(|rx| rx.recv()) {
+            // The thread has already returned without running `f`.
+            native.join();
+            return Err(e);
+        }
+
+        Ok(JoinInner { native, thread: my_thread, packet: my_packet })
     }
 }
 
@@ -1680,9 +1769,10 @@ fn _assert_both<T: Send + Sync>() {}
 /// On Linux:
 /// - It may overcount the amount of parallelism available when limited by a
 ///   process-wide affinity mask or cgroup quotas and `sched_getaffinity()` or cgroup fs can't be
-///   queried, e.g. due to sandboxing.
+///   queried, e.g. due to sandboxing. If only the affinity mask can't be queried, the cgroup's
+///   cpuset is used instead.
 /// - It may undercount the amount of parallelism if the current thread's affinity mask
-///   does not reflect the process' cpuset, e.g. due to pinned threads.
+///   does not reflect the process' cpuset, e.g. due to threads pinned with [`set_affinity`].
 /// - If the process is in a cgroup v1 cpu controller, this may need to
 ///   scan mountpoints to find the corresponding cgroup v1 controller,
 ///   which may take time on systems with large numbers of mountpoints.
@@ -1721,3 +1811,112 @@ fn _assert_both<T: Send + Sync>() {}
 pub fn available_parallelism() -> io::Result<NonZeroUsize> {
     imp::available_parallelism()
 }
+
+/// A scheduling policy for a thread, together with its priority.
+///
+/// This is used with [`set_priority`] and [`Builder::priority`]. The valid
+/// range of the real-time priorities depends on the platform; on Linux it is
+/// 1 to 99, with higher values taking precedence.
+#[unstable(feature = "thread_scheduling", issue = "none")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Priority {
+    /// The default time-sharing scheduling of the platform.
+    ///
+    /// This corresponds to `SCHED_OTHER` on Unix.
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    Normal,
+    /// Real-time first-in, first-out scheduling with the given priority.
+    ///
+    /// This corresponds to `SCHED_FIFO` on Unix.
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    Fifo(i32),
+    /// Real-time round-robin scheduling with the given priority.
+    ///
+    /// This corresponds to `SCHED_RR` on Unix.
+    #[unstable(feature = "thread_scheduling", issue = "none")]
+    RoundRobin(i32),
+}
+
+/// Restricts the current thread to run only on the given CPUs.
+///
+/// CPUs are identified by their index, starting at zero, as also used by
+/// [`affinity`]. This replaces any previous affinity of the thread. Threads
+/// spawned afterwards inherit this affinity, unless configured otherwise with
+/// [`Builder::affinity`].
+///
+/// # Platform-specific behavior
+///
+/// This is currently implemented with `sched_setaffinity` on Linux and
+/// Android, and returns an error of kind [`Unsupported`] on other platforms.
+///
+/// [`Unsupported`]: io::ErrorKind::Unsupported
+///
+/// # Errors
+///
+/// This function returns an error if `cpus` is empty, if none of the CPUs is
+/// available to the process, or if an index is larger than the platform
+/// supports.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_scheduling)]
+/// use std::thread;
+///
+/// thread::set_affinity(&[0, 1]).expect("failed to pin thread");
+/// ```
+#[unstable(feature = "thread_scheduling", issue = "none")]
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    imp::set_affinity(cpus)
+}
+
+/// Returns the CPUs the current thread is allowed to run on, in increasing
+/// order.
+///
+/// # Platform-specific behavior
+///
+/// This is currently implemented with `sched_getaffinity` on Linux and
+/// Android, and returns an error of kind [`Unsupported`] on other platforms.
+///
+/// [`Unsupported`]: io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_scheduling)]
+/// use std::thread;
+///
+/// let cpus = thread::affinity().expect("failed to get affinity");
+/// println!("running on CPUs {cpus:?}");
+/// ```
+#[unstable(feature = "thread_scheduling", issue = "none")]
+pub fn affinity() -> io::Result<Vec<usize>> {
+    imp::affinity()
+}
+
+/// Sets the scheduling policy and priority of the current thread.
+///
+/// Real-time policies usually require elevated privileges, such as
+/// `CAP_SYS_NICE` on Linux.
+///
+/// # Platform-specific behavior
+///
+/// This is currently implemented with `pthread_setschedparam` on Linux,
+/// Android, FreeBSD, NetBSD and Apple platforms, and returns an error of kind
+/// [`Unsupported`] on other platforms.
+///
+/// [`Unsupported`]: io::ErrorKind::Unsupported
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_scheduling)]
+/// use std::thread::{self, Priority};
+///
+/// thread::set_priority(Priority::RoundRobin(10)).expect("failed to set priority");
+/// ```
+#[unstable(feature = "thread_scheduling", issue = "none")]
+pub fn set_priority(priority: Priority) -> io::Result<()> {
+    imp::set_priority(priority)
+}
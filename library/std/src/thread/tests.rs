@@ -74,6 +74,39 @@ fn test_invalid_named_thread() {
     let _ = Builder::new().name("ada l\0velace".to_string()).spawn(|| {});
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_affinity() {
+    let cpus = thread::affinity().unwrap();
+    assert!(!cpus.is_empty());
+    let first = cpus[0];
+
+    Builder::new()
+        .affinity(&[first])
+        .spawn(move || {
+            assert_eq!(thread::affinity().unwrap(), [first]);
+            thread::set_affinity(&cpus).unwrap();
+            assert_eq!(thread::affinity().unwrap(), cpus);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let err = Builder::new().affinity(&[]).spawn(|| panic!("should not run")).unwrap_err();
+    assert_eq!(err.kind(), crate::io::ErrorKind::InvalidInput);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_priority_normal() {
+    Builder::new()
+        .priority(thread::Priority::Normal)
+        .spawn(|| thread::set_priority(thread::Priority::Normal).unwrap())
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
 #[test]
 fn test_run_basic() {
     let (tx, rx) = channel();
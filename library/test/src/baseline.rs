@@ -0,0 +1,145 @@
+//! Saved benchmark results, for `--save-baseline` and `--baseline`.
+//!
+//! A baseline is a plain text file holding the raw ns/iter samples of every
+//! benchmark, one benchmark per line:
+//!
+//! ```text
+//! # libtest benchmark baseline
+//! bench::name<TAB>sample sample sample...
+//! ```
+//!
+//! Baselines live in the directory named by the `RUST_TEST_BASELINE_DIR`
+//! environment variable, or in a `bench-baselines` directory next to the test
+//! executable. Saving merges into an existing baseline of the same name, so
+//! that all the benchmark executables of a package can share it.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::bench::{Comparison, Verdict};
+use crate::stats::{self, Stats};
+
+const HEADER: &str = "# libtest benchmark baseline";
+
+/// A change is only reported if the p-value of the samples being drawn from
+/// the same distribution is below this level...
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// ...and if the medians differ by more than this fraction, to ignore changes
+/// that are statistically significant but too small to matter.
+const NOISE_THRESHOLD: f64 = 0.01;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Baseline {
+    benches: BTreeMap<String, Vec<f64>>,
+}
+
+impl Baseline {
+    /// Returns the path of the baseline called `name`.
+    pub fn path(name: &str) -> io::Result<PathBuf> {
+        let dir = match env::var_os("RUST_TEST_BASELINE_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let exe = env::current_exe()?;
+                exe.parent().map(|dir| dir.join("bench-baselines")).unwrap_or_default()
+            }
+        };
+        Ok(dir.join(format!("{name}.txt")))
+    }
+
+    /// Reads the baseline called `name`.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = Baseline::path(name)?;
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("failed to read baseline `{name}` from `{}`: {e}", path.display()),
+            )
+        })?;
+        Baseline::parse(&contents).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed baseline `{name}` in `{}`: {e}", path.display()),
+            )
+        })
+    }
+
+    /// Writes the samples of this run into the baseline called `name`,
+    /// replacing the entries of the benchmarks that were run again.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let path = Baseline::path(name)?;
+        // A malformed baseline is an error rather than overwritten, so its samples aren't lost.
+        let mut merged = match Baseline::load(name) {
+            Ok(baseline) => baseline,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Baseline::default(),
+            Err(e) => return Err(e),
+        };
+        merged.benches.extend(self.benches.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, merged.serialize())
+    }
+
+    pub fn parse(contents: &str) -> Result<Baseline, String> {
+        let mut benches = BTreeMap::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, samples) =
+                line.rsplit_once('\t').ok_or_else(|| format!("line {}: missing tab", i + 1))?;
+            let samples = samples
+                .split(' ')
+                .map(|s| s.parse::<f64>().map_err(|e| format!("line {}: {e}", i + 1)))
+                .collect::<Result<Vec<_>, _>>()?;
+            benches.insert(name.to_string(), samples);
+        }
+        Ok(Baseline { benches })
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut contents = format!("{HEADER}\n");
+        for (name, samples) in &self.benches {
+            let samples: Vec<_> = samples.iter().map(|s| s.to_string()).collect();
+            contents.push_str(&format!("{name}\t{}\n", samples.join(" ")));
+        }
+        contents
+    }
+
+    pub fn insert(&mut self, name: &str, samples: Vec<f64>) {
+        if !samples.is_empty() {
+            self.benches.insert(name.to_string(), samples);
+        }
+    }
+
+    /// Compares new samples of the benchmark `name` against this baseline.
+    /// Returns `None` if the baseline doesn't contain that benchmark.
+    pub fn compare(&self, name: &str, samples: &[f64]) -> Option<Comparison> {
+        let baseline = self.benches.get(name).filter(|b| !b.is_empty())?;
+        if samples.is_empty() {
+            return None;
+        }
+
+        let baseline_median = baseline.median();
+        if baseline_median <= 0.0 {
+            // There's no meaningful relative change from a benchmark that took no time.
+            return None;
+        }
+        let change = samples.median() / baseline_median - 1.0;
+        let p_value = stats::mann_whitney_u_test(baseline, samples);
+        let verdict = if p_value >= SIGNIFICANCE_LEVEL || change.abs() <= NOISE_THRESHOLD {
+            Verdict::Unchanged
+        } else if change > 0.0 {
+            Verdict::Regressed
+        } else {
+            Verdict::Improved
+        };
+
+        Some(Comparison { baseline_median, change, p_value, verdict })
+    }
+}
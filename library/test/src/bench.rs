@@ -9,7 +9,9 @@
 
 use crate::stats;
 use std::cmp;
+use std::fmt;
 use std::io;
+use std::mem;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -32,6 +34,7 @@ pub fn black_box<T>(dummy: T) -> T {
 pub struct Bencher {
     mode: BenchMode,
     summary: Option<stats::Summary>,
+    samples: Vec<f64>,
     pub bytes: u64,
 }
 
@@ -46,7 +49,9 @@ pub fn iter<T, F>(&mut self, mut inner: F)
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        let (summary, samples) = iter_samples(&mut inner);
+        self.summary = Some(summary);
+        self.samples = samples;
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Result<Option<stats::Summary>, String>
@@ -62,6 +67,47 @@ pub fn bench<F>(&mut self, mut f: F) -> Result<Option<stats::Summary>, String>
 pub struct BenchSamples {
     pub ns_iter_summ: stats::Summary,
     pub mb_s: usize,
+    /// The ns/iter measurements `ns_iter_summ` was computed from, before
+    /// outliers were winsorized.
+    pub samples: Vec<f64>,
+    /// How this benchmark compares to the `--baseline` it was run against.
+    pub comparison: Option<Comparison>,
+}
+
+/// The result of comparing a benchmark against a saved baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Median ns/iter of the baseline.
+    pub baseline_median: f64,
+    /// Relative change of the median from the baseline, e.g. `0.1` for 10% slower.
+    pub change: f64,
+    /// p-value of the Mann-Whitney U test between the baseline and the new samples.
+    pub p_value: f64,
+    pub verdict: Verdict,
+}
+
+/// Whether a benchmark got significantly slower or faster than its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Verdict::Regressed => "regressed",
+            Verdict::Improved => "improved",
+            Verdict::Unchanged => "unchanged",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
@@ -81,6 +127,17 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     if bs.mb_s != 0 {
         write!(output, " = {} MB/s", bs.mb_s).unwrap();
     }
+    if let Some(ref comparison) = bs.comparison {
+        write!(
+            output,
+            " (baseline {} ns/iter, {:+.2}%, p = {:.3}: {})",
+            fmt_thousands_sep(comparison.baseline_median as usize, ','),
+            comparison.change * 100.0,
+            comparison.p_value,
+            comparison.verdict,
+        )
+        .unwrap();
+    }
     output
 }
 
@@ -120,6 +177,14 @@ fn ns_iter_inner<T, F>(inner: &mut F, k: u64) -> u64
 }
 
 pub fn iter<T, F>(inner: &mut F) -> stats::Summary
+where
+    F: FnMut() -> T,
+{
+    iter_samples(inner).0
+}
+
+/// Like [`iter`], but also returns the samples the summary was computed from.
+fn iter_samples<T, F>(inner: &mut F) -> (stats::Summary, Vec<f64>)
 where
     F: FnMut() -> T,
 {
@@ -155,6 +220,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             *p = ns as f64 / (5 * n) as f64;
         }
 
+        let raw5 = samples.to_vec();
         stats::winsorize(samples, 5.0);
         let summ5 = stats::Summary::new(samples);
 
@@ -166,13 +232,13 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             && summ.median_abs_dev_pct < 1.0
             && summ.median - summ5.median < summ5.median_abs_dev
         {
-            return summ5;
+            return (summ5, raw5);
         }
 
         total_run += loop_run;
         // Longest we ever run for is 3s.
         if total_run > Duration::from_secs(3) {
-            return summ5;
+            return (summ5, raw5);
         }
 
         // If we overflow here just return the results so far. We check a
@@ -182,7 +248,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return (summ5, raw5);
             }
         };
     }
@@ -197,7 +263,7 @@ pub fn benchmark<F>(
 ) where
     F: FnMut(&mut Bencher) -> Result<(), String>,
 {
-    let mut bs = Bencher { mode: BenchMode::Auto, summary: None, bytes: 0 };
+    let mut bs = Bencher { mode: BenchMode::Auto, summary: None, samples: Vec::new(), bytes: 0 };
 
     let data = Arc::new(Mutex::new(Vec::new()));
 
@@ -215,14 +281,20 @@ pub fn benchmark<F>(
             let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
             let mb_s = bs.bytes * 1000 / ns_iter;
 
-            let bs = BenchSamples { ns_iter_summ, mb_s: mb_s as usize };
+            let samples = mem::take(&mut bs.samples);
+            let bs = BenchSamples { ns_iter_summ, mb_s: mb_s as usize, samples, comparison: None };
             TestResult::TrBench(bs)
         }
         Ok(Ok(None)) => {
             // iter not called, so no data.
             // FIXME: error in this case?
             let samples: &mut [f64] = &mut [0.0_f64; 1];
-            let bs = BenchSamples { ns_iter_summ: stats::Summary::new(samples), mb_s: 0 };
+            let bs = BenchSamples {
+                ns_iter_summ: stats::Summary::new(samples),
+                mb_s: 0,
+                samples: Vec::new(),
+                comparison: None,
+            };
             TestResult::TrBench(bs)
         }
         Err(_) => TestResult::TrFailed,
@@ -238,6 +310,6 @@ pub fn run_once<F>(f: F) -> Result<(), String>
 where
     F: FnMut(&mut Bencher) -> Result<(), String>,
 {
-    let mut bs = Bencher { mode: BenchMode::Single, summary: None, bytes: 0 };
+    let mut bs = Bencher { mode: BenchMode::Single, summary: None, samples: Vec::new(), bytes: 0 };
     bs.bench(f).map(|_| ())
 }
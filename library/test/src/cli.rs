@@ -1,10 +1,12 @@
 //! Module converting command-line arguments into test configuration.
 
 use std::env;
+use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
-use super::time::TestTimeOptions;
+use super::options::{Capture, ColorConfig, Isolation, Options, OutputFormat, RunIgnored, Shard};
+use super::time::{self, TestTimeOptions};
 use std::io::{self, IsTerminal};
 
 #[derive(Debug)]
@@ -13,19 +15,41 @@ pub struct TestOpts {
     pub filters: Vec<String>,
     pub filter_exact: bool,
     pub force_run_in_process: bool,
+    pub isolation: Isolation,
     pub exclude_should_panic: bool,
     pub run_ignored: RunIgnored,
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<PathBuf>,
     pub nocapture: bool,
+    pub capture: Capture,
     pub color: ColorConfig,
     pub format: OutputFormat,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
+    /// Only run the tests belonging to this part of the test suite.
+    pub shard: Option<Shard>,
+    /// Names of the tests that failed in an earlier run, read from the file passed
+    /// to `--failed-from`. Only these tests are run, unless `failed_first` is set.
+    pub previous_failures: Option<Vec<String>>,
+    /// Run `previous_failures` before all other tests instead of only running them.
+    pub failed_first: bool,
+    /// File to write the names of the failing tests to, for use with `--failed-from`.
+    pub record_failures: Option<PathBuf>,
+    /// Number of times a failed test is run again before it is reported as failed. Only
+    /// `StaticTestFn`s can be run again, so dynamic tests are never retried.
+    pub retries: usize,
+    /// Count tests that only passed when retried as failures.
+    pub fail_on_flaky: bool,
+    /// Save the samples of the benchmarks as the baseline with this name.
+    pub save_baseline: Option<String>,
+    /// Compare the benchmarks against the baseline with this name.
+    pub baseline: Option<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Hard limit on the run time of each test, unless overridden by `#[test_timeout]`.
+    pub test_timeout: Option<Duration>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
@@ -53,6 +77,17 @@ fn optgroups() -> getopts::Options {
     opts.optflag("", "include-ignored", "Run ignored and not ignored tests")
         .optflag("", "ignored", "Run only ignored tests")
         .optflag("", "force-run-in-process", "Forces tests to run in-process when panic=abort")
+        .optopt(
+            "",
+            "isolate",
+            "Configure how tests are isolated from each other:
+            thread  = run each test on its own thread (default, unless panic=abort);
+            process = run each test in its own process, so that tests don't share
+                      global state such as the environment or the working directory.
+                      Tests that can't be looked up by name in a new process (such as
+                      dynamically created tests) still run on their own thread.",
+            "thread|process",
+        )
         .optflag("", "exclude-should-panic", "Excludes tests marked as should_panic")
         .optflag("", "test", "Run tests and not benchmarks")
         .optflag("", "bench", "Run benchmarks instead of tests")
@@ -65,6 +100,16 @@ fn optgroups() -> getopts::Options {
             "don't capture stdout/stderr of each \
              task, allow printing directly",
         )
+        .optopt(
+            "",
+            "capture",
+            "Configure how the output of tests is captured:
+            print = Capture what tests print with print! and eprint!, also from
+                    the threads they spawn (default);
+            fd    = Run each test in its own process, and capture everything it
+                    writes to stdout and stderr, in order",
+            "print|fd",
+        )
         .optopt(
             "",
             "test-threads",
@@ -102,8 +147,10 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP 13 stream;
+            github = Print verbose output with GitHub Actions annotations",
+            "pretty|terse|json|junit|tap|github",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -142,6 +189,72 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that are still running after DURATION, e.g. `500ms`, `30s` or
+            `2m` (a bare number means seconds). `#[test_timeout]` overrides this per test.
+
+            Tests run in a subprocess (panic=abort) are killed. A test running in-process
+            cannot be stopped, so it is reported and the run ends with the tests so far.",
+            "DURATION",
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Run only the tests of shard N (counting from 0) out of the number of
+            shards given by --shard-count",
+            "N",
+        )
+        .optopt(
+            "",
+            "shard-count",
+            "Split the tests that remain after filtering into COUNT shards. Every
+            test is part of exactly one shard, and the split only depends on the
+            names of the tests, so that all runners agree on it.",
+            "COUNT",
+        )
+        .optopt(
+            "",
+            "failed-from",
+            "Only run the tests listed in PATH, as written by --record-failures",
+            "PATH",
+        )
+        .optflag(
+            "",
+            "failed-first",
+            "Run the tests listed in the --failed-from file before all other tests,
+            instead of only running those",
+        )
+        .optopt(
+            "",
+            "record-failures",
+            "Write the names of the failing tests to PATH, one per line",
+            "PATH",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run a failed test up to N more times. Tests that pass on a later attempt
+            are reported as flaky. Dynamically created tests are never retried.",
+            "N",
+        )
+        .optflag("", "fail-on-flaky", "Fail the test run if any test is flaky")
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the results of the benchmarks as the baseline NAME. The baselines
+            are stored in the RUST_TEST_BASELINE_DIR directory, or next to the
+            test executable.",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the results of the benchmarks against the baseline NAME, and
+            report the ones that got significantly slower or faster",
+            "NAME",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
@@ -258,9 +371,29 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     // Unstable flags
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
+    let isolation = get_isolation(&matches, allow_unstable, force_run_in_process)?;
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
+    let previous_failures = get_previous_failures(&matches, allow_unstable)?;
+    let failed_first = unstable_optflag!(matches, allow_unstable, "failed-first");
+    let retries = get_retries(&matches, allow_unstable)?;
+    let fail_on_flaky = unstable_optflag!(matches, allow_unstable, "fail-on-flaky");
+    let record_failures =
+        unstable_optopt!(matches, allow_unstable, "record-failures").map(PathBuf::from);
+    let save_baseline = unstable_optopt!(matches, allow_unstable, "save-baseline");
+    check_baseline_name("save-baseline", save_baseline.as_deref())?;
+    let baseline = unstable_optopt!(matches, allow_unstable, "baseline");
+    check_baseline_name("baseline", baseline.as_deref())?;
+
+    if failed_first && previous_failures.is_none() {
+        return Err("--failed-first requires --failed-from".to_string());
+    }
+    if failed_first && (shuffle || shuffle_seed.is_some()) {
+        return Err("--failed-first can't be combined with shuffling the tests".to_string());
+    }
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -275,6 +408,7 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let run_ignored = get_run_ignored(&matches, include_ignored)?;
     let filters = matches.free.clone();
     let nocapture = get_nocapture(&matches)?;
+    let capture = get_capture(&matches, allow_unstable, nocapture, force_run_in_process)?;
     let test_threads = get_test_threads(&matches)?;
     let color = get_color_config(&matches)?;
     let format = get_format(&matches, quiet, allow_unstable)?;
@@ -286,19 +420,30 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         filters,
         filter_exact: exact,
         force_run_in_process,
+        isolation,
         exclude_should_panic,
         run_ignored,
         run_tests,
         bench_benchmarks,
         logfile,
         nocapture,
+        capture,
         color,
         format,
         shuffle,
         shuffle_seed,
         test_threads,
         skip,
+        shard,
+        previous_failures,
+        failed_first,
+        record_failures,
+        retries,
+        fail_on_flaky,
+        save_baseline,
+        baseline,
         time_options,
+        test_timeout,
         options,
         fail_fast: false,
     };
@@ -336,6 +481,26 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let test_timeout = match unstable_optopt!(matches, allow_unstable, "test-timeout") {
+        Some(timeout_str) => match time::parse_timeout(&timeout_str) {
+            Some(timeout) => Some(timeout),
+            None => {
+                return Err(format!(
+                    "argument for --test-timeout must be a positive duration such as \
+                     `500ms`, `30s` or `2m` (was {timeout_str})"
+                ));
+            }
+        },
+        None => None,
+    };
+
+    Ok(test_timeout)
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -375,6 +540,86 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<Shard>> {
+    let parse = |name: &str, value: String| {
+        value.parse::<usize>().map_err(|e| {
+            format!(
+                "argument for --{name} must be a number \
+                 (error: {e})"
+            )
+        })
+    };
+    let index = unstable_optopt!(matches, allow_unstable, "shard-index");
+    let count = unstable_optopt!(matches, allow_unstable, "shard-count");
+
+    let shard = match (index, count) {
+        (None, None) => None,
+        (Some(index), Some(count)) => {
+            let index = parse("shard-index", index)?;
+            let count = parse("shard-count", count)?;
+            if count == 0 {
+                return Err("argument for --shard-count must not be 0".to_string());
+            }
+            if index >= count {
+                return Err(format!(
+                    "argument for --shard-index must be less than --shard-count ({count})"
+                ));
+            }
+            Some(Shard { index, count })
+        }
+        (Some(_), None) => return Err("--shard-index requires --shard-count".to_string()),
+        (None, Some(_)) => return Err("--shard-count requires --shard-index".to_string()),
+    };
+
+    Ok(shard)
+}
+
+fn get_previous_failures(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Vec<String>>> {
+    let path = match unstable_optopt!(matches, allow_unstable, "failed-from") {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read the --failed-from file `{path}`: {e}"))?;
+    let names =
+        contents.lines().map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect();
+
+    Ok(Some(names))
+}
+
+fn get_retries(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<usize> {
+    let retries = match unstable_optopt!(matches, allow_unstable, "retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Err(format!(
+                    "argument for --retries must be a number \
+                     (error: {e})"
+                ));
+            }
+        },
+        None => 0,
+    };
+
+    Ok(retries)
+}
+
+fn check_baseline_name(option: &str, name: Option<&str>) -> OptPartRes<()> {
+    if let Some(name) = name {
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(format!(
+                "argument for --{option} must be a non-empty name without path separators \
+                 (was {name})"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -414,10 +659,22 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler".into());
+            }
+            OutputFormat::Tap
+        }
+        Some("github") => {
+            if !allow_unstable {
+                return Err("The \"github\" format is only accepted on the nightly compiler".into());
+            }
+            OutputFormat::Github
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
-                 {v})"
+                "argument for --format must be pretty, terse, json, junit, tap or github \
+                 (was {v})"
             ));
         }
     };
@@ -425,6 +682,28 @@ fn get_format(
     Ok(format)
 }
 
+fn get_isolation(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+    force_run_in_process: bool,
+) -> OptPartRes<Isolation> {
+    let isolation = match unstable_optopt!(matches, allow_unstable, "isolate").as_deref() {
+        Some("thread") | None => Isolation::Thread,
+        Some("process") if force_run_in_process => {
+            return Err(
+                "the options --isolate=process and --force-run-in-process are mutually exclusive"
+                    .into(),
+            );
+        }
+        Some("process") => Isolation::Process,
+        Some(v) => {
+            return Err(format!("argument for --isolate must be thread or process (was {v})"));
+        }
+    };
+
+    Ok(isolation)
+}
+
 fn get_color_config(matches: &getopts::Matches) -> OptPartRes<ColorConfig> {
     let color = match matches.opt_str("color").as_deref() {
         Some("auto") | None => ColorConfig::AutoColor,
@@ -442,6 +721,31 @@ fn get_color_config(matches: &getopts::Matches) -> OptPartRes<ColorConfig> {
     Ok(color)
 }
 
+fn get_capture(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+    nocapture: bool,
+    force_run_in_process: bool,
+) -> OptPartRes<Capture> {
+    let capture = match unstable_optopt!(matches, allow_unstable, "capture").as_deref() {
+        Some("print") | None => Capture::Print,
+        Some("fd") if nocapture => {
+            return Err("the options --capture=fd and --nocapture are mutually exclusive".into());
+        }
+        Some("fd") if force_run_in_process => {
+            return Err(
+                "the options --capture=fd and --force-run-in-process are mutually exclusive".into(),
+            );
+        }
+        Some("fd") => Capture::Fd,
+        Some(v) => {
+            return Err(format!("argument for --capture must be print or fd (was {v})"));
+        }
+    };
+
+    Ok(capture)
+}
+
 fn get_nocapture(matches: &getopts::Matches) -> OptPartRes<bool> {
     let mut nocapture = matches.opt_present("nocapture");
     if !nocapture {
@@ -1,16 +1,21 @@
 //! Module providing interface for running tests in the console.
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
 use std::io::prelude::Write;
+use std::path::Path;
 use std::time::Instant;
 
 use super::{
+    baseline::Baseline,
     bench::fmt_bench_samples,
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
+    formatters::{
+        GithubFormatter, JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter,
+        TapFormatter, TerseFormatter,
+    },
     helpers::{concurrency::get_concurrency, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests, term,
@@ -46,6 +51,7 @@ pub struct ConsoleTestState {
     pub total: usize,
     pub passed: usize,
     pub failed: usize,
+    pub flaky: usize,
     pub ignored: usize,
     pub filtered_out: usize,
     pub measured: usize,
@@ -55,6 +61,12 @@ pub struct ConsoleTestState {
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub ignores: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    pub flaky_tests: Vec<(TestDesc, Vec<u8>)>,
+    pub fail_on_flaky: bool,
+    /// The baseline the benchmarks are compared against.
+    pub baseline: Option<Baseline>,
+    /// The samples of the benchmarks that were run, for `--save-baseline`.
+    pub bench_samples: Baseline,
     pub options: Options,
 }
 
@@ -64,12 +76,17 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             Some(ref path) => Some(File::create(path)?),
             None => None,
         };
+        let baseline = match opts.baseline {
+            Some(ref name) => Some(Baseline::load(name)?),
+            None => None,
+        };
 
         Ok(ConsoleTestState {
             log_out,
             total: 0,
             passed: 0,
             failed: 0,
+            flaky: 0,
             ignored: 0,
             filtered_out: 0,
             measured: 0,
@@ -79,6 +96,10 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             not_failures: Vec::new(),
             ignores: Vec::new(),
             time_failures: Vec::new(),
+            flaky_tests: Vec::new(),
+            fail_on_flaky: opts.fail_on_flaky,
+            baseline,
+            bench_samples: Baseline::default(),
             options: opts.options,
         })
     }
@@ -121,6 +142,8 @@ pub fn write_log_result(
                     }
                     TestResult::TrBench(ref bs) => fmt_bench_samples(bs),
                     TestResult::TrTimedFail => "failed (time limit exceeded)".to_owned(),
+                    TestResult::TrSignaled(signal) => format!("failed (signal {signal})"),
+                    TestResult::TrFlaky(attempt) => format!("flaky (passed on attempt {attempt})"),
                 },
                 name,
             )
@@ -132,7 +155,12 @@ pub fn write_log_result(
     }
 
     fn current_test_count(&self) -> usize {
-        self.passed + self.failed + self.ignored + self.measured
+        self.passed + self.failed + self.flaky + self.ignored + self.measured
+    }
+
+    /// Whether the test run as a whole succeeded.
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && !(self.fail_on_flaky && self.flaky > 0)
     }
 }
 
@@ -206,6 +234,7 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
                 bs.ns_iter_summ.median,
                 bs.ns_iter_summ.max - bs.ns_iter_summ.min,
             );
+            st.bench_samples.insert(test.name.as_slice(), bs.samples);
             st.measured += 1
         }
         TestResult::TrFailed => {
@@ -222,27 +251,50 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
             st.failed += 1;
             st.time_failures.push((test, stdout));
         }
+        TestResult::TrFlaky(_) => {
+            st.flaky += 1;
+            // With `--fail-on-flaky` they fail the run, so they're listed and recorded as failures.
+            if st.fail_on_flaky {
+                st.failures.push((test, stdout));
+            } else {
+                st.flaky_tests.push((test, stdout));
+            }
+        }
+        TestResult::TrSignaled(signal) => {
+            st.failed += 1;
+            let mut stdout = stdout;
+            stdout.extend_from_slice(
+                format!("note: test process was terminated by signal {signal}").as_bytes(),
+            );
+            st.failures.push((test, stdout));
+        }
     }
 }
 
 // Handler for events that occur during test execution.
 // It is provided as a callback to the `run_tests` function.
-fn on_test_event(
+pub(crate) fn on_test_event(
     event: &TestEvent,
     st: &mut ConsoleTestState,
     out: &mut dyn OutputFormatter,
 ) -> io::Result<()> {
     match (*event).clone() {
-        TestEvent::TeFiltered(filtered_tests, shuffle_seed) => {
+        TestEvent::TeFiltered(filtered_tests, shuffle_seed, shard) => {
             st.total = filtered_tests;
-            out.write_run_start(filtered_tests, shuffle_seed)?;
+            out.write_run_start(filtered_tests, shuffle_seed, shard)?;
         }
         TestEvent::TeFilteredOut(filtered_out) => {
             st.filtered_out = filtered_out;
         }
         TestEvent::TeWait(ref test) => out.write_test_start(test)?,
         TestEvent::TeTimeout(ref test) => out.write_timeout(test)?,
-        TestEvent::TeResult(completed_test) => {
+        TestEvent::TeResult(mut completed_test) => {
+            if let (TestResult::TrBench(ref mut bs), Some(baseline)) =
+                (&mut completed_test.result, &st.baseline)
+            {
+                bs.comparison = baseline.compare(completed_test.desc.name.as_slice(), &bs.samples);
+            }
+
             let test = &completed_test.desc;
             let result = &completed_test.result;
             let exec_time = &completed_test.exec_time;
@@ -286,6 +338,14 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
+        OutputFormat::Github => Box::new(GithubFormatter::new(PrettyFormatter::new(
+            output,
+            opts.use_color(),
+            max_name_len,
+            is_multithreaded,
+            opts.time_options,
+        ))),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
@@ -300,9 +360,30 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
 
     assert!(opts.fail_fast || st.current_test_count() == st.total);
 
+    if let Some(ref path) = opts.record_failures {
+        record_failures(path, &st)?;
+    }
+    if let Some(ref name) = opts.save_baseline {
+        st.bench_samples.save(name)?;
+    }
+
     out.write_run_finish(&st)
 }
 
+/// Writes the names of the failed tests to `path`, one per line, for `--failed-from`.
+pub(crate) fn record_failures(path: &Path, st: &ConsoleTestState) -> io::Result<()> {
+    let mut names: Vec<_> =
+        st.failures.iter().chain(&st.time_failures).map(|(desc, _)| desc.name.as_slice()).collect();
+    names.sort_unstable();
+
+    let mut contents = String::new();
+    for name in names {
+        contents.push_str(name);
+        contents.push('\n');
+    }
+    fs::write(path, contents)
+}
+
 // Calculates padding for given test description.
 fn len_if_padded(t: &TestDescAndFn) -> usize {
     match t.testfn.padding() {
@@ -0,0 +1,107 @@
+//! Values that are set up for tests and handed to them as arguments.
+//!
+//! With `#![feature(test_fixtures)]`, a `#[test]` function can take arguments whose
+//! types implement [`Fixture`]:
+//!
+//! * An argument taken by value is a per-test fixture. It is set up right before the
+//!   test runs, and dropped when the test returns, so its `Drop` impl does the teardown.
+//! * An argument taken by shared reference is a suite fixture. It is set up when the
+//!   first test that needs it runs, shared with all other tests of the same process,
+//!   and dropped after all tests have run.
+//!
+//! A fixture that fails to set up fails every test that takes it.
+
+use std::any::{type_name, Any, TypeId};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A value that can be set up for a test.
+pub trait Fixture: Sized {
+    /// Creates the value, or returns why it couldn't be.
+    fn setup() -> Result<Self, String>;
+}
+
+type SuiteFixture = Arc<dyn Any + Send + Sync>;
+type Slot = Arc<Mutex<Option<Result<SuiteFixture, String>>>>;
+
+struct Registry {
+    slots: Vec<(TypeId, Slot)>,
+    /// The suite fixtures that were set up, in order, so they can be torn down in reverse.
+    ready: Vec<SuiteFixture>,
+}
+
+static SUITE_FIXTURES: Mutex<Registry> =
+    Mutex::new(Registry { slots: Vec::new(), ready: Vec::new() });
+
+/// Sets up a per-test fixture. Called by the code generated for `#[test]` functions.
+///
+/// # Panics
+///
+/// Panics if the fixture can't be set up, which fails the test.
+pub fn setup<T: Fixture>() -> T {
+    T::setup().unwrap_or_else(|e| panic!("failed to set up fixture `{}`: {e}", type_name::<T>()))
+}
+
+/// Returns the suite fixture of type `T`, setting it up if this is the first test to
+/// use it. Called by the code generated for `#[test]` functions.
+///
+/// # Panics
+///
+/// Panics if the fixture can't be set up, which fails the test.
+pub fn suite<T: Fixture + Send + Sync + 'static>() -> Arc<T> {
+    let slot = {
+        let mut registry = SUITE_FIXTURES.lock().unwrap_or_else(|e| e.into_inner());
+        let id = TypeId::of::<T>();
+        match registry.slots.iter().find(|(slot_id, _)| *slot_id == id) {
+            Some((_, slot)) => slot.clone(),
+            None => {
+                let slot = Slot::default();
+                registry.slots.push((id, slot.clone()));
+                slot
+            }
+        }
+    };
+
+    // Only the slot is locked during the setup, so that tests using other fixtures
+    // aren't held up, and so that a fixture can use other suite fixtures.
+    let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+    let fixture = slot.get_or_insert_with(|| {
+        let fixture: SuiteFixture = Arc::new(T::setup()?);
+        let mut registry = SUITE_FIXTURES.lock().unwrap_or_else(|e| e.into_inner());
+        registry.ready.push(fixture.clone());
+        Ok(fixture)
+    });
+    match fixture {
+        Ok(fixture) => fixture.clone().downcast().unwrap(),
+        Err(e) => panic!("failed to set up suite fixture `{}`: {e}", type_name::<T>()),
+    }
+}
+
+/// Tears down the suite fixtures, in the reverse order they were set up in.
+///
+/// This is done after all tests have run, also when some of them failed: by `test_main`,
+/// and by a test process spawned for a single test before it exits. A fixture is only
+/// dropped once no test holds on to it anymore, so that a test that is still running
+/// after timing out can keep using it. This also means that the fixtures of a test that
+/// panicked without unwinding are not torn down.
+pub fn teardown_suite() {
+    let (slots, mut ready) = {
+        let mut registry = SUITE_FIXTURES.lock().unwrap_or_else(|e| e.into_inner());
+        (mem::take(&mut registry.slots), mem::take(&mut registry.ready))
+    };
+    // The slots hold on to the fixtures as well.
+    drop(slots);
+    while let Some(fixture) = ready.pop() {
+        drop(fixture);
+    }
+}
+
+/// Calls [`teardown_suite`] when dropped, so that it also happens when the test run fails
+/// with an error or a panic.
+pub(crate) struct SuiteTeardown;
+
+impl Drop for SuiteTeardown {
+    fn drop(&mut self) {
+        teardown_suite();
+    }
+}
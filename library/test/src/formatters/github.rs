@@ -0,0 +1,141 @@
+use std::{io, io::prelude::Write};
+
+use super::{OutputFormatter, PrettyFormatter};
+use crate::{
+    console::ConsoleTestState, options::Shard, test_result::TestResult, time, types::TestDesc,
+};
+
+/// Formatter for GitHub Actions.
+///
+/// The output is the same as the one of [`PrettyFormatter`], with a
+/// [workflow command](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions)
+/// emitted after each failing test so that the failure gets annotated inline
+/// at the test's location.
+pub(crate) struct GithubFormatter<T> {
+    pretty: PrettyFormatter<T>,
+}
+
+impl<T: Write> GithubFormatter<T> {
+    pub fn new(pretty: PrettyFormatter<T>) -> Self {
+        Self { pretty }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &crate::console::OutputLocation<T> {
+        self.pretty.output_location()
+    }
+
+    fn write_annotation(
+        &mut self,
+        command: &str,
+        desc: &TestDesc,
+        message: &str,
+    ) -> io::Result<()> {
+        let mut properties = String::new();
+        if !desc.source_file.is_empty() {
+            properties.push_str(&format!(
+                "file={},line={},",
+                EscapeProperty(desc.source_file),
+                desc.start_line
+            ));
+            // Doctests only know their line.
+            if desc.start_col != 0 {
+                properties.push_str(&format!(
+                    "col={},endLine={},endColumn={},",
+                    desc.start_col, desc.end_line, desc.end_col,
+                ));
+            }
+        }
+        properties.push_str(&format!("title={}", EscapeProperty(desc.name.as_slice())));
+
+        self.pretty.write_plain(format!("::{command} {properties}::{}\n", EscapeData(message)))
+    }
+}
+
+impl<T: Write> OutputFormatter for GithubFormatter<T> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()> {
+        self.pretty.write_run_start(test_count, shuffle_seed, shard)
+    }
+
+    fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_test_start(desc)
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_timeout(desc)
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        self.pretty.write_result(desc, result, exec_time, stdout, state)?;
+
+        let output = String::from_utf8_lossy(stdout);
+        let output = output.trim_end();
+        match *result {
+            TestResult::TrFailed if output.is_empty() => {
+                self.write_annotation("error", desc, "test failed")
+            }
+            TestResult::TrFailed => self.write_annotation("error", desc, output),
+            TestResult::TrFailedMsg(ref m) => self.write_annotation("error", desc, m),
+            TestResult::TrTimedFail => self.write_annotation("error", desc, "time limit exceeded"),
+            TestResult::TrSignaled(signal) => {
+                self.write_annotation("error", desc, &format!("terminated by signal {signal}"))
+            }
+            TestResult::TrFlaky(attempt) => {
+                let command = if state.fail_on_flaky { "error" } else { "warning" };
+                let message = format!("flaky, passed on attempt {attempt}");
+                self.write_annotation(command, desc, &message)
+            }
+            TestResult::TrOk | TestResult::TrIgnored | TestResult::TrBench(_) => Ok(()),
+        }
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.pretty.write_run_finish(state)
+    }
+}
+
+/// Escapes the message of a workflow command.
+struct EscapeData<'a>(&'a str);
+
+impl std::fmt::Display for EscapeData<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '%' => f.write_str("%25")?,
+                '\r' => f.write_str("%0D")?,
+                '\n' => f.write_str("%0A")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a property value of a workflow command, which additionally can't
+/// contain the `:` and `,` separators.
+struct EscapeProperty<'a>(&'a str);
+
+impl std::fmt::Display for EscapeProperty<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                ':' => f.write_str("%3A")?,
+                ',' => f.write_str("%2C")?,
+                c => write!(f, "{}", EscapeData(c.encode_utf8(&mut [0; 4])))?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestState, OutputLocation},
+    options::Shard,
     test_result::TestResult,
     time,
     types::TestDesc,
@@ -60,14 +61,24 @@ fn write_event(
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()> {
         let shuffle_seed_json = if let Some(shuffle_seed) = shuffle_seed {
             format!(r#", "shuffle_seed": {shuffle_seed}"#)
         } else {
             String::new()
         };
+        let shard_json = if let Some(Shard { index, count }) = shard {
+            format!(r#", "shard_index": {index}, "shard_count": {count}"#)
+        } else {
+            String::new()
+        };
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {test_count}{shuffle_seed_json} }}"#
+            r#"{{ "type": "suite", "event": "started", "test_count": {test_count}{shuffle_seed_json}{shard_json} }}"#
         ))
     }
 
@@ -110,6 +121,24 @@ fn write_result(
                 Some(r#""reason": "time limit exceeded""#),
             ),
 
+            TestResult::TrFlaky(attempt) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "flaky",
+                exec_time,
+                stdout,
+                Some(&*format!(r#""attempts": {attempt}"#)),
+            ),
+
+            TestResult::TrSignaled(signal) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                Some(&*format!(r#""reason": "signal", "signal": {signal}"#)),
+            ),
+
             TestResult::TrFailedMsg(ref m) => self.write_event(
                 "test",
                 desc.name.as_slice(),
@@ -140,15 +169,28 @@ fn write_result(
                     format!(r#", "mib_per_second": {}"#, bs.mb_s)
                 };
 
+                let samples: Vec<_> = bs.samples.iter().map(|s| s.to_string()).collect();
+
+                let comparison = match bs.comparison {
+                    Some(ref c) => format!(
+                        r#", "baseline": {{ "median": {}, "change": {}, "p_value": {}, "verdict": "{}" }}"#,
+                        c.baseline_median, c.change, c.p_value, c.verdict
+                    ),
+                    None => String::new(),
+                };
+
                 let line = format!(
                     "{{ \"type\": \"bench\", \
                      \"name\": \"{}\", \
                      \"median\": {}, \
-                     \"deviation\": {}{} }}",
+                     \"deviation\": {}{}, \
+                     \"samples\": [{}]{} }}",
                     EscapedString(desc.name.as_slice()),
                     median,
                     deviation,
-                    mbps
+                    mbps,
+                    samples.join(", "),
+                    comparison
                 );
 
                 self.writeln_message(&line)
@@ -172,7 +214,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
              \"ignored\": {}, \
              \"measured\": {}, \
              \"filtered_out\": {}",
-            if state.failed == 0 { "ok" } else { "failed" },
+            if state.is_success() { "ok" } else { "failed" },
             state.passed,
             state.failed,
             state.ignored,
@@ -180,6 +222,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             state.filtered_out,
         ))?;
 
+        if state.flaky > 0 {
+            self.write_message(&format!(", \"flaky\": {}", state.flaky))?;
+        }
+
         if let Some(ref exec_time) = state.exec_time {
             let time_str = format!(", \"exec_time\": {}", exec_time.0.as_secs_f64());
             self.write_message(&time_str)?;
@@ -187,7 +233,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
 
         self.writeln_message(" }")?;
 
-        Ok(state.failed == 0)
+        Ok(state.is_success())
     }
 }
 
@@ -4,6 +4,7 @@
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestState, OutputLocation},
+    options::Shard,
     test_result::TestResult,
     time,
     types::{TestDesc, TestType},
@@ -31,6 +32,7 @@ fn write_run_start(
         &mut self,
         _test_count: usize,
         _shuffle_seed: Option<u64>,
+        _shard: Option<Shard>,
     ) -> io::Result<()> {
         // We write xml header on run start
         self.write_message("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
@@ -71,7 +73,9 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
              tests=\"{}\" \
              skipped=\"{}\" \
              >",
-            state.failed, state.total, state.ignored
+            state.failed + if state.fail_on_flaky { state.flaky } else { 0 },
+            state.total,
+            state.ignored
         ))?;
         for (desc, result, duration) in std::mem::take(&mut self.results) {
             let (class_name, test_name) = parse_class_name(&desc);
@@ -113,6 +117,36 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
                     self.write_message("</testcase>")?;
                 }
 
+                TestResult::TrFlaky(attempt) => {
+                    self.write_message(&format!(
+                        "<testcase classname=\"{}\" \
+                         name=\"{}\" time=\"{}\">",
+                        class_name,
+                        test_name,
+                        duration.as_secs_f64()
+                    ))?;
+                    // A flaky test that passed is reported the way Maven Surefire does it.
+                    let element = if state.fail_on_flaky { "failure" } else { "flakyFailure" };
+                    self.write_message(&format!(
+                        "<{element} message=\"passed on attempt {attempt}\" type=\"flaky\"/>"
+                    ))?;
+                    self.write_message("</testcase>")?;
+                }
+
+                TestResult::TrSignaled(signal) => {
+                    self.write_message(&format!(
+                        "<testcase classname=\"{}\" \
+                         name=\"{}\" time=\"{}\">",
+                        class_name,
+                        test_name,
+                        duration.as_secs_f64()
+                    ))?;
+                    self.write_message(&format!(
+                        "<failure message=\"terminated by signal {signal}\" type=\"signal\"/>"
+                    ))?;
+                    self.write_message("</testcase>")?;
+                }
+
                 TestResult::TrBench(ref b) => {
                     self.write_message(&format!(
                         "<testcase classname=\"benchmark::{}\" \
@@ -139,7 +173,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
 
         self.out.write_all(b"\n")?;
 
-        Ok(state.failed == 0)
+        Ok(state.is_success())
     }
 }
 
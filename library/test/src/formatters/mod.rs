@@ -2,23 +2,33 @@
 
 use crate::{
     console::ConsoleTestState,
+    options::Shard,
     test_result::TestResult,
     time,
     types::{TestDesc, TestName},
 };
 
+mod github;
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
+pub(crate) use self::github::GithubFormatter;
 pub(crate) use self::json::JsonFormatter;
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()>;
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()>;
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()>;
     fn write_result(
@@ -4,6 +4,7 @@
 use crate::{
     bench::fmt_bench_samples,
     console::{ConsoleTestState, OutputLocation},
+    options::Shard,
     term,
     test_result::TestResult,
     time,
@@ -53,6 +54,10 @@ pub fn write_ignored(&mut self, message: Option<&'static str>) -> io::Result<()>
         }
     }
 
+    pub fn write_flaky(&mut self) -> io::Result<()> {
+        self.write_short_result("flaky", term::color::YELLOW)
+    }
+
     pub fn write_time_failed(&mut self) -> io::Result<()> {
         self.write_short_result("FAILED (time limit exceeded)", term::color::RED)
     }
@@ -168,6 +173,10 @@ pub fn write_time_failures(&mut self, state: &ConsoleTestState) -> io::Result<()
         self.write_results(&state.time_failures, "failures (time limit exceeded)")
     }
 
+    pub fn write_flaky_tests(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        self.write_results(&state.flaky_tests, "flaky tests")
+    }
+
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = desc.padded_name(self.max_name_len, desc.name.padding());
         if let Some(test_mode) = desc.test_mode() {
@@ -181,14 +190,24 @@ fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
 }
 
 impl<T: Write> OutputFormatter for PrettyFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()> {
         let noun = if test_count != 1 { "tests" } else { "test" };
         let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
             format!(" (shuffle seed: {shuffle_seed})")
         } else {
             String::new()
         };
-        self.write_plain(format!("\nrunning {test_count} {noun}{shuffle_seed_msg}\n"))
+        let shard_msg = if let Some(Shard { index, count }) = shard {
+            format!(" (shard {index} of {count})")
+        } else {
+            String::new()
+        };
+        self.write_plain(format!("\nrunning {test_count} {noun}{shuffle_seed_msg}{shard_msg}\n"))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -217,13 +236,19 @@ fn write_result(
 
         match *result {
             TestResult::TrOk => self.write_ok()?,
-            TestResult::TrFailed | TestResult::TrFailedMsg(_) => self.write_failed()?,
+            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrSignaled(_) => {
+                self.write_failed()?
+            }
             TestResult::TrIgnored => self.write_ignored(desc.ignore_message)?,
             TestResult::TrBench(ref bs) => {
                 self.write_bench()?;
                 self.write_plain(format!(": {}", fmt_bench_samples(bs)))?;
             }
             TestResult::TrTimedFail => self.write_time_failed()?,
+            TestResult::TrFlaky(attempt) => {
+                self.write_flaky()?;
+                self.write_plain(format!(" (passed on attempt {attempt})"))?;
+            }
         }
 
         self.write_time(desc, exec_time)?;
@@ -242,7 +267,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         if state.options.display_output {
             self.write_successes(state)?;
         }
-        let success = state.failed == 0;
+        let success = state.is_success();
         if !success {
             if !state.failures.is_empty() {
                 self.write_failures(state)?;
@@ -253,6 +278,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             }
         }
 
+        if !state.flaky_tests.is_empty() {
+            self.write_flaky_tests(state)?;
+        }
+
         self.write_plain("\ntest result: ")?;
 
         if success {
@@ -262,9 +291,11 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let flaky =
+            if state.flaky > 0 { format!("{} flaky; ", state.flaky) } else { String::new() };
         let s = format!(
-            ". {} passed; {} failed; {} ignored; {} measured; {} filtered out",
-            state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+            ". {} passed; {} failed; {}{} ignored; {} measured; {} filtered out",
+            state.passed, state.failed, flaky, state.ignored, state.measured, state.filtered_out
         );
 
         self.write_plain(s)?;
@@ -0,0 +1,194 @@
+use std::{io, io::prelude::Write};
+
+use super::OutputFormatter;
+use crate::{
+    bench::fmt_bench_samples,
+    console::{ConsoleTestState, OutputLocation},
+    options::Shard,
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Formatter for the [Test Anything Protocol](https://testanything.org/tap-version-13-specification.html),
+/// version 13.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn writeln_message(&mut self, s: &str) -> io::Result<()> {
+        assert!(!s.contains('\n'));
+
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+
+    fn write_test_line(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+    ) -> io::Result<()> {
+        self.test_number += 1;
+        let status = if ok { "ok" } else { "not ok" };
+        // `#` starts a directive, so it has to be escaped in the description, and so does
+        // the `\` that escapes it.
+        let name = escape_line_breaks(&desc.name.as_slice().replace('\\', "\\\\"));
+        let name = name.replace('#', "\\#");
+        let directive =
+            directive.map(|d| format!(" # {}", escape_line_breaks(d))).unwrap_or_default();
+        self.writeln_message(&format!("{status} {} - {name}{directive}", self.test_number))
+    }
+
+    /// Writes the YAML diagnostics block that follows a test line.
+    fn write_diagnostics(&mut self, message: Option<&str>, stdout: &[u8]) -> io::Result<()> {
+        if message.is_none() && stdout.is_empty() {
+            return Ok(());
+        }
+        self.writeln_message("  ---")?;
+        if let Some(message) = message {
+            self.writeln_message(&format!("  message: {}", YamlString(message)))?;
+        }
+        if !stdout.is_empty() {
+            self.writeln_message("  output: |")?;
+            for line in String::from_utf8_lossy(stdout).lines() {
+                self.writeln_message(&format!("    {line}"))?;
+            }
+        }
+        self.writeln_message("  ...")
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()> {
+        self.writeln_message("TAP version 13")?;
+        self.writeln_message(&format!("1..{test_count}"))?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.writeln_message(&format!("# shuffle seed: {shuffle_seed}"))?;
+        }
+        if let Some(Shard { index, count }) = shard {
+            self.writeln_message(&format!("# shard {index} of {count}"))?;
+        }
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // TAP has no notion of a test that started but didn't finish yet.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.writeln_message(&format!(
+            "# test {} has been running for over {} seconds",
+            escape_line_breaks(desc.name.as_slice()),
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        _exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let stdout =
+            if state.options.display_output || *result != TestResult::TrOk { stdout } else { &[] };
+        match *result {
+            TestResult::TrOk => {
+                self.write_test_line(true, desc, None)?;
+                self.write_diagnostics(None, stdout)
+            }
+            TestResult::TrFailed => {
+                self.write_test_line(false, desc, None)?;
+                self.write_diagnostics(None, stdout)
+            }
+            TestResult::TrFailedMsg(ref m) => {
+                self.write_test_line(false, desc, None)?;
+                self.write_diagnostics(Some(m), stdout)
+            }
+            TestResult::TrTimedFail => {
+                self.write_test_line(false, desc, None)?;
+                self.write_diagnostics(Some("time limit exceeded"), stdout)
+            }
+            TestResult::TrSignaled(signal) => {
+                self.write_test_line(false, desc, None)?;
+                self.write_diagnostics(Some(&format!("terminated by signal {signal}")), stdout)
+            }
+            TestResult::TrFlaky(attempt) => {
+                self.write_test_line(!state.fail_on_flaky, desc, None)?;
+                self.write_diagnostics(Some(&format!("flaky, passed on attempt {attempt}")), stdout)
+            }
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(message) => format!("SKIP {message}"),
+                    None => "SKIP".to_string(),
+                };
+                self.write_test_line(true, desc, Some(&directive))
+            }
+            TestResult::TrBench(ref bs) => {
+                self.write_test_line(true, desc, None)?;
+                self.writeln_message(&format!("# bench: {}", fmt_bench_samples(bs)))
+            }
+        }
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.writeln_message(&format!(
+            "# test result: {}. {} passed; {} failed; {} flaky; {} ignored; {} measured; {} filtered out",
+            if state.is_success() { "ok" } else { "FAILED" },
+            state.passed,
+            state.failed,
+            state.flaky,
+            state.ignored,
+            state.measured,
+            state.filtered_out,
+        ))?;
+
+        Ok(state.is_success())
+    }
+}
+
+/// Escapes the line breaks in text that has to stay on a single TAP line.
+fn escape_line_breaks(s: &str) -> String {
+    s.replace('\r', "\\r").replace('\n', "\\n")
+}
+
+/// A formatting utility used to print strings as double-quoted YAML scalars.
+struct YamlString<'a>(&'a str);
+
+impl std::fmt::Display for YamlString<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{
     bench::fmt_bench_samples,
     console::{ConsoleTestState, OutputLocation},
+    options::Shard,
     term,
     test_result::TestResult,
     time,
@@ -51,6 +52,10 @@ pub fn write_failed(&mut self) -> io::Result<()> {
         self.write_short_result("F", term::color::RED)
     }
 
+    pub fn write_flaky(&mut self) -> io::Result<()> {
+        self.write_short_result("~", term::color::YELLOW)
+    }
+
     pub fn write_ignored(&mut self) -> io::Result<()> {
         self.write_short_result("i", term::color::YELLOW)
     }
@@ -167,7 +172,12 @@ fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
 }
 
 impl<T: Write> OutputFormatter for TerseFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(
+        &mut self,
+        test_count: usize,
+        shuffle_seed: Option<u64>,
+        shard: Option<Shard>,
+    ) -> io::Result<()> {
         self.total_test_count = test_count;
         let noun = if test_count != 1 { "tests" } else { "test" };
         let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
@@ -175,7 +185,12 @@ fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> i
         } else {
             String::new()
         };
-        self.write_plain(format!("\nrunning {test_count} {noun}{shuffle_seed_msg}\n"))
+        let shard_msg = if let Some(Shard { index, count }) = shard {
+            format!(" (shard {index} of {count})")
+        } else {
+            String::new()
+        };
+        self.write_plain(format!("\nrunning {test_count} {noun}{shuffle_seed_msg}{shard_msg}\n"))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -200,9 +215,11 @@ fn write_result(
     ) -> io::Result<()> {
         match *result {
             TestResult::TrOk => self.write_ok(),
-            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
-                self.write_failed()
-            }
+            TestResult::TrFailed
+            | TestResult::TrFailedMsg(_)
+            | TestResult::TrTimedFail
+            | TestResult::TrSignaled(_) => self.write_failed(),
+            TestResult::TrFlaky(_) => self.write_flaky(),
             TestResult::TrIgnored => self.write_ignored(),
             TestResult::TrBench(ref bs) => {
                 if self.is_multithreaded {
@@ -226,11 +243,21 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         if state.options.display_output {
             self.write_outputs(state)?;
         }
-        let success = state.failed == 0;
+        let success = state.is_success();
         if !success {
             self.write_failures(state)?;
         }
 
+        if !state.flaky_tests.is_empty() {
+            self.write_plain("\nflaky tests:\n")?;
+            let mut flaky: Vec<_> =
+                state.flaky_tests.iter().map(|(f, _)| f.name.to_string()).collect();
+            flaky.sort();
+            for name in &flaky {
+                self.write_plain(&format!("    {name}\n"))?;
+            }
+        }
+
         self.write_plain("\ntest result: ")?;
 
         if success {
@@ -240,9 +267,11 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_pretty("FAILED", term::color::RED)?;
         }
 
+        let flaky =
+            if state.flaky > 0 { format!("{} flaky; ", state.flaky) } else { String::new() };
         let s = format!(
-            ". {} passed; {} failed; {} ignored; {} measured; {} filtered out",
-            state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+            ". {} passed; {} failed; {}{} ignored; {} measured; {} filtered out",
+            state.passed, state.failed, flaky, state.ignored, state.measured, state.filtered_out
         );
 
         self.write_plain(s)?;
@@ -18,3 +18,15 @@ pub fn get_exit_code(status: ExitStatus) -> Result<i32, String> {
         },
     }
 }
+
+#[cfg(not(unix))]
+pub fn get_exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Returns the signal that terminated the child process, if any.
+#[cfg(unix)]
+pub fn get_exit_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
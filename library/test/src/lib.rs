@@ -26,7 +26,10 @@
 // Public reexports
 pub use self::bench::{black_box, Bencher};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::fixture::Fixture;
+pub use self::options::{
+    Capture, ColorConfig, Isolation, Options, OutputFormat, RunIgnored, Shard, ShouldPanic,
+};
 pub use self::types::TestName::*;
 pub use self::types::*;
 pub use self::ColorConfig::*;
@@ -38,11 +41,11 @@ pub mod test {
         assert_test_result,
         bench::Bencher,
         cli::{parse_opts, TestOpts},
-        filter_tests,
+        filter_tests, fixture,
         helpers::metrics::{Metric, MetricMap},
-        options::{Options, RunIgnored, RunStrategy, ShouldPanic},
-        run_test, test_main, test_main_static,
-        test_result::{TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk},
+        options::{Capture, Isolation, Options, RunIgnored, RunStrategy, Shard, ShouldPanic},
+        parse_test_timeout, run_test, test_main, test_main_static,
+        test_result::{TestResult, TrFailed, TrFailedMsg, TrFlaky, TrIgnored, TrOk, TrSignaled},
         time::{TestExecTime, TestTimeOptions},
         types::{
             DynTestFn, DynTestName, StaticBenchFn, StaticTestFn, StaticTestName, TestDesc,
@@ -52,22 +55,26 @@ pub mod test {
 }
 
 use std::{
-    collections::VecDeque,
-    env, io,
-    io::prelude::Write,
-    mem::ManuallyDrop,
+    collections::{HashSet, VecDeque},
+    env, fs, io,
+    io::prelude::{Read, Seek, Write},
+    mem::{self, ManuallyDrop},
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
+    path::PathBuf,
     process::{self, Command, Termination},
+    sync::atomic::{AtomicUsize, Ordering},
     sync::mpsc::{channel, Sender},
     sync::{Arc, Mutex},
     thread,
     time::{Duration, Instant},
 };
 
+mod baseline;
 pub mod bench;
 mod cli;
 mod console;
 mod event;
+pub mod fixture;
 mod formatters;
 mod helpers;
 mod options;
@@ -83,7 +90,7 @@ pub mod test {
 use core::any::Any;
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
-use helpers::exit_code::get_exit_code;
+use helpers::exit_code::{get_exit_code, get_exit_signal};
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::RunStrategy;
 use test_result::*;
@@ -123,7 +130,7 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
             let hook = Box::new({
                 move |info: &'_ PanicInfo<'_>| {
                     if !info.can_unwind() {
-                        std::mem::forget(std::io::stderr().lock());
+                        mem::forget(std::io::stderr().lock());
                         let mut stdout = ManuallyDrop::new(std::io::stdout().lock());
                         if let Some(captured) = io::set_output_capture(None) {
                             if let Ok(data) = captured.lock() {
@@ -137,7 +144,12 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
             });
             panic::set_hook(hook);
         }
-        match console::run_tests_console(&opts, tests) {
+        let result = {
+            // Also tears the suite fixtures down if the run panics.
+            let _teardown = fixture::SuiteTeardown;
+            console::run_tests_console(&opts, tests)
+        };
+        match result {
             Ok(true) => {}
             Ok(false) => process::exit(ERROR_EXIT_CODE),
             Err(e) => {
@@ -154,6 +166,10 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=unwind.
 pub fn test_main_static(tests: &[&TestDescAndFn]) {
+    // If we're being run in SpawnedSecondary mode (`--isolate=process`), run the test here.
+    // run_test will then exit the process.
+    run_spawned_secondary_test(tests);
+
     let args = env::args().collect::<Vec<_>>();
     let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
     test_main(&args, owned_tests, None)
@@ -170,6 +186,16 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
     // If we're being run in SpawnedSecondary mode, run the test here. run_test
     // will then exit the process.
+    run_spawned_secondary_test(tests);
+
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+/// Runs the single test named by `SECONDARY_TEST_INVOKER_VAR` and exits, if this process
+/// was spawned by a test runner using `RunStrategy::SpawnPrimary`.
+fn run_spawned_secondary_test(tests: &[&TestDescAndFn]) {
     if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
         env::remove_var(SECONDARY_TEST_INVOKER_VAR);
         let test = tests
@@ -185,10 +211,6 @@ pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
         };
         run_test_in_spawned_subprocess(desc, Box::new(testfn));
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -218,6 +240,17 @@ pub fn assert_test_result<T: Termination>(result: T) -> Result<(), String> {
     }
 }
 
+/// Invoked by the expansion of `#[test_timeout = "..."]`. The test's descriptor is a
+/// constant, so an invalid duration makes compilation fail.
+#[doc(hidden)]
+#[track_caller]
+pub const fn parse_test_timeout(timeout: &str) -> Duration {
+    match time::parse_timeout(timeout) {
+        Some(timeout) => timeout,
+        None => panic!("invalid duration in `#[test_timeout]` attribute"),
+    }
+}
+
 struct FilteredTests {
     tests: Vec<(TestId, TestDescAndFn)>,
     benchs: Vec<(TestId, TestDescAndFn)>,
@@ -289,6 +322,67 @@ struct TimeoutEntry {
         timeout: Instant,
     }
 
+    // Static tests are run again when they fail, as long as `--retries` allows it. A `DynTestFn`
+    // is consumed by running it, so dynamic tests only ever get one attempt.
+    struct Attempts {
+        testfn: fn() -> Result<(), String>,
+        failed: usize,
+        stdout: Vec<u8>,
+    }
+
+    type AttemptsMap =
+        HashMap<TestId, Attempts, BuildHasherDefault<collections::hash_map::DefaultHasher>>;
+
+    // Returns whether the test is being retried, in which case it shouldn't be announced again.
+    fn start_attempt(
+        opts: &TestOpts,
+        attempts: &mut AttemptsMap,
+        id: TestId,
+        test: &TestDescAndFn,
+    ) -> bool {
+        if let Some(attempts) = attempts.get(&id) {
+            return attempts.failed > 0;
+        }
+        if let StaticTestFn(testfn) = test.testfn {
+            if opts.retries > 0 {
+                attempts.insert(id, Attempts { testfn, failed: 0, stdout: Vec::new() });
+            }
+        }
+        false
+    }
+
+    // Queues the test to run again if it failed and has retries left. Otherwise returns the
+    // result to report, which is `TrFlaky` if the test only passed after failing before.
+    fn finish_attempt(
+        opts: &TestOpts,
+        attempts: &mut AttemptsMap,
+        remaining: &mut VecDeque<(TestId, TestDescAndFn)>,
+        mut completed_test: CompletedTest,
+    ) -> Option<CompletedTest> {
+        let id = completed_test.id;
+        let Some(attempt) = attempts.get_mut(&id) else { return Some(completed_test) };
+        let failed = matches!(
+            completed_test.result,
+            TrFailed | TrFailedMsg(_) | TrTimedFail | TrSignaled(_)
+        );
+        if failed && attempt.failed < opts.retries {
+            attempt.failed += 1;
+            attempt.stdout = completed_test.stdout;
+            let test =
+                TestDescAndFn { desc: completed_test.desc, testfn: StaticTestFn(attempt.testfn) };
+            remaining.push_front((id, test));
+            return None;
+        }
+
+        let attempt = attempts.remove(&id).unwrap();
+        if completed_test.result == TrOk && attempt.failed > 0 {
+            completed_test.result = TrFlaky(attempt.failed + 1);
+            // The output of the failed attempt is more useful than that of the passing one.
+            completed_test.stdout = attempt.stdout;
+        }
+        Some(completed_test)
+    }
+
     let tests_len = tests.len();
 
     let mut filtered = FilteredTests { tests: Vec::new(), benchs: Vec::new(), next_id: 0 };
@@ -324,7 +418,7 @@ struct TimeoutEntry {
 
     let shuffle_seed = get_shuffle_seed(opts);
 
-    let event = TestEvent::TeFiltered(filtered.total_len(), shuffle_seed);
+    let event = TestEvent::TeFiltered(filtered.total_len(), shuffle_seed, opts.shard);
     notify_about_test_event(event)?;
 
     let concurrency = opts.test_threads.unwrap_or_else(get_concurrency);
@@ -339,14 +433,19 @@ struct TimeoutEntry {
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    let run_strategy = if (opts.options.panic_abort && !opts.force_run_in_process)
+        || opts.isolation == Isolation::Process
+        || (opts.capture == Capture::Fd && !opts.nocapture)
+    {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
     };
 
     let mut running_tests: TestMap = HashMap::default();
+    let mut attempts: AttemptsMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
+    let mut deadlines: Vec<TimeoutEntry> = Vec::new();
 
     fn get_timed_out_tests(
         running_tests: &TestMap,
@@ -366,26 +465,73 @@ fn get_timed_out_tests(
         timed_out
     }
 
-    fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
-        timeout_queue.front().map(|&TimeoutEntry { timeout: next_timeout, .. }| {
+    fn calc_timeout(
+        timeout_queue: &VecDeque<TimeoutEntry>,
+        deadlines: &[TimeoutEntry],
+    ) -> Option<Duration> {
+        let next_timeout =
+            timeout_queue.front().into_iter().chain(deadlines).map(|entry| entry.timeout).min();
+        next_timeout.map(|next_timeout| {
             let now = Instant::now();
             if next_timeout >= now { next_timeout - now } else { Duration::new(0, 0) }
         })
     }
 
+    // Tests running in a subprocess are killed by `spawn_test_subprocess` when they time out.
+    // A test running in-process can't be stopped, so the runner has to track its deadline,
+    // and ends the run once it passes. Dynamic tests always run in-process, see `run_test`.
+    let get_in_process_deadline = |test: &TestDescAndFn| {
+        let in_process = match run_strategy {
+            RunStrategy::InProcess => true,
+            RunStrategy::SpawnPrimary => matches!(test.testfn, DynTestFn(_)),
+        };
+        if !in_process {
+            return None;
+        }
+        test.desc.timeout.or(opts.test_timeout).map(|t| Instant::now() + t)
+    };
+
     if concurrency == 1 {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop_front().unwrap();
-            let event = TestEvent::TeWait(test.desc.clone());
-            notify_about_test_event(event)?;
+            let desc = test.desc.clone();
+            if !start_attempt(opts, &mut attempts, id, &test) {
+                let event = TestEvent::TeWait(desc.clone());
+                notify_about_test_event(event)?;
+            }
+            let deadline = get_in_process_deadline(&test);
             let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
             // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
+            let res = match deadline {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let mut completed_test = match res {
+                Ok(completed_test) => completed_test,
+                Err(_) => {
+                    // The test can't be stopped, and could keep holding locks or writing
+                    // output while other tests run, so report it and end the run.
+                    let timed_out = CompletedTest::new(id, desc, TrTimedFail, None, Vec::new());
+                    let event = TestEvent::TeResult(timed_out);
+                    notify_about_test_event(event)?;
+                    mem::forget(rx);
+                    return Ok(());
+                }
+            };
             RunningTest { join_handle }.join(&mut completed_test);
 
+            let completed_test =
+                match finish_attempt(opts, &mut attempts, &mut remaining, completed_test) {
+                    Some(completed_test) => completed_test,
+                    None => continue,
+                };
+
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrSignaled(_) => opts.fail_fast,
+                TrFlaky(_) => opts.fail_fast && opts.fail_on_flaky,
             };
 
             let event = TestEvent::TeResult(completed_test);
@@ -402,8 +548,13 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
                 let timeout = time::get_default_test_timeout();
                 let desc = test.desc.clone();
 
-                let event = TestEvent::TeWait(desc.clone());
-                notify_about_test_event(event)?; //here no pad
+                if !start_attempt(opts, &mut attempts, id, &test) {
+                    let event = TestEvent::TeWait(desc.clone());
+                    notify_about_test_event(event)?; //here no pad
+                }
+                if let Some(deadline) = get_in_process_deadline(&test) {
+                    deadlines.push(TimeoutEntry { id, desc: desc.clone(), timeout: deadline });
+                }
                 let join_handle =
                     run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
                 running_tests.insert(id, RunningTest { join_handle });
@@ -413,7 +564,7 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&timeout_queue) {
+                if let Some(timeout) = calc_timeout(&timeout_queue, &deadlines) {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
                         let event = TestEvent::TeTimeout(test);
@@ -422,6 +573,20 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
                     match res {
                         Err(RecvTimeoutError::Timeout) => {
+                            let now = Instant::now();
+                            if let Some(i) = deadlines.iter().position(|entry| entry.timeout <= now)
+                            {
+                                // The test can't be stopped, and could keep holding locks or
+                                // writing output while other tests run, so report it and end
+                                // the run.
+                                let TimeoutEntry { id, desc, .. } = deadlines.swap_remove(i);
+                                let timed_out =
+                                    CompletedTest::new(id, desc, TrTimedFail, None, Vec::new());
+                                let event = TestEvent::TeResult(timed_out);
+                                notify_about_test_event(event)?;
+                                mem::forget(rx);
+                                return Ok(());
+                            }
                             // Result is not yet ready, continue waiting.
                         }
                         _ => {
@@ -437,20 +602,28 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
             let mut completed_test = res.unwrap();
             let running_test = running_tests.remove(&completed_test.id).unwrap();
+            deadlines.retain(|entry| entry.id != completed_test.id);
             running_test.join(&mut completed_test);
+            pending -= 1;
+
+            let completed_test =
+                match finish_attempt(opts, &mut attempts, &mut remaining, completed_test) {
+                    Some(completed_test) => completed_test,
+                    None => continue,
+                };
 
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrSignaled(_) => opts.fail_fast,
+                TrFlaky(_) => opts.fail_fast && opts.fail_on_flaky,
             };
 
             let event = TestEvent::TeResult(completed_test);
             notify_about_test_event(event)?;
-            pending -= 1;
 
             if fail_fast {
                 // Prevent remaining test threads from panicking
-                std::mem::forget(rx);
+                mem::forget(rx);
                 return Ok(());
             }
         }
@@ -470,6 +643,7 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             notify_about_test_event(event)?;
         }
     }
+
     Ok(())
 }
 
@@ -511,9 +685,44 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         RunIgnored::No => {}
     }
 
+    // Keep only the tests of the requested shard
+    if let Some(shard) = opts.shard {
+        filtered = shard_tests(shard, filtered);
+    }
+
+    // Only run the tests that failed last time, or run them first
+    if let Some(ref previous_failures) = opts.previous_failures {
+        let previous_failures: HashSet<&str> =
+            previous_failures.iter().map(String::as_str).collect();
+        let (failed, others): (Vec<_>, Vec<_>) = filtered
+            .into_iter()
+            .partition(|test| previous_failures.contains(test.desc.name.as_slice()));
+        filtered = failed;
+        if opts.failed_first {
+            filtered.extend(others);
+        }
+    }
+
     filtered
 }
 
+/// Returns the tests belonging to `shard`, keeping their relative order.
+///
+/// Tests are dealt out to the shards round-robin in name order, so the split doesn't
+/// depend on the order in which the tests were passed in, and every shard gets a similar
+/// share of each module.
+fn shard_tests(shard: Shard, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
+    let mut by_name: Vec<usize> = (0..tests.len()).collect();
+    by_name.sort_by(|&a, &b| tests[a].desc.name.as_slice().cmp(tests[b].desc.name.as_slice()));
+
+    let mut in_shard = vec![false; tests.len()];
+    for (position, &i) in by_name.iter().enumerate() {
+        in_shard[i] = position % shard.count == shard.index;
+    }
+
+    tests.into_iter().zip(in_shard).filter_map(|(test, keep)| keep.then_some(test)).collect()
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests
@@ -557,7 +766,9 @@ pub fn run_test(
     struct TestRunOpts {
         pub strategy: RunStrategy,
         pub nocapture: bool,
+        pub capture: Capture,
         pub time: Option<time::TestTimeOptions>,
+        pub timeout: Option<Duration>,
     }
 
     fn run_test_inner(
@@ -583,9 +794,11 @@ fn run_test_inner(
                 id,
                 desc,
                 opts.nocapture,
+                opts.capture,
                 opts.time.is_some(),
                 monitor_ch,
                 opts.time,
+                opts.timeout,
             ),
         };
 
@@ -613,8 +826,13 @@ fn run_test_inner(
         }
     }
 
-    let test_run_opts =
-        TestRunOpts { strategy, nocapture: opts.nocapture, time: opts.time_options };
+    let test_run_opts = TestRunOpts {
+        strategy,
+        nocapture: opts.nocapture,
+        capture: opts.capture,
+        time: opts.time_options,
+        timeout: desc.timeout.or(opts.test_timeout),
+    };
 
     match testfn {
         DynBenchFn(benchfn) => {
@@ -628,8 +846,13 @@ fn run_test_inner(
             None
         }
         DynTestFn(f) => {
-            match strategy {
-                RunStrategy::InProcess => (),
+            // A spawned test process can only look up static tests. Without panic=abort,
+            // a dynamic test can still be run safely in-process instead.
+            let test_run_opts = match strategy {
+                RunStrategy::InProcess => test_run_opts,
+                _ if !opts.options.panic_abort => {
+                    TestRunOpts { strategy: RunStrategy::InProcess, ..test_run_opts }
+                }
                 _ => panic!("Cannot run dynamic test fn out-of-process"),
             };
             run_test_inner(
@@ -710,9 +933,11 @@ fn spawn_test_subprocess(
     id: TestId,
     desc: TestDesc,
     nocapture: bool,
+    capture: Capture,
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -720,13 +945,34 @@ fn spawn_test_subprocess(
 
         let mut command = Command::new(current_exe);
         command.env(SECONDARY_TEST_INVOKER_VAR, desc.name.as_slice());
+        let mut output_file = None;
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else if capture == Capture::Fd {
+            // Both streams share one file, so that the output keeps the order it was written in.
+            let file = OutputFile::create().and_then(|file| {
+                file.redirect(&mut command)?;
+                Ok(file)
+            });
+            match file {
+                Ok(file) => output_file = Some(file),
+                Err(e) => {
+                    let err = format!("Failed to create the output file of the test: {e:?}");
+                    return (TrFailed, err.into_bytes(), None);
+                }
+            }
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut command, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, timed_out) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -739,11 +985,29 @@ fn spawn_test_subprocess(
         });
 
         let std::process::Output { stdout, stderr, status } = output;
-        let mut test_output = stdout;
-        formatters::write_stderr_delimiter(&mut test_output, &desc.name);
-        test_output.extend_from_slice(&stderr);
+        let mut test_output = match output_file {
+            Some(file) => file.read().unwrap_or_else(|e| {
+                format!("Failed to read the output file of the test: {e:?}").into_bytes()
+            }),
+            None => {
+                let mut test_output = stdout;
+                formatters::write_stderr_delimiter(&mut test_output, &desc.name);
+                test_output.extend_from_slice(&stderr);
+                test_output
+            }
+        };
+
+        if timed_out {
+            let timeout = timeout.unwrap();
+            write!(&mut test_output, "note: test did not finish within {timeout:?} and was killed")
+                .unwrap();
+            return (TrTimedFail, test_output, exec_time);
+        }
 
         let result = match (|| -> Result<TestResult, String> {
+            if let Some(signal) = get_exit_signal(status) {
+                return Ok(TrSignaled(signal));
+            }
             let exit_code = get_exit_code(status)?;
             Ok(get_result_from_exit_code(&desc, exit_code, &time_opts, &exec_time))
         })() {
@@ -761,16 +1025,117 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
+/// Like `Command::output`, but kills the child if it is still running after `timeout`.
+/// Returns whether the child had to be killed along with its output.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    struct PipeReader {
+        buf: Arc<Mutex<Vec<u8>>>,
+        handle: thread::JoinHandle<()>,
+    }
+
+    impl PipeReader {
+        fn spawn<R: io::Read + Send + 'static>(pipe: Option<R>) -> PipeReader {
+            let buf = Arc::new(Mutex::new(Vec::new()));
+            let buf2 = buf.clone();
+            let handle = thread::spawn(move || {
+                let Some(mut pipe) = pipe else { return };
+                let mut chunk = [0; 4096];
+                while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                    buf2.lock().unwrap().extend_from_slice(&chunk[..n]);
+                }
+            });
+            PipeReader { buf, handle }
+        }
+
+        // Processes spawned by a killed test may keep the pipe open, so in that case
+        // only take what has been read so far instead of waiting for EOF.
+        fn finish(self, wait_for_eof: bool) -> Vec<u8> {
+            if wait_for_eof {
+                let _ = self.handle.join();
+            }
+            mem::take(&mut *self.buf.lock().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut child = command.spawn()?;
+    let stdout = PipeReader::spawn(child.stdout.take());
+    let stderr = PipeReader::spawn(child.stderr.take());
+
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status, false);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            // The child may have exited in the meantime, in which case this fails harmlessly.
+            let _ = child.kill();
+            break (child.wait()?, true);
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(10)));
+    };
+
+    let stdout = stdout.finish(!timed_out);
+    let stderr = stderr.finish(!timed_out);
+    Ok((process::Output { status, stdout, stderr }, timed_out))
+}
+
+/// A temporary file that the standard output and error of a test process are redirected to,
+/// for `--capture=fd`. It is removed when dropped.
+struct OutputFile {
+    file: fs::File,
+    path: PathBuf,
+}
+
+impl OutputFile {
+    fn create() -> io::Result<OutputFile> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = env::temp_dir();
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!("rust-test-output-{}-{n}", process::id()));
+            match fs::OpenOptions::new().read(true).write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok(OutputFile { file, path }),
+                // Left behind by an earlier process with the same id.
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Redirects both the standard output and error of `command` to this file.
+    fn redirect(&self, command: &mut Command) -> io::Result<()> {
+        command.stdout(self.file.try_clone()?);
+        command.stderr(self.file.try_clone()?);
+        Ok(())
+    }
+
+    /// Reads everything the test process wrote.
+    fn read(mut self) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        self.file.seek(io::SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut output)?;
+        Ok(output)
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
 fn run_test_in_spawned_subprocess(
     desc: TestDesc,
     testfn: Box<dyn FnOnce() -> Result<(), String> + Send>,
 ) -> ! {
     let builtin_panic_hook = panic::take_hook();
-    let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
-        let test_result = match panic_info {
-            Some(info) => calc_result(&desc, Err(info.payload()), &None, &None),
-            None => calc_result(&desc, Ok(()), &None, &None),
-        };
+    let record_result = Arc::new(move |result: Result<(), &(dyn Any + 'static + Send)>| {
+        let test_result = calc_result(&desc, result, &None, &None);
 
         // We don't support serializing TrFailedMsg, so just
         // print the message out to stderr.
@@ -778,9 +1143,8 @@ fn run_test_in_spawned_subprocess(
             eprintln!("{msg}");
         }
 
-        if let Some(info) = panic_info {
-            builtin_panic_hook(info);
-        }
+        // The process exits right here, without returning to `test_main`.
+        fixture::teardown_suite();
 
         if let TrOk = test_result {
             process::exit(test_result::TR_OK);
@@ -789,10 +1153,22 @@ fn run_test_in_spawned_subprocess(
         }
     });
     let record_result2 = record_result.clone();
-    panic::set_hook(Box::new(move |info| record_result2(Some(info))));
-    if let Err(message) = testfn() {
-        panic!("{}", message);
+    panic::set_hook(Box::new(move |info| {
+        builtin_panic_hook(info);
+        // A panic that unwinds is recorded once it is caught below, after the fixtures
+        // held by the test were dropped.
+        if !info.can_unwind() {
+            record_result2(Err(info.payload()));
+        }
+    }));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        if let Err(message) = testfn() {
+            panic!("{}", message);
+        }
+    }));
+    match result {
+        Ok(()) => record_result(Ok(())),
+        Err(e) => record_result(Err(e.as_ref())),
     }
-    record_result(None);
     unreachable!("panic=abort callback should have exited the process")
 }
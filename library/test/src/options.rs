@@ -34,6 +34,10 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP 13 output
+    Tap,
+    /// GitHub Actions workflow commands
+    Github,
 }
 
 /// Whether ignored test should be run or not
@@ -45,6 +49,36 @@ pub enum RunIgnored {
     Only,
 }
 
+/// The part of the test suite to run when it is split across several runners
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// Zero-based index of the shard to run, always less than `count`
+    pub index: usize,
+    /// Total number of shards the test suite is split into
+    pub count: usize,
+}
+
+/// How the output of tests is captured, unless `--nocapture` is passed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Capture {
+    /// Capture what the test prints with `print!`, `eprint!` and the like, including from
+    /// the threads it spawns with `std::thread`
+    Print,
+    /// Run each test in its own process, and capture everything written to its standard
+    /// output and error, in the order it was written
+    Fd,
+}
+
+/// How tests are kept from affecting each other
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Isolation {
+    /// Run each test on its own thread of the test harness process
+    Thread,
+    /// Run each test in its own process, so that tests don't share global state such
+    /// as the environment, the working directory, signal handlers or `static`s
+    Process,
+}
+
 #[derive(Clone, Copy)]
 pub enum RunStrategy {
     /// Runs the test in the current process, and sends the result back over the
@@ -300,3 +300,81 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
         }
     }
 }
+
+/// Performs a two-sided [Mann-Whitney U test][mwu] on two sets of samples and
+/// returns its p-value, i.e. the probability of observing a difference at least
+/// this large if both sets were drawn from the same distribution.
+///
+/// This test doesn't assume the samples to be normally distributed, which
+/// benchmark timings usually aren't. The p-value is computed with the normal
+/// approximation, corrected for ties, which is accurate for the sample sizes
+/// the benchmark harness produces.
+///
+/// [mwu]: https://en.wikipedia.org/wiki/Mann%E2%80%93Whitney_U_test
+pub fn mann_whitney_u_test(a: &[f64], b: &[f64]) -> f64 {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return 1.0;
+    }
+
+    let mut all: Vec<(f64, bool)> =
+        a.iter().map(|&x| (x, true)).chain(b.iter().map(|&x| (x, false))).collect();
+    all.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Assign ranks, averaging them over runs of tied values.
+    let mut rank_sum_a = 0.0;
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i + 1;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        let ties = (j - i) as f64;
+        let rank = (i + j + 1) as f64 / 2.0;
+        rank_sum_a += rank * all[i..j].iter().filter(|&&(_, from_a)| from_a).count() as f64;
+        tie_correction += ties * ties * ties - ties;
+        i = j;
+    }
+
+    let n = n1 + n2;
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let var = n1 * n2 / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    if var <= 0.0 {
+        // All samples are equal.
+        return 1.0;
+    }
+
+    // Continuity correction.
+    let z = ((u - mean).abs() - 0.5).max(0.0) / var.sqrt();
+    erfc(z / std::f64::consts::SQRT_2).min(1.0)
+}
+
+/// Complementary error function, with a fractional error below 1.2e-7.
+///
+/// See Numerical Recipes in C, 2nd edition, section 6.2.
+fn erfc(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 10] = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ];
+
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = COEFFICIENTS.iter().rev().fold(0.0, |acc, c| acc * t + c);
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
@@ -571,6 +571,20 @@ fn test_sum_f64_between_ints_that_sum_to_0() {
     assert_eq!([1e30f64, 1.2f64, -1e30f64].sum(), 1.2);
 }
 
+#[test]
+fn test_mann_whitney_u_test() {
+    let low = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let high = [6.0, 7.0, 8.0, 9.0, 10.0];
+    let p = mann_whitney_u_test(&low, &high);
+    assert!((p - 0.0122).abs() < 1e-4, "p = {p}");
+    assert_eq!(mann_whitney_u_test(&high, &low), p);
+
+    let interleaved = [1.5, 2.5, 3.5, 4.5, 5.5];
+    assert!(mann_whitney_u_test(&low, &interleaved) > 0.5);
+    assert_eq!(mann_whitney_u_test(&[1.0; 10], &[1.0; 10]), 1.0);
+    assert_eq!(mann_whitney_u_test(&[], &low), 1.0);
+}
+
 #[bench]
 pub fn sum_three_items(b: &mut Bencher) {
     b.iter(|| {
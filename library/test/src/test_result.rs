@@ -21,6 +21,11 @@ pub enum TestResult {
     TrIgnored,
     TrBench(BenchSamples),
     TrTimedFail,
+    /// The test's subprocess was terminated by the given signal.
+    TrSignaled(i32),
+    /// The test failed, but passed when it was retried. Holds the number of the
+    /// attempt that passed.
+    TrFlaky(usize),
 }
 
 /// Creates a `TestResult` depending on the raw result of test execution
@@ -1,9 +1,10 @@
 use super::*;
 
 use crate::{
-    bench::Bencher,
+    baseline::Baseline,
+    bench::{Bencher, Verdict},
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{GithubFormatter, OutputFormatter, PrettyFormatter, TapFormatter},
     options::OutputFormat,
     test::{
         filter_tests,
@@ -27,6 +28,7 @@
     },
     time::{TestTimeOptions, TimeThreshold},
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::channel;
 use std::time::Duration;
 
@@ -37,51 +39,61 @@ fn new() -> TestOpts {
             filters: vec![],
             filter_exact: false,
             force_run_in_process: false,
+            isolation: Isolation::Thread,
             exclude_should_panic: false,
             run_ignored: RunIgnored::No,
             run_tests: false,
             bench_benchmarks: false,
             logfile: None,
             nocapture: false,
+            capture: Capture::Print,
             color: AutoColor,
             format: OutputFormat::Pretty,
             shuffle: false,
             shuffle_seed: None,
             test_threads: None,
             skip: vec![],
+            shard: None,
+            previous_failures: None,
+            failed_first: false,
+            record_failures: None,
+            retries: 0,
+            fail_on_flaky: false,
+            save_baseline: None,
+            baseline: None,
             time_options: None,
+            test_timeout: None,
             options: Options::new(),
             fail_fast: false,
         }
     }
 }
 
+fn test_desc(name: &'static str) -> TestDesc {
+    TestDesc {
+        name: StaticTestName(name),
+        ignore: false,
+        ignore_message: None,
+        source_file: "",
+        start_line: 0,
+        start_col: 0,
+        end_line: 0,
+        end_col: 0,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::Unknown,
+        timeout: None,
+    }
+}
+
 fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
     vec![
         TestDescAndFn {
-            desc: TestDesc {
-                name: StaticTestName("1"),
-                ignore: true,
-                ignore_message: None,
-                should_panic: ShouldPanic::No,
-                compile_fail: false,
-                no_run: false,
-                test_type: TestType::Unknown,
-            },
-            testfn: DynTestFn(Box::new(move || Ok(()))),
-        },
-        TestDescAndFn {
-            desc: TestDesc {
-                name: StaticTestName("2"),
-                ignore: false,
-                ignore_message: None,
-                should_panic: ShouldPanic::No,
-                compile_fail: false,
-                no_run: false,
-                test_type: TestType::Unknown,
-            },
+            desc: TestDesc { ignore: true, ..test_desc("1") },
             testfn: DynTestFn(Box::new(move || Ok(()))),
         },
+        TestDescAndFn { desc: test_desc("2"), testfn: DynTestFn(Box::new(move || Ok(()))) },
     ]
 }
 
@@ -91,15 +103,7 @@ fn f() -> Result<(), String> {
         panic!();
     }
     let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: true,
-            ignore_message: None,
-            should_panic: ShouldPanic::No,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
+        desc: TestDesc { ignore: true, ..test_desc("whatever") },
         testfn: DynTestFn(Box::new(f)),
     };
     let (tx, rx) = channel();
@@ -114,15 +118,7 @@ fn f() -> Result<(), String> {
         Ok(())
     }
     let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: true,
-            ignore_message: None,
-            should_panic: ShouldPanic::No,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
+        desc: TestDesc { ignore: true, ..test_desc("whatever") },
         testfn: DynTestFn(Box::new(f)),
     };
     let (tx, rx) = channel();
@@ -139,15 +135,7 @@ fn f() -> Result<(), String> {
         panic!();
     }
     let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
-            should_panic: ShouldPanic::Yes,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
+        desc: TestDesc { should_panic: ShouldPanic::Yes, ..test_desc("whatever") },
         testfn: DynTestFn(Box::new(f)),
     };
     let (tx, rx) = channel();
@@ -165,13 +153,8 @@ fn f() -> Result<(), String> {
     }
     let desc = TestDescAndFn {
         desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
             should_panic: ShouldPanic::YesWithMessage("error message"),
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
+            ..test_desc("whatever")
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -195,13 +178,8 @@ fn f() -> Result<(), String> {
  expected substring: `"foobar"`"#;
     let desc = TestDescAndFn {
         desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
             should_panic: ShouldPanic::YesWithMessage(expected),
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
+            ..test_desc("whatever")
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -229,13 +207,8 @@ fn f() -> Result<(), String> {
     );
     let desc = TestDescAndFn {
         desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
             should_panic: ShouldPanic::YesWithMessage(expected),
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
+            ..test_desc("whatever")
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -256,15 +229,7 @@ fn f() -> Result<(), String> {
             Ok(())
         }
         let desc = TestDescAndFn {
-            desc: TestDesc {
-                name: StaticTestName("whatever"),
-                ignore: false,
-                ignore_message: None,
-                should_panic,
-                compile_fail: false,
-                no_run: false,
-                test_type: TestType::Unknown,
-            },
+            desc: TestDesc { should_panic, ..test_desc("whatever") },
             testfn: DynTestFn(Box::new(f)),
         };
         let (tx, rx) = channel();
@@ -283,18 +248,7 @@ fn report_time_test_template(report_time: bool) -> Option<TestExecTime> {
     fn f() -> Result<(), String> {
         Ok(())
     }
-    let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
-            should_panic: ShouldPanic::No,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
-        testfn: DynTestFn(Box::new(f)),
-    };
+    let desc = TestDescAndFn { desc: test_desc("whatever"), testfn: DynTestFn(Box::new(f)) };
     let time_options = if report_time { Some(TestTimeOptions::default()) } else { None };
 
     let test_opts = TestOpts { time_options, ..TestOpts::new() };
@@ -321,15 +275,7 @@ fn f() -> Result<(), String> {
         Ok(())
     }
     let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
-            should_panic: ShouldPanic::No,
-            compile_fail: false,
-            no_run: false,
-            test_type,
-        },
+        desc: TestDesc { test_type, ..test_desc("whatever") },
         testfn: DynTestFn(Box::new(f)),
     };
     // `Default` will initialize all the thresholds to 0 milliseconds.
@@ -360,15 +306,7 @@ fn test_error_on_exceed() {
 }
 
 fn typed_test_desc(test_type: TestType) -> TestDesc {
-    TestDesc {
-        name: StaticTestName("whatever"),
-        ignore: false,
-        ignore_message: None,
-        should_panic: ShouldPanic::No,
-        compile_fail: false,
-        no_run: false,
-        test_type,
-    }
+    TestDesc { test_type, ..test_desc("whatever") }
 }
 
 fn test_exec_time(millis: u64) -> TestExecTime {
@@ -409,6 +347,378 @@ fn test_time_options_threshold() {
     }
 }
 
+#[test]
+fn test_parse_timeout() {
+    assert_eq!(time::parse_timeout("500ms"), Some(Duration::from_millis(500)));
+    assert_eq!(time::parse_timeout("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(time::parse_timeout("2m"), Some(Duration::from_secs(120)));
+    assert_eq!(time::parse_timeout("15"), Some(Duration::from_secs(15)));
+    assert_eq!(time::parse_timeout("0s"), None);
+    assert_eq!(time::parse_timeout("1h"), None);
+    assert_eq!(time::parse_timeout("s"), None);
+}
+
+#[test]
+fn parse_test_timeout_flag() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout=250ms".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.test_timeout, Some(Duration::from_millis(250)));
+
+    let args = vec!["progname".to_string(), "--test-timeout=250ms".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_capture_flag() {
+    let args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.capture, Capture::Print);
+
+    let args =
+        vec!["progname".to_string(), "-Zunstable-options".to_string(), "--capture=fd".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.capture, Capture::Fd);
+
+    for conflicting in ["--nocapture", "--force-run-in-process"] {
+        let args = vec![
+            "progname".to_string(),
+            "-Zunstable-options".to_string(),
+            "--capture=fd".to_string(),
+            conflicting.to_string(),
+        ];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    let args =
+        vec!["progname".to_string(), "-Zunstable-options".to_string(), "--capture=all".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec!["progname".to_string(), "--capture=fd".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_isolate_flag() {
+    let args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.isolation, Isolation::Thread);
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--isolate=process".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.isolation, Isolation::Process);
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--isolate=fork".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--isolate=process".to_string(),
+        "--force-run-in-process".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec!["progname".to_string(), "--isolate=process".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+#[cfg(panic = "unwind")]
+fn dynamic_test_runs_in_process_when_isolated() {
+    fn f() -> Result<(), String> {
+        panic!();
+    }
+    let desc = TestDescAndFn {
+        desc: TestDesc { should_panic: ShouldPanic::Yes, ..test_desc("whatever") },
+        testfn: DynTestFn(Box::new(f)),
+    };
+    let mut opts = TestOpts::new();
+    opts.isolation = Isolation::Process;
+    let (tx, rx) = channel();
+    run_test(&opts, false, TestId(0), desc, RunStrategy::SpawnPrimary, tx);
+    let result = rx.recv().unwrap().result;
+    assert_eq!(result, TrOk);
+}
+
+#[test]
+#[cfg(all(panic = "unwind", not(target_os = "emscripten")))]
+fn dynamic_test_times_out_when_isolated() {
+    // The test blocks until it is released, so that it doesn't outlive this test.
+    let (release_tx, release_rx) = channel::<()>();
+    let (done_tx, done_rx) = channel::<()>();
+    let desc = TestDescAndFn {
+        desc: TestDesc { timeout: Some(Duration::from_millis(100)), ..test_desc("whatever") },
+        testfn: DynTestFn(Box::new(move || {
+            let _ = release_rx.recv();
+            done_tx.send(()).unwrap();
+            Ok(())
+        })),
+    };
+    let mut opts = TestOpts::new();
+    opts.run_tests = true;
+    opts.isolation = Isolation::Process;
+    let mut results = Vec::new();
+    let notify = |event: TestEvent| {
+        if let TestEvent::TeResult(completed_test) = event {
+            results.push(completed_test.result);
+        }
+        Ok(())
+    };
+    run_tests(&opts, vec![desc], notify).unwrap();
+    release_tx.send(()).unwrap();
+    done_rx.recv().unwrap();
+    assert_eq!(results, [TrTimedFail]);
+}
+
+#[test]
+fn parse_retries_flags() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--retries=2".to_string(),
+        "--fail-on-flaky".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.retries, 2);
+    assert!(opts.fail_on_flaky);
+
+    let args = vec!["progname".to_string(), "-Zunstable-options".to_string(), "--retries=x".into()];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec!["progname".to_string(), "--retries=2".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_baseline_flags() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--save-baseline=new".to_string(),
+        "--baseline=main".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.save_baseline.as_deref(), Some("new"));
+    assert_eq!(opts.baseline.as_deref(), Some("main"));
+
+    let args =
+        vec!["progname".to_string(), "-Zunstable-options".to_string(), "--baseline=../x".into()];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec!["progname".to_string(), "--save-baseline=new".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_retries() {
+    static FLAKY_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn flaky() -> Result<(), String> {
+        if FLAKY_RUNS.fetch_add(1, Ordering::SeqCst) < 2 { Err("not yet".into()) } else { Ok(()) }
+    }
+    fn fails() -> Result<(), String> {
+        Err("always".into())
+    }
+
+    for test_threads in [1, 2] {
+        for (retries, flaky_result) in [(2, TrFlaky(3)), (1, TrFailed)] {
+            FLAKY_RUNS.store(0, Ordering::SeqCst);
+            let tests = [("flaky", flaky as fn() -> Result<(), String>), ("fails", fails)]
+                .into_iter()
+                .map(|(name, f)| TestDescAndFn { desc: test_desc(name), testfn: StaticTestFn(f) })
+                .collect();
+
+            let mut started = Vec::new();
+            let mut results = Vec::new();
+            let notify = |event: TestEvent| {
+                match event {
+                    TestEvent::TeWait(desc) => started.push(desc.name.to_string()),
+                    TestEvent::TeResult(test) => {
+                        results.push((test.desc.name.to_string(), test.result))
+                    }
+                    _ => {}
+                }
+                Ok(())
+            };
+            let opts = TestOpts {
+                run_tests: true,
+                test_threads: Some(test_threads),
+                retries,
+                ..TestOpts::new()
+            };
+            run_tests(&opts, tests, notify).unwrap();
+
+            // Each test is announced once, however often it runs.
+            started.sort();
+            assert_eq!(started, ["fails", "flaky"]);
+            results.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(
+                results,
+                [("fails".to_string(), TrFailed), ("flaky".to_string(), flaky_result)]
+            );
+        }
+    }
+}
+
+#[test]
+fn fail_on_flaky_records_failures() {
+    let desc = test_desc("flaky");
+    let opts = TestOpts { fail_on_flaky: true, ..TestOpts::new() };
+    let mut st = console::ConsoleTestState::new(&opts).unwrap();
+    let mut out = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 0, false, None);
+    let event =
+        TestEvent::TeResult(CompletedTest::new(TestId(0), desc, TrFlaky(2), None, Vec::new()));
+    console::on_test_event(&event, &mut st, &mut out).unwrap();
+    assert!(!st.is_success());
+
+    let path = env::temp_dir().join(format!("libtest-fail-on-flaky-{}", std::process::id()));
+    console::record_failures(&path, &st).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "flaky\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_timeout_in_process() {
+    // The hung test blocks until it is released, so that it doesn't outlive this test.
+    let (release_tx, release_rx) = channel::<()>();
+    let (done_tx, done_rx) = channel::<()>();
+    let hang = move || {
+        let _ = release_rx.recv();
+        done_tx.send(()).unwrap();
+        Ok(())
+    };
+    let tests: [(_, Box<dyn FnOnce() -> Result<(), String> + Send>); 3] =
+        [("a", Box::new(|| Ok(()))), ("b", Box::new(hang)), ("c", Box::new(|| Ok(())))];
+    let tests = tests
+        .into_iter()
+        .map(|(name, f)| TestDescAndFn { desc: test_desc(name), testfn: DynTestFn(f) })
+        .collect();
+
+    let mut results = Vec::new();
+    let notify = |event: TestEvent| {
+        if let TestEvent::TeResult(completed_test) = event {
+            results.push((completed_test.desc.name.to_string(), completed_test.result));
+        }
+        Ok(())
+    };
+    let opts = TestOpts {
+        run_tests: true,
+        test_threads: Some(1),
+        test_timeout: Some(Duration::from_millis(100)),
+        ..TestOpts::new()
+    };
+    run_tests(&opts, tests, notify).unwrap();
+    release_tx.send(()).unwrap();
+    done_rx.recv().unwrap();
+
+    // The hung test can't be stopped, so the run ends after it.
+    assert_eq!(results, [("a".to_string(), TrOk), ("b".to_string(), TrTimedFail)]);
+}
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_timeout_in_process_concurrent() {
+    let desc = TestDesc { timeout: Some(Duration::from_millis(100)), ..test_desc("hang") };
+    let (release_tx, release_rx) = channel::<()>();
+    let (done_tx, done_rx) = channel::<()>();
+    let tests = vec![
+        TestDescAndFn {
+            desc: desc.clone(),
+            testfn: DynTestFn(Box::new(move || {
+                let _ = release_rx.recv();
+                done_tx.send(()).unwrap();
+                Ok(())
+            })),
+        },
+        TestDescAndFn {
+            desc: TestDesc { name: StaticTestName("pass"), timeout: None, ..desc },
+            testfn: DynTestFn(Box::new(|| Ok(()))),
+        },
+    ];
+
+    let mut results = Vec::new();
+    let notify = |event: TestEvent| {
+        if let TestEvent::TeResult(completed_test) = event {
+            results.push((completed_test.desc.name.to_string(), completed_test.result));
+        }
+        Ok(())
+    };
+    let opts = TestOpts { run_tests: true, test_threads: Some(4), ..TestOpts::new() };
+    run_tests(&opts, tests, notify).unwrap();
+    release_tx.send(()).unwrap();
+    done_rx.recv().unwrap();
+
+    // The run ends once the hung test times out, usually after the other test passed.
+    let hang = ("hang".to_string(), TrTimedFail);
+    assert!(results == [hang.clone()] || results == [("pass".to_string(), TrOk), hang]);
+}
+
+#[test]
+fn suite_fixtures() {
+    static SETUPS: AtomicUsize = AtomicUsize::new(0);
+    static TEARDOWNS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+    struct Server;
+    impl Fixture for Server {
+        fn setup() -> Result<Self, String> {
+            SETUPS.fetch_add(1, Ordering::SeqCst);
+            Ok(Server)
+        }
+    }
+    impl Drop for Server {
+        fn drop(&mut self) {
+            TEARDOWNS.lock().unwrap().push("server");
+        }
+    }
+
+    struct Client;
+    impl Fixture for Client {
+        fn setup() -> Result<Self, String> {
+            // Suite fixtures can depend on each other.
+            fixture::suite::<Server>();
+            Ok(Client)
+        }
+    }
+    impl Drop for Client {
+        fn drop(&mut self) {
+            TEARDOWNS.lock().unwrap().push("client");
+        }
+    }
+
+    struct Broken;
+    impl Fixture for Broken {
+        fn setup() -> Result<Self, String> {
+            Err("no network".to_string())
+        }
+    }
+
+    fixture::suite::<Server>();
+    fixture::suite::<Client>();
+    fixture::suite::<Server>();
+    assert_eq!(SETUPS.load(Ordering::SeqCst), 1);
+
+    let err = panic::catch_unwind(fixture::suite::<Broken>).err().unwrap();
+    assert!(err.downcast_ref::<String>().unwrap().ends_with("`: no network"));
+    assert!(panic::catch_unwind(fixture::setup::<Broken>).is_err());
+
+    fixture::teardown_suite();
+    assert_eq!(*TEARDOWNS.lock().unwrap(), ["client", "server"]);
+}
+
 #[test]
 fn parse_ignored_flag() {
     let args = vec!["progname".to_string(), "filter".to_string(), "--ignored".to_string()];
@@ -472,15 +782,7 @@ pub fn exclude_should_panic_option() {
 
     let mut tests = one_ignored_one_unignored_test();
     tests.push(TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("3"),
-            ignore: false,
-            ignore_message: None,
-            should_panic: ShouldPanic::Yes,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
+        desc: TestDesc { should_panic: ShouldPanic::Yes, ..test_desc("3") },
         testfn: DynTestFn(Box::new(move || Ok(()))),
     });
 
@@ -496,15 +798,7 @@ fn tests() -> Vec<TestDescAndFn> {
         ["base", "base::test", "base::test1", "base::test2"]
             .into_iter()
             .map(|name| TestDescAndFn {
-                desc: TestDesc {
-                    name: StaticTestName(name),
-                    ignore: false,
-                    ignore_message: None,
-                    should_panic: ShouldPanic::No,
-                    compile_fail: false,
-                    no_run: false,
-                    test_type: TestType::Unknown,
-                },
+                desc: test_desc(name),
                 testfn: DynTestFn(Box::new(move || Ok(()))),
             })
             .collect()
@@ -567,6 +861,155 @@ fn tests() -> Vec<TestDescAndFn> {
     assert_eq!(exact.len(), 2);
 }
 
+#[test]
+fn parse_shard_flags() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--shard-index=2".to_string(),
+        "--shard-count=3".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.shard, Some(Shard { index: 2, count: 3 }));
+
+    for bad in [
+        &["--shard-index=3", "--shard-count=3"][..],
+        &["--shard-index=0"],
+        &["--shard-index=0", "--shard-count=0"],
+    ] {
+        let mut args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        args.extend(bad.iter().map(|arg| arg.to_string()));
+        assert!(parse_opts(&args).unwrap().is_err(), "{bad:?} should be rejected");
+    }
+
+    let args =
+        vec!["progname".to_string(), "--shard-index=0".to_string(), "--shard-count=2".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+pub fn shard_tests() {
+    fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+        tests.into_iter().map(|test| test.desc.name.to_string()).collect()
+    }
+    let shard = |index, count, tests| {
+        let opts = TestOpts { shard: Some(Shard { index, count }), ..TestOpts::new() };
+        names(filter_tests(&opts, tests))
+    };
+
+    // Every test ends up in exactly one shard.
+    let mut all = Vec::new();
+    for index in 0..3 {
+        let part = shard(index, 3, sample_tests());
+        assert!(part.len() == 3 || part.len() == 4);
+        all.extend(part);
+    }
+    all.sort();
+    let mut expected = names(sample_tests());
+    expected.sort();
+    assert_eq!(all, expected);
+
+    // The split doesn't depend on the order of the tests, but the order is kept.
+    let mut reversed = sample_tests();
+    reversed.reverse();
+    let mut part = shard(1, 3, reversed);
+    part.reverse();
+    assert_eq!(part, shard(1, 3, sample_tests()));
+
+    // Sharding applies after filtering.
+    let opts = TestOpts {
+        filters: vec!["test::".into()],
+        shard: Some(Shard { index: 0, count: 2 }),
+        ..TestOpts::new()
+    };
+    assert_eq!(
+        names(filter_tests(&opts, sample_tests())),
+        [
+            "test::do_not_run_ignored_tests",
+            "test::first_free_arg_should_be_a_filter",
+            "test::parse_ignored_flag",
+            "test::run_include_ignored_option",
+        ]
+    );
+}
+
+#[test]
+fn parse_failed_from_flags() {
+    let path = env::temp_dir().join(format!("libtest-failed-from-{}", std::process::id()));
+    std::fs::write(&path, "b::test\n\na::test\n").unwrap();
+    let failed_from = format!("--failed-from={}", path.display());
+
+    let args = vec!["progname".to_string(), "-Zunstable-options".to_string(), failed_from.clone()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.previous_failures, Some(vec!["b::test".to_string(), "a::test".to_string()]));
+    assert!(!opts.failed_first);
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        failed_from.clone(),
+        "--failed-first".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().unwrap().failed_first);
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        failed_from,
+        "--failed-first".to_string(),
+        "--shuffle".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--failed-first".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+
+    std::fs::remove_file(&path).unwrap();
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        format!("--failed-from={}", path.display()),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+pub fn filter_previous_failures() {
+    fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+        tests.into_iter().map(|test| test.desc.name.to_string()).collect()
+    }
+    let previous_failures = Some(vec![
+        "test::sort_tests".to_string(),
+        "isize::test_pow".to_string(),
+        "test::no_longer_exists".to_string(),
+    ]);
+
+    let opts = TestOpts { previous_failures: previous_failures.clone(), ..TestOpts::new() };
+    assert_eq!(names(filter_tests(&opts, sample_tests())), ["isize::test_pow", "test::sort_tests"]);
+
+    let opts = TestOpts { previous_failures, failed_first: true, ..TestOpts::new() };
+    let filtered = names(filter_tests(&opts, sample_tests()));
+    assert_eq!(filtered.len(), sample_tests().len());
+    assert_eq!(filtered[..3], ["isize::test_pow", "test::sort_tests", "sha1::test"]);
+}
+
+#[test]
+fn record_failures() {
+    let mut st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    st.failures.push((test_desc("b"), Vec::new()));
+    st.time_failures.push((test_desc("a"), Vec::new()));
+    st.not_failures.push((test_desc("c"), Vec::new()));
+
+    let path = env::temp_dir().join(format!("libtest-record-failures-{}", std::process::id()));
+    console::record_failures(&path, &st).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
 fn sample_tests() -> Vec<TestDescAndFn> {
     let names = vec![
         "sha1::test".to_string(),
@@ -587,15 +1030,7 @@ fn testfn() -> Result<(), String> {
     let mut tests = Vec::new();
     for name in &names {
         let test = TestDescAndFn {
-            desc: TestDesc {
-                name: DynTestName((*name).clone()),
-                ignore: false,
-                ignore_message: None,
-                should_panic: ShouldPanic::No,
-                compile_fail: false,
-                no_run: false,
-                test_type: TestType::Unknown,
-            },
+            desc: TestDesc { name: DynTestName((*name).clone()), ..test_desc("") },
             testfn: DynTestFn(Box::new(testfn)),
         };
         tests.push(test);
@@ -716,15 +1151,7 @@ fn f(_: &mut Bencher) -> Result<(), String> {
 
     let (tx, rx) = channel();
 
-    let desc = TestDesc {
-        name: StaticTestName("f"),
-        ignore: false,
-        ignore_message: None,
-        should_panic: ShouldPanic::No,
-        compile_fail: false,
-        no_run: false,
-        test_type: TestType::Unknown,
-    };
+    let desc = test_desc("f");
 
     crate::bench::benchmark(TestId(0), desc, tx, true, f);
     rx.recv().unwrap();
@@ -739,41 +1166,43 @@ fn f(b: &mut Bencher) -> Result<(), String> {
 
     let (tx, rx) = channel();
 
-    let desc = TestDesc {
-        name: StaticTestName("f"),
-        ignore: false,
-        ignore_message: None,
-        should_panic: ShouldPanic::No,
-        compile_fail: false,
-        no_run: false,
-        test_type: TestType::Unknown,
-    };
+    let desc = test_desc("f");
 
     crate::bench::benchmark(TestId(0), desc, tx, true, f);
-    rx.recv().unwrap();
+    let completed = rx.recv().unwrap();
+    match completed.result {
+        TestResult::TrBench(bs) => assert_eq!(bs.samples.len(), 50),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn compare_against_baseline() {
+    let samples = |median: f64| (0..50).map(|i| median + (i % 10) as f64).collect::<Vec<_>>();
+
+    let mut baseline = Baseline::default();
+    baseline.insert("slower", samples(100.0));
+    baseline.insert("faster", samples(100.0));
+    baseline.insert("same", samples(100.0));
+    let baseline = Baseline::parse(&baseline.serialize()).unwrap();
+
+    let slower = baseline.compare("slower", &samples(150.0)).unwrap();
+    assert_eq!(slower.verdict, Verdict::Regressed);
+    assert!((slower.change - 50.0 / 104.5).abs() < 1e-9);
+    assert!(slower.p_value < 0.001);
+    assert_eq!(baseline.compare("faster", &samples(50.0)).unwrap().verdict, Verdict::Improved);
+    assert_eq!(baseline.compare("same", &samples(100.0)).unwrap().verdict, Verdict::Unchanged);
+    assert_eq!(baseline.compare("new", &samples(100.0)), None);
+
+    assert!(Baseline::parse("bench\t1 2 x").is_err());
+    assert!(Baseline::parse("bench 1 2 3").is_err());
 }
 
 #[test]
 fn should_sort_failures_before_printing_them() {
-    let test_a = TestDesc {
-        name: StaticTestName("a"),
-        ignore: false,
-        ignore_message: None,
-        should_panic: ShouldPanic::No,
-        compile_fail: false,
-        no_run: false,
-        test_type: TestType::Unknown,
-    };
+    let test_a = test_desc("a");
 
-    let test_b = TestDesc {
-        name: StaticTestName("b"),
-        ignore: false,
-        ignore_message: None,
-        should_panic: ShouldPanic::No,
-        compile_fail: false,
-        no_run: false,
-        test_type: TestType::Unknown,
-    };
+    let test_b = test_desc("b");
 
     let mut out = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 10, false, None);
 
@@ -782,6 +1211,7 @@ fn should_sort_failures_before_printing_them() {
         total: 0,
         passed: 0,
         failed: 0,
+        flaky: 0,
         ignored: 0,
         filtered_out: 0,
         measured: 0,
@@ -792,6 +1222,10 @@ fn should_sort_failures_before_printing_them() {
         not_failures: Vec::new(),
         ignores: Vec::new(),
         time_failures: Vec::new(),
+        flaky_tests: Vec::new(),
+        fail_on_flaky: false,
+        baseline: None,
+        bench_samples: Default::default(),
     };
 
     out.write_failures(&st).unwrap();
@@ -805,24 +1239,95 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+fn located_test_desc(name: &'static str) -> TestDesc {
+    TestDesc {
+        source_file: "src/lib.rs",
+        start_line: 12,
+        start_col: 4,
+        end_line: 12,
+        end_col: 10,
+        test_type: TestType::UnitTest,
+        ..test_desc(name)
+    }
+}
+
+#[test]
+fn tap_output() {
+    let mut out = TapFormatter::new(OutputLocation::Raw(Vec::new()));
+    let mut st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+
+    let ignored =
+        TestDesc { ignore_message: Some("slow\nand flaky"), ..located_test_desc("tests::c") };
+    out.write_run_start(3, None, None).unwrap();
+    out.write_result(&located_test_desc("tests::a"), &TrOk, None, b"", &st).unwrap();
+    st.failed = 1;
+    let failure = TestResult::TrFailedMsg("oops \"quoted\"".to_string());
+    out.write_result(&located_test_desc("tests::b\\#2"), &failure, None, b"line 1\nline 2\n", &st)
+        .unwrap();
+    out.write_result(&ignored, &TrIgnored, None, b"", &st).unwrap();
+    assert!(!out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+    assert_eq!(
+        s,
+        "TAP version 13\n\
+         1..3\n\
+         ok 1 - tests::a\n\
+         not ok 2 - tests::b\\\\\\#2\n  \
+         ---\n  \
+         message: \"oops \\\"quoted\\\"\"\n  \
+         output: |\n    \
+         line 1\n    \
+         line 2\n  \
+         ...\n\
+         ok 3 - tests::c # SKIP slow\\nand flaky\n\
+         # test result: FAILED. 0 passed; 1 failed; 0 flaky; 0 ignored; 0 measured; 0 filtered out\n"
+    );
+}
+
+#[test]
+fn github_annotations() {
+    let pretty = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 10, false, None);
+    let mut out = GithubFormatter::new(pretty);
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+
+    let unlocated = TestDesc { source_file: "", ..located_test_desc("tests::b") };
+    out.write_result(&located_test_desc("tests::a"), &TrOk, None, b"", &st).unwrap();
+    let failure = TestResult::TrFailedMsg("100% wrong\nsecond line".to_string());
+    out.write_result(&located_test_desc("tests::a"), &failure, None, b"", &st).unwrap();
+    out.write_result(&unlocated, &TestResult::TrFailed, None, b"panicked\n", &st).unwrap();
+    out.write_result(&unlocated, &TestResult::TrFlaky(2), None, b"", &st).unwrap();
+    let doctest = TestDesc { start_col: 0, end_col: 0, ..located_test_desc("tests::d") };
+    let failure = TestResult::TrFailedMsg("boom".to_string());
+    out.write_result(&doctest, &failure, None, b"", &st).unwrap();
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+    let annotations: Vec<_> = s.lines().filter(|l| l.starts_with("::")).collect();
+    assert_eq!(
+        annotations,
+        [
+            "::error file=src/lib.rs,line=12,col=4,endLine=12,endColumn=10,\
+             title=tests%3A%3Aa::100%25 wrong%0Asecond line",
+            "::error title=tests%3A%3Ab::panicked",
+            "::warning title=tests%3A%3Ab::flaky, passed on attempt 2",
+            "::error file=src/lib.rs,line=12,title=tests%3A%3Ad::boom",
+        ]
+    );
+}
+
 #[test]
 #[cfg(not(target_os = "emscripten"))]
 fn test_dyn_bench_returning_err_fails_when_run_as_test() {
     fn f(_: &mut Bencher) -> Result<(), String> {
         Result::Err("An error".into())
     }
-    let desc = TestDescAndFn {
-        desc: TestDesc {
-            name: StaticTestName("whatever"),
-            ignore: false,
-            ignore_message: None,
-            should_panic: ShouldPanic::No,
-            compile_fail: false,
-            no_run: false,
-            test_type: TestType::Unknown,
-        },
-        testfn: DynBenchFn(Box::new(f)),
-    };
+    let desc = TestDescAndFn { desc: test_desc("whatever"), testfn: DynBenchFn(Box::new(f)) };
     let (tx, rx) = channel();
     let notify = move |event: TestEvent| {
         if let TestEvent::TeResult(result) = event {
@@ -61,6 +61,57 @@ pub fn get_default_test_timeout() -> Instant {
     Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S)
 }
 
+/// Parses a test timeout as accepted by `--test-timeout`: an integer followed by
+/// `ms`, `s` or `m`, where a missing unit means seconds.
+///
+/// This is a `const fn` because `#[test_timeout]` is parsed by it as well, while the
+/// test's descriptor is evaluated at compile time.
+pub const fn parse_timeout(s: &str) -> Option<Duration> {
+    let s = s.as_bytes();
+    let mut start = 0;
+    let mut end = s.len();
+    while start < end && s[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while end > start && s[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+
+    let (end, factor) = if end - start >= 2 && s[end - 2] == b'm' && s[end - 1] == b's' {
+        (end - 2, 1)
+    } else if end > start && s[end - 1] == b's' {
+        (end - 1, 1_000)
+    } else if end > start && s[end - 1] == b'm' {
+        (end - 1, 60_000)
+    } else {
+        (end, 1_000)
+    };
+    if start == end {
+        return None;
+    }
+
+    let mut n: u64 = 0;
+    let mut i = start;
+    while i < end {
+        if !s[i].is_ascii_digit() {
+            return None;
+        }
+        n = match n.checked_mul(10) {
+            Some(n) => match n.checked_add((s[i] - b'0') as u64) {
+                Some(n) => n,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+
+    match n.checked_mul(factor) {
+        Some(0) | None => None,
+        Some(millis) => Some(Duration::from_millis(millis)),
+    }
+}
+
 /// The measured execution time of a unit test.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestExecTime(pub Duration);
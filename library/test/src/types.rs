@@ -2,6 +2,7 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::time::Duration;
 
 use super::bench::Bencher;
 use super::options;
@@ -119,10 +120,18 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub ignore_message: Option<&'static str>,
+    pub source_file: &'static str,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
     pub should_panic: options::ShouldPanic,
     pub compile_fail: bool,
     pub no_run: bool,
     pub test_type: TestType,
+    /// Hard limit on the test's run time, set with `#[test_timeout]`. Takes precedence
+    /// over `--test-timeout`.
+    pub timeout: Option<Duration>,
 }
 
 impl TestDesc {
@@ -1057,11 +1057,19 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                     Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
                 },
                 ignore_message: None,
+                // The test descriptions live until rustdoc exits, so leaking the name is fine.
+                source_file: Box::leak(filename.prefer_local().to_string().into_boxed_str()),
+                // Only the line of a doctest is known.
+                start_line: line,
+                start_col: 0,
+                end_line: line,
+                end_col: 0,
                 // compiler failures are test failures
                 should_panic: test::ShouldPanic::No,
                 compile_fail: config.compile_fail,
                 no_run,
                 test_type: test::TestType::DocTest,
+                timeout: None,
             },
             testfn: test::DynTestFn(Box::new(move || {
                 let report_unused_externs = |uext| {
@@ -1047,10 +1047,22 @@ macro_rules! reason {
         name,
         ignore,
         ignore_message,
+        #[cfg(not(bootstrap))]
+        source_file: "",
+        #[cfg(not(bootstrap))]
+        start_line: 0,
+        #[cfg(not(bootstrap))]
+        start_col: 0,
+        #[cfg(not(bootstrap))]
+        end_line: 0,
+        #[cfg(not(bootstrap))]
+        end_col: 0,
         should_panic,
         compile_fail: false,
         no_run: false,
         test_type: test::TestType::Unknown,
+        #[cfg(not(bootstrap))]
+        timeout: None,
     }
 }
 
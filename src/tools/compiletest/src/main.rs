@@ -509,15 +509,37 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
             Ok(val) => &val != "0",
             Err(_) => false,
         },
+        #[cfg(not(bootstrap))]
+        capture: test::Capture::Print,
         color: config.color,
         shuffle: false,
         shuffle_seed: None,
         test_threads: None,
         skip: config.skip.clone(),
+        #[cfg(not(bootstrap))]
+        shard: None,
+        #[cfg(not(bootstrap))]
+        previous_failures: None,
+        #[cfg(not(bootstrap))]
+        failed_first: false,
+        #[cfg(not(bootstrap))]
+        record_failures: None,
+        #[cfg(not(bootstrap))]
+        retries: 0,
+        #[cfg(not(bootstrap))]
+        fail_on_flaky: false,
+        #[cfg(not(bootstrap))]
+        save_baseline: None,
+        #[cfg(not(bootstrap))]
+        baseline: None,
         list: false,
         options: test::Options::new(),
         time_options: None,
         force_run_in_process: false,
+        #[cfg(not(bootstrap))]
+        isolation: test::Isolation::Thread,
+        #[cfg(not(bootstrap))]
+        test_timeout: None,
         fail_fast: std::env::var_os("RUSTC_TEST_FAIL_FAST").is_some(),
     }
 }
@@ -4,7 +4,7 @@
 use ::std::prelude::rust_2015::*;
 #[macro_use]
 extern crate std;
-// compile-flags: --crate-type=lib --test
+// compile-flags: --crate-type=lib --test --remap-path-prefix={{src-base}}/=/the/src/ --remap-path-prefix={{src-base}}\=/the/src/
 // pretty-compare-only
 // pretty-mode:expanded
 // pp-exact:tests-are-sorted.pp
@@ -18,10 +18,16 @@ pub const m_test: test::TestDescAndFn =
             name: test::StaticTestName("m_test"),
             ignore: false,
             ignore_message: ::core::option::Option::None,
+            source_file: "/the/src/tests-are-sorted.rs",
+            start_line: 7usize,
+            start_col: 4usize,
+            end_line: 7usize,
+            end_col: 10usize,
             compile_fail: false,
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(|| test::assert_test_result(m_test())),
     };
@@ -36,10 +42,16 @@ pub const z_test: test::TestDescAndFn =
             name: test::StaticTestName("z_test"),
             ignore: false,
             ignore_message: ::core::option::Option::None,
+            source_file: "/the/src/tests-are-sorted.rs",
+            start_line: 10usize,
+            start_col: 4usize,
+            end_line: 10usize,
+            end_col: 10usize,
             compile_fail: false,
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(|| test::assert_test_result(z_test())),
     };
@@ -54,10 +66,16 @@ pub const a_test: test::TestDescAndFn =
             name: test::StaticTestName("a_test"),
             ignore: false,
             ignore_message: ::core::option::Option::None,
+            source_file: "/the/src/tests-are-sorted.rs",
+            start_line: 13usize,
+            start_col: 4usize,
+            end_line: 13usize,
+            end_col: 10usize,
             compile_fail: false,
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(|| test::assert_test_result(a_test())),
     };
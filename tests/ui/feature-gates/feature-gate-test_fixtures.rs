@@ -0,0 +1,6 @@
+// compile-flags: --test
+
+struct Server;
+
+#[test]
+fn f(_server: Server) {} //~ ERROR fixture arguments of `#[test]` functions are experimental
@@ -0,0 +1,5 @@
+// compile-flags: --test
+
+#[test]
+#[test_timeout = "1s"] //~ ERROR the `#[test_timeout]` attribute is an experimental feature
+fn f() {}
@@ -0,0 +1,56 @@
+// run-pass
+// needs-unwind
+
+// ignore-emscripten no threads support
+
+#![feature(panic_add_hook)]
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+static A: AtomicUsize = AtomicUsize::new(0);
+static B: AtomicUsize = AtomicUsize::new(0);
+static C: AtomicUsize = AtomicUsize::new(0);
+static EVENTS: Mutex<Vec<(String, Option<String>, u32, bool)>> = Mutex::new(Vec::new());
+
+fn main() {
+    panic::set_hook(Box::new(|_| {
+        A.fetch_add(1, Ordering::SeqCst);
+    }));
+    panic::add_hook(Box::new(|event| {
+        B.fetch_add(1, Ordering::SeqCst);
+        let name = event.thread().and_then(|t| t.name()).map(str::to_owned);
+        EVENTS.lock().unwrap().push((
+            event.message().to_owned(),
+            name,
+            event.location().line(),
+            event.is_double_panic(),
+        ));
+    }));
+    let id = panic::add_hook(Box::new(|_| {
+        C.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    let line = line!() + 3;
+    let _ = thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(|| panic!("oh {}", "no"))
+        .unwrap()
+        .join();
+
+    assert!(panic::remove_hook(id));
+    assert!(!panic::remove_hook(id));
+
+    let _ = thread::spawn(|| panic::panic_any(42)).join();
+
+    assert_eq!(2, A.load(Ordering::SeqCst));
+    assert_eq!(2, B.load(Ordering::SeqCst));
+    assert_eq!(1, C.load(Ordering::SeqCst));
+
+    let events = EVENTS.lock().unwrap();
+    assert_eq!(events[0], ("oh no".to_owned(), Some("worker".to_owned()), line, false));
+    assert_eq!(events[1].0, "Box<dyn Any>");
+    assert_eq!(events[1].1, None);
+}
@@ -0,0 +1,30 @@
+// compile-flags: --test
+// run-flags: --test-threads=1 -Zunstable-options --capture=fd
+// run-fail
+// check-run-results
+// exec-env:RUST_BACKTRACE=0
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+// ignore-wasm no subprocess support
+// ignore-emscripten no subprocess support
+// ignore-sgx no subprocess support
+
+#![cfg(test)]
+
+use std::io::{self, Write};
+use std::thread;
+
+#[test]
+fn a_passes() {
+    println!("not shown");
+}
+
+#[test]
+fn b_fails() {
+    // None of these go through the print capture of libtest, so they are only
+    // captured at the file descriptor level, in the order they were written.
+    io::stdout().write_all(b"written to stdout\n").unwrap();
+    io::stderr().write_all(b"written to stderr\n").unwrap();
+    thread::spawn(|| writeln!(io::stdout(), "written by a thread").unwrap()).join().unwrap();
+    assert!(false);
+}
@@ -0,0 +1,29 @@
+// compile-flags: --test
+
+#![feature(test, test_fixtures)]
+
+extern crate test;
+
+use test::Fixture;
+
+struct Counter(usize);
+
+impl Fixture for Counter {
+    fn setup() -> Result<Self, String> {
+        Ok(Counter(0))
+    }
+}
+
+#[test]
+fn borrows_mutably(counter: &mut Counter) {} //~ ERROR suite fixtures can not be borrowed mutably
+
+#[test]
+fn generic<T: Fixture>(t: T) {} //~ ERROR functions used as tests can not be generic
+
+#[test]
+fn impl_trait(t: impl Fixture) {} //~ ERROR fixtures must have a concrete type
+
+#[test]
+#[should_panic]
+fn should_panic(counter: Counter) {}
+//~^ ERROR functions using `#[should_panic]` can not take fixtures
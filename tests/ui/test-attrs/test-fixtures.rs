@@ -0,0 +1,65 @@
+// no-prefer-dynamic
+// compile-flags: --test
+// run-flags: --test-threads=1
+// run-pass
+// check-run-results
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// ignore-wasm32 no support for `Instant`
+
+// Tests that `#[test]` functions can take per-test and suite fixtures.
+
+#![cfg(test)]
+#![feature(test, test_fixtures)]
+
+extern crate test;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use test::Fixture;
+
+static SERVERS_STARTED: AtomicUsize = AtomicUsize::new(0);
+static TEMP_DIRS_LIVE: AtomicUsize = AtomicUsize::new(0);
+
+struct Server {
+    port: u16,
+}
+
+impl Fixture for Server {
+    fn setup() -> Result<Self, String> {
+        SERVERS_STARTED.fetch_add(1, Ordering::SeqCst);
+        Ok(Server { port: 8080 })
+    }
+}
+
+struct TempDir;
+
+impl Fixture for TempDir {
+    fn setup() -> Result<Self, String> {
+        TEMP_DIRS_LIVE.fetch_add(1, Ordering::SeqCst);
+        Ok(TempDir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        TEMP_DIRS_LIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn uses_both(server: &Server, _dir: TempDir) -> Result<(), String> {
+    assert_eq!(server.port, 8080);
+    assert_eq!(TEMP_DIRS_LIVE.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn uses_server(server: &Server) {
+    assert_eq!(server.port, 8080);
+    assert_eq!(SERVERS_STARTED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn uses_temp_dir(_dir: TempDir) {
+    // The temp dir of `uses_both` was dropped when that test returned.
+    assert_eq!(TEMP_DIRS_LIVE.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,40 @@
+// compile-flags: --test
+// run-flags: --test-threads=1 -Zunstable-options --isolate=process
+// run-fail
+// check-run-results
+// exec-env:RUST_BACKTRACE=0
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+// only-unix signals are needed to observe the crash
+// ignore-emscripten no subprocess support
+// ignore-sgx no subprocess support
+
+#![cfg(test)]
+
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn a_sets_global_state() {
+    env::set_var("TEST_ISOLATE_PROCESS", "1");
+    assert_eq!(COUNTER.fetch_add(1, Ordering::SeqCst), 0);
+}
+
+#[test]
+fn b_does_not_see_global_state() {
+    assert!(env::var_os("TEST_ISOLATE_PROCESS").is_none());
+    assert_eq!(COUNTER.fetch_add(1, Ordering::SeqCst), 0);
+}
+
+#[test]
+#[should_panic]
+fn c_panics() {
+    assert_eq!(1 + 1, 4);
+}
+
+#[test]
+fn d_aborts() {
+    std::process::abort();
+}
@@ -0,0 +1,34 @@
+// compile-flags: --test
+
+#![feature(test, test_params)]
+
+extern crate test;
+
+#[test]
+#[test_params(one, 1)]
+#[test_params(one, 2)] //~ ERROR duplicate test case `one`
+fn duplicate(_n: u32) {}
+
+#[test]
+#[test_params(two, 1, 2)] //~ ERROR test case `two` has 2 arguments, but `arity` takes 1
+fn arity(_n: u32) {}
+
+#[test]
+#[test_params("three", 3)] //~ ERROR expected identifier, found `"three"`
+fn unnamed(_n: u32) {}
+
+#[test]
+#[test_params(four 4)] //~ ERROR expected `,`, found `4`
+fn missing_comma(_n: u32) {}
+
+#[bench]
+#[test_params(five, 5)] //~ ERROR `#[test_params]` can only be used on `#[test]` functions
+fn bench(_b: &mut test::Bencher) {}
+
+#[test]
+#[test_params(six, 6)]
+fn generic<T>(_n: u32) {} //~ ERROR functions used as tests can not be generic
+
+#[test]
+#[test_params(seven, 7)] //~ ERROR test case `seven` has 1 argument, but `fixture` takes 2
+fn fixture(_n: u32, _s: String) {}
@@ -0,0 +1,6 @@
+// compile-flags: --test
+
+#![feature(test_params)]
+
+#[test_params(one, 1)] //~ ERROR `#[test_params]` can only be used on `#[test]` functions
+fn not_a_test(_n: u32) {}
@@ -0,0 +1,50 @@
+// no-prefer-dynamic
+// compile-flags: --test
+// run-flags: --test-threads=1
+// run-pass
+// check-run-results
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// ignore-wasm32 no support for `Instant`
+
+// Tests that every `#[test_params]` case of a test function is a test of its own.
+
+#![cfg(test)]
+#![feature(test_params)]
+
+#[test]
+#[test_params(zero, 0, "0")]
+#[test_params(negative, -12, "-12")]
+#[test_params(large, u32::MAX as i64 + 1, "4294967296")]
+fn formats(n: i64, expected: &str) {
+    assert_eq!(n.to_string(), expected);
+}
+
+mod nested {
+    #[test]
+    #[test_params(empty, &[], 0)]
+    #[test_params(three, &[1, 2, 3], 6,)]
+    fn sums(v: &[u32], sum: u32) -> Result<(), String> {
+        if v.iter().sum::<u32>() == sum {
+            Ok(())
+        } else {
+            Err(format!("{v:?} doesn't sum to {sum}"))
+        }
+    }
+}
+
+// The consts generated for the cases don't clash with user items named like them, nor with
+// each other.
+#[allow(dead_code, non_upper_case_globals)]
+const formats__zero: () = ();
+
+#[test]
+#[test_params(_a, 1)]
+fn check(n: u32) {
+    assert_eq!(n, 1);
+}
+
+#[test]
+#[test_params(a, 2)]
+fn check_(n: u32) {
+    assert_eq!(n, 2);
+}
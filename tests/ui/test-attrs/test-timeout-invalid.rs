@@ -0,0 +1,7 @@
+// compile-flags: --test
+
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout = "soon"] //~ ERROR evaluation of constant value failed
+fn f() {}
@@ -0,0 +1,27 @@
+// no-prefer-dynamic
+// compile-flags: --test -Cpanic=abort -Zpanic_abort_tests
+// run-flags: --test-threads=1 -Zunstable-options --test-timeout=60s
+// run-fail
+// check-run-results
+// exec-env:RUST_BACKTRACE=0
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+// ignore-wasm no panic or subprocess support
+// ignore-emscripten no panic or subprocess support
+// ignore-sgx no subprocess support
+
+#![cfg(test)]
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout = "100ms"]
+fn it_hangs() {
+    loop {
+        std::thread::park();
+    }
+}
+
+#[test]
+fn it_works() {
+    assert_eq!(1 + 1, 2);
+}